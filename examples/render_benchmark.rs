@@ -0,0 +1,56 @@
+use surreal::prelude::*;
+use surreal::render::offscreen::OffscreenRenderer;
+
+const ROWS: usize = 25;
+const COLUMNS: usize = 20;
+const FRAMES: u32 = 200;
+
+// Measures the average time taken to render a view with hundreds of widgets on the GPU.
+// Run with `cargo run --release --example render_benchmark`.
+pub fn main() {
+    let rows = (0..ROWS).map(|_| {
+        let buttons = (0..COLUMNS).map(|_| {
+            Button::new("")
+                .text(Text::new("").text("0"))
+                .into_element()
+        })
+        .collect();
+
+        Stack::new(Orientation::Horizontal, buttons).into_element()
+    })
+    .collect();
+
+    let mut view: Stack<(), ()> = Stack::new(Orientation::Vertical, rows);
+
+    let fonts = include_fonts! {
+        default => "../res/JetBrainsMono/JetBrainsMono-Medium.ttf",
+    };
+
+    let images = include_images! {
+        plus => "../res/images/plus_thing.png",
+    };
+
+    let mut offscreen = match OffscreenRenderer::try_new(fonts, images) {
+        Some(offscreen) => offscreen,
+        None => {
+            println!("No graphics adapter was found");
+            return;
+        }
+    };
+
+    let dimensions = (1600, 1200);
+
+    // The first frames create pipelines and caches
+    for _ in 0..10 {
+        offscreen.render_to_image(&mut view, &DEFAULT_THEME, dimensions);
+    }
+
+    let start = std::time::Instant::now();
+    for _ in 0..FRAMES {
+        offscreen.render_to_image(&mut view, &DEFAULT_THEME, dimensions);
+    }
+    let elapsed = start.elapsed();
+
+    // Includes layout and reading the image back from the GPU, which are the same for every frame
+    println!("Average frame time: {:.3}ms over {} frames ({} buttons)", elapsed.as_secs_f64() * 1000.0 / FRAMES as f64, FRAMES, ROWS * COLUMNS);
+}
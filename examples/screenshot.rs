@@ -0,0 +1,47 @@
+use surreal::prelude::*;
+use surreal::render::{Renderer, software::render_view_to_image};
+
+// Renders a view to `screenshot.png` without a GPU or window
+pub fn main() {
+    let mut view: Stack<(), _> = Stateful! {
+        @State {
+            counter: i32 = 0,
+        },
+
+        VStack! {
+            Text::new("title")
+                .text("Rendered on the CPU"),
+
+            HStack! {
+                Button::new("button")
+                    .text(Text::new("")
+                        .text("Button")
+                        .color(Color::BLACK)
+                    ),
+
+                CircleButton::new("circle_button")
+                    .image(Image::new("")
+                        .resource("plus")
+                    )
+                    .radius(40)
+                    .color(Color::LIGHT_GRAY),
+            },
+        }
+        .alignment(Alignment::Center),
+    };
+
+    let fonts = include_fonts! {
+        default => "../res/JetBrainsMono/JetBrainsMono-Medium.ttf",
+    };
+
+    let images = include_images! {
+        plus => "../res/images/plus_thing.png",
+    };
+
+    let mut renderer = Renderer::headless(fonts, images);
+
+    let screenshot = render_view_to_image(&mut view, &mut renderer, &DEFAULT_THEME, (600, 300));
+    screenshot.save("screenshot.png").unwrap();
+
+    println!("Saved screenshot.png");
+}
@@ -163,7 +163,7 @@ pub fn main() {
     };
 
     // NOTE: Future: Can define view via DSL. Hook can then be used to implement the view
-    view.set_hook(|view, message| {
+    view.set_hook(Box::new(|view, message| {
         // Two ways to get widgets
 
         GetWidget!(view.more_text as Text)
@@ -173,8 +173,7 @@ pub fn main() {
 
         match message {
             Message::AppendButton => {
-                println!("Appending a button");
-                view.append(
+                let result = view.append(
                     Button::new("_appended")
                         .text(Text::new("")
                             .text("Appended")
@@ -182,7 +181,12 @@ pub fn main() {
                         )
                         .color(Color::new(0.5, 0.2, 0.2, 1.0))
                         .into_element()
-                )
+                );
+
+                match result {
+                    Ok(()) => println!("Appending a button"),
+                    Err(error) => println!("{}", error),
+                }
             }
 
             Message::DeleteAppended => {
@@ -195,7 +199,7 @@ pub fn main() {
 
             _ => {}
         }
-    });
+    }));
 
     // Resources are embeded by default. They can be loaded from disk instead
     // if the feature "embed-resources" is disabled
@@ -20,6 +20,7 @@ use quote::{quote, ToTokens};
 
 use syn::parse::{Parse, ParseStream, Result};
 use syn::{parse_macro_input, DeriveInput, Error, Ident, Token, braced, Type, Expr};
+use syn::visit_mut::{self, VisitMut};
 
 /////////////////////////////////////////////////////////////////////////////
 // Stateful Macro
@@ -32,22 +33,50 @@ struct State {
     pub view: Expr,
 }
 
+/// Name of the struct generated to hold the state's fields.
+/// Each `Stateful!` defines its own struct within its own block.
+fn fields_struct() -> Ident {
+    Ident::new("__StatefulFields", proc_macro2::Span::call_site())
+}
+
+/// Name of the function generated to tell the compiler that a closure's `state` holds the generated struct.
+/// Widgets only learn their state's type once they are part of the view, which is too late for `state.fields.x`.
+fn pin_function() -> Ident {
+    Ident::new("pin_state", proc_macro2::Span::call_site())
+}
+
 impl ToTokens for State {
-    // This only turns `self.fields` into tokens, ignoring view
+    // This only turns `self.fields` into tokens (defining the fields struct and creating the state), ignoring view
     fn to_tokens(&self, tokens: &mut syn::export::TokenStream2) {
         let mut state_macro_body = syn::export::TokenStream2::new();
+        let mut fields_init = syn::export::TokenStream2::new();
         for (field_name, field_type, init_expr) in &self.fields {
             let current = quote! {
                 #field_name: #field_type = #init_expr,
             };
             current.to_tokens(&mut state_macro_body);
+
+            let init = quote! {
+                #field_name: #init_expr,
+            };
+            init.to_tokens(&mut fields_init);
         }
-        
-        // let view = &self.view;
+
+        let fields = fields_struct();
+        let pin = pin_function();
         let t = quote! {
             State! {
+                struct #fields;
                 #state_macro_body
-            };
+            }
+
+            impl #fields {
+                fn #pin<D: std::ops::Deref<Target = state::State<#fields>>>(_state: &D) {}
+            }
+
+            let mut state = state::State::new(#fields {
+                #fields_init
+            });
         };
 
         t.to_tokens(tokens);
@@ -140,13 +169,17 @@ fn apply_recursive_macro(state: &State, tokens: syn::export::TokenStream2, macro
 
 // 1. Traverse token stream
 // 2. Check for `@state_field`
-// 3. Replace this with `*state.get::<field_type>("state_field")`
+// 3. Replace this with `state.fields.state_field`
+//    (unknown fields and mismatched types are compile errors rather than runtime panics)
 // FIXME: Dealing with `@`s here gets ugly
 fn format_closures(state: &State, input: syn::export::TokenStream2) -> syn::export::TokenStream2 {
     let mut has_marker: bool = false;
     let mut marker_iter = 0;
     let mut iter = 0;
     let mut should_give_back_at_sign = false;
+    // `name @ pattern` bindings follow a name, while `@field` follows an operator or keyword (such as `if @field`)
+    let mut follows_name = false;
+    let mut is_binding = false;
 
     apply_recursive_macro(state, input, format_closures).map(|tree| {
         iter += 1;
@@ -154,10 +187,15 @@ fn format_closures(state: &State, input: syn::export::TokenStream2) -> syn::expo
             has_marker = false;
         }
 
+        // Keywords aren't parsed as an `Ident`
+        let is_name = matches!(&tree, TokenTree::Ident(ident) if syn::parse2::<Ident>(ident.to_token_stream()).is_ok());
+        let previous_is_name = std::mem::replace(&mut follows_name, is_name);
+
         if let TokenTree::Punct(punct) = &tree {
             if punct.as_char() == '@' {
                 has_marker = true;
                 should_give_back_at_sign = true;
+                is_binding = previous_is_name;
                 marker_iter = iter;
                 let field = quote! {
                 };
@@ -167,12 +205,17 @@ fn format_closures(state: &State, input: syn::export::TokenStream2) -> syn::expo
 
         if let TokenTree::Ident(ident) = &tree {
             if has_marker && iter == marker_iter + 1 {
-                if let Some((ident, ty)) = state.map.get(&ident.to_string()) {
+                if let Some((ident, _ty)) = state.map.get(&ident.to_string()) {
                     let field = quote! {
-                        *state.get::<#ty>(stringify!(#ident))
+                        state.fields.#ident
                     };
                     should_give_back_at_sign = false;
                     return field.into_token_stream();
+                } else if !is_binding {
+                    should_give_back_at_sign = false;
+                    // Within parentheses, so the rest of the expression still parses
+                    let error = Error::new(ident.span(), format!("No such state field: `{}`", ident)).to_compile_error();
+                    return quote! { (#error) };
                 }
             }
         }
@@ -180,8 +223,8 @@ fn format_closures(state: &State, input: syn::export::TokenStream2) -> syn::expo
         if should_give_back_at_sign {
             should_give_back_at_sign = false;
             let at = proc_macro2::Punct::new('@', proc_macro2::Spacing::Alone);
-            let mut tokens = tree.into_token_stream();
-            at.to_tokens(&mut tokens);
+            let mut tokens = at.into_token_stream();
+            tree.to_tokens(&mut tokens);
             return tokens;
         }
 
@@ -189,17 +232,124 @@ fn format_closures(state: &State, input: syn::export::TokenStream2) -> syn::expo
     }).collect()
 }
 
+/// Gives closures taking `state` (such as `|mut state| ...` or `move |_, state: RefMut<_>| ...`) `__StatefulFields::pin_state(&state);`
+/// as their first statement. This is the only way the compiler can tell which struct `state.fields` is
+/// before the widget is added to the view (and the closure's type is inferred).
+struct PinClosureState;
+
+impl PinClosureState {
+    fn statement() -> syn::Stmt {
+        let fields = fields_struct();
+        let pin = pin_function();
+        syn::parse_quote! { #fields::#pin(&state); }
+    }
+
+    /// Whether the pattern binds `state` (such as `state`, `mut state` or `state: RefMut<_>`)
+    fn binds_state(pat: &syn::Pat) -> bool {
+        match pat {
+            syn::Pat::Ident(pat) => pat.ident == "state" || pat.subpat.as_ref().map_or(false, |(_, pat)| Self::binds_state(pat)),
+            syn::Pat::Type(pat) => Self::binds_state(&pat.pat),
+            syn::Pat::Reference(pat) => Self::binds_state(&pat.pat),
+            syn::Pat::Tuple(pat) => pat.elems.iter().any(Self::binds_state),
+            _ => false,
+        }
+    }
+
+    /// Pins the closures within a macro's tokens.
+    /// Macros such as `VStack!` aren't made of expressions, so expressions are picked out of their tokens wherever possible.
+    /// `if |state| condition { ... }` in the stack macros gets its condition pinned (in parentheses, since the
+    /// condition ends at the first block).
+    fn macro_tokens(&mut self, input: ParseStream) -> Result<syn::export::TokenStream2> {
+        use syn::parse::discouraged::Speculative;
+
+        let mut output = syn::export::TokenStream2::new();
+
+        while !input.is_empty() {
+            if input.peek(Token![if]) && input.peek2(Token![|]) {
+                let fork = input.fork();
+                fork.parse::<Token![if]>()?;
+                fork.parse::<Token![|]>()?;
+                let param: Ident = fork.parse()?;
+                fork.parse::<Token![|]>()?;
+
+                // Up to the `then` block
+                let mut condition = syn::export::TokenStream2::new();
+                while !fork.is_empty() && !fork.peek(syn::token::Brace) {
+                    condition.extend(std::iter::once(fork.parse::<TokenTree>()?));
+                }
+                input.advance_to(&fork);
+
+                let condition = syn::parse::Parser::parse2(|input: ParseStream| self.macro_tokens(input), condition)?;
+                let condition = if param == "state" {
+                    let pin = Self::statement();
+                    quote! { ({ #pin #condition }) }
+                } else {
+                    condition
+                };
+
+                output.extend(quote! { if |#param| #condition });
+                continue;
+            }
+
+            let fork = input.fork();
+            if let Ok(mut expr) = fork.parse::<Expr>() {
+                input.advance_to(&fork);
+                self.visit_expr_mut(&mut expr);
+                expr.to_tokens(&mut output);
+                continue;
+            }
+
+            match input.parse::<TokenTree>()? {
+                TokenTree::Group(group) => {
+                    let stream = syn::parse::Parser::parse2(|input: ParseStream| self.macro_tokens(input), group.stream())?;
+                    let mut new_group = proc_macro2::Group::new(group.delimiter(), stream);
+                    new_group.set_span(group.span());
+                    new_group.to_tokens(&mut output);
+                }
+                tree => tree.to_tokens(&mut output),
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+impl VisitMut for PinClosureState {
+    fn visit_expr_closure_mut(&mut self, closure: &mut syn::ExprClosure) {
+        visit_mut::visit_expr_closure_mut(self, closure);
+
+        if !closure.inputs.iter().any(Self::binds_state) {
+            return;
+        }
+
+        let pin = Self::statement();
+        match &mut *closure.body {
+            Expr::Block(body) if body.label.is_none() => body.block.stmts.insert(0, pin),
+            body => *body = syn::parse_quote! { { #pin #body } },
+        }
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut syn::Macro) {
+        let tokens = std::mem::take(&mut mac.tokens);
+        mac.tokens = match syn::parse::Parser::parse2(|input: ParseStream| self.macro_tokens(input), tokens.clone()) {
+            Ok(tokens) => tokens,
+            // Left to the macro to report
+            Err(_) => tokens,
+        };
+    }
+}
+
 /// Adds state to a view.
 /// State is declared using the following syntax:
 ///
-/// ```
+/// ```ignore
 /// @State {
 ///     field_name: type = initial_expression,
 ///     ...
 /// }
 /// ```
 /// State can then be used in callbacks like so:
-/// ```
+/// ```ignore
 /// Widget::new("id")
 ///     .callback_function(|mut state| {
 ///         @field_name += 12;
@@ -207,17 +357,24 @@ fn format_closures(state: &State, input: syn::export::TokenStream2) -> syn::expo
 ///     })
 /// ```
 /// Note that that `state` **must** be named `state` and nothing else
+///
+/// The fields are stored in a struct generated by the macro, so `@field_name` is a plain field access
+/// and a misspelled field or a value of the wrong type is a compile error.
 #[allow(non_snake_case)]
 #[proc_macro]
 pub fn Stateful(input: TokenStream) -> TokenStream {
     let state = parse_macro_input!(input as State);
 
-    let formatted_view = format_closures(&state, state.view.to_token_stream());
+    let mut formatted_view: Expr = match syn::parse2(format_closures(&state, state.view.to_token_stream())) {
+        Ok(view) => view,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    PinClosureState.visit_expr_mut(&mut formatted_view);
     // println!("View: {}", formatted_view);
     
     let state_expansion = quote! {
         {
-            let mut state = #state;
+            #state
             let mut view = #formatted_view;
             view.assign_state(std::rc::Rc::new(std::cell::RefCell::new(state)));
             view
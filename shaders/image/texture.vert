@@ -1,15 +1,26 @@
 #version 450
 
-// In
-layout(location = 0) in vec3 attr_position;
-layout(location = 1) in vec2 attr_tex_coord;
+// Corner of the unit quad (0..1, down is +y)
+layout(location = 0) in vec2 vertex_position;
+
+// NOTE: These must be matched with `TextureInstance`
+layout(location = 1) in vec2 instance_top_left;
+layout(location = 2) in vec2 instance_size;
+
+// Set 0 is the texture
+layout(set = 1, binding = 0)
+uniform Globals {
+    vec2 window_dimensions;
+};
 
 // Out
 layout(location = 0) out vec2 vert_tex_coord;
 
 void main() {
     // Interpolate texture coordinates
-    vert_tex_coord = attr_tex_coord;
+    vert_tex_coord = vec2(vertex_position.x, 1.0 - vertex_position.y);
 
-    gl_Position = vec4(attr_position, 1.0);
-}
\ No newline at end of file
+    // Pixel coordinates -> draw space (where up is +y)
+    vec2 position = (instance_top_left + vertex_position * instance_size) / window_dimensions * 2.0 - 1.0;
+    gl_Position = vec4(position.x, -position.y, 0.0, 1.0);
+}
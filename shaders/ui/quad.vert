@@ -1,7 +1,37 @@
 #version 450
 
-layout(location = 0) in vec3 vertex_position;
+// Corner of the unit quad (0..1, down is +y)
+layout(location = 0) in vec2 vertex_position;
+
+// NOTE: These must be matched with `QuadInstance`
+layout(location = 1) in vec2 instance_top_left;
+layout(location = 2) in vec2 instance_size;
+layout(location = 3) in vec4 instance_color;
+layout(location = 4) in uint instance_primitive_type;
+layout(location = 5) in float instance_roundness;
+
+layout(set = 0, binding = 0)
+uniform Globals {
+    vec2 window_dimensions;
+};
+
+// Constant for the entire quad
+layout(location = 0) flat out vec4 vert_color;
+layout(location = 1) flat out uint vert_primitive_type;
+layout(location = 2) flat out vec2 vert_center;
+layout(location = 3) flat out vec2 vert_half_size;
+layout(location = 4) flat out float vert_roundness;
 
 void main() {
-    gl_Position = vec4(vertex_position, 1.0);
-}
\ No newline at end of file
+    vec2 half_size = instance_size / 2.0;
+
+    vert_color = instance_color;
+    vert_primitive_type = instance_primitive_type;
+    vert_center = instance_top_left + half_size;
+    vert_half_size = half_size;
+    vert_roundness = instance_roundness;
+
+    // Pixel coordinates -> draw space (where up is +y)
+    vec2 position = (instance_top_left + vertex_position * instance_size) / window_dimensions * 2.0 - 1.0;
+    gl_Position = vec4(position.x, -position.y, 0.0, 1.0);
+}
@@ -1,4 +1,4 @@
-use crate::view::View;
+use crate::view::{View, BoxConstraints};
 
 use winit::{
     event::{Event, WindowEvent},
@@ -7,7 +7,10 @@ use winit::{
     dpi::PhysicalSize,
 };
 
-use wgpu::*;
+use wgpu::{
+    Instance, BackendBit, RequestAdapterOptions, PowerPreference, DeviceDescriptor, Features, Limits,
+    Surface, Adapter, Device, Queue, SwapChain, SwapChainDescriptor, PresentMode, TextureUsage,
+};
 
 // Ignore Results (remove `unused Result` warning)
 trait Ignore {
@@ -84,6 +87,9 @@ pub struct Application {
     is_resizable: bool,
     // allows_scrollbars: bool,
     is_minimized: bool,
+
+    /// Id of the widget which receives keyboard focus
+    focused_widget: Option<&'static str>,
 }
 
 impl Application {
@@ -126,9 +132,16 @@ impl Application {
             is_resizable: settings.resizable,
             // allows_scrollbars: settings.allow_scrollbars,
             is_minimized: false,
+
+            focused_widget: None,
         }
     }
 
+    /// Id of the widget which currently has keyboard focus
+    pub fn focused_widget(&self) -> Option<&'static str> {
+        self.focused_widget
+    }
+
     fn resize_swap_chain(&mut self, width: u32, height: u32) {
         self.gpu.sc_desc.width = width;
         self.gpu.sc_desc.height = height;
@@ -137,7 +150,7 @@ impl Application {
     }
 
     /// Runs the application with the given view. Targets web if `target_arch` is wasm32
-    pub fn run<Msg: crate::EmptyMessage + 'static>(&mut self, view: &mut dyn View<Msg>) {
+    pub fn run<Msg: crate::EmptyMessage + 'static, S: 'static>(&mut self, view: &mut dyn View<Msg, S>) {
         #[cfg(not(target_arch = "wasm32"))]
         self.run_(view);
 
@@ -162,41 +175,48 @@ impl Application {
         }
     }
 
-    fn run_<Msg: crate::EmptyMessage + 'static>(&mut self, view: &mut dyn View<Msg>) {
+    fn run_<Msg: crate::EmptyMessage + 'static, S: 'static>(&mut self, view: &mut dyn View<Msg, S>) {
         // FIXME: winit requires static lifetimes, but these aren't static.
         //        I can't take ownership of `self` here for the same reason (static closure requires `move`)
         //        Furthermore, I can't use `run_return` because this needs to work on the web too
         let this = unsafe { std::mem::transmute::<_, &'static mut Application>(self) };
-        let view = unsafe { std::mem::transmute::<_, &'static mut dyn View<Msg>>(view) };
+        let view = unsafe { std::mem::transmute::<_, &'static mut dyn View<Msg, S>>(view) };
         
         let mut message_queue = crate::MessageQueue::new();
 
         view._init(&mut this.renderer, &this.global_theme, true);
-        view.layout(&mut this.renderer, &this.global_theme, (this.gpu.sc_desc.width, this.gpu.sc_desc.height), true);
-        
+        // After initializing, so elements created by views (such as `ForEach`) are checked as well
+        if let Err(error) = crate::view::validate_ids(view) {
+            panic!("{}", error);
+        }
+
         {
-            let (width, height) = view.render_size();
+            // Size the view would like to be within the window
+            let window_size = (this.gpu.sc_desc.width, this.gpu.sc_desc.height);
+            let (width, height) = view.measure(&mut this.renderer, &this.global_theme, BoxConstraints::loose(window_size), true);
             
             // TODO: Account for when the view changes
             if this.fit_window_to_view {
                 println!("Resizing window to view dimensions: {}x{}", width, height);
                 this.window_system.window.set_inner_size(winit::dpi::LogicalSize::new(width, height));
                 this.resize_swap_chain(width, height);
+            } else if (width, height) != window_size {
+                // Fill the window (the view was only measured at the size of its contents)
+                view.measure(&mut this.renderer, &this.global_theme, BoxConstraints::tight(window_size), true);
             }
 
+            view.arrange(0, 0);
+
             // FIXME: This needs to be updated when views become dynamic
             if this.is_resizable /* && !this.allows_scrollbars */ {
                 this.window_system.window.set_min_inner_size(Some(winit::dpi::LogicalSize::new(width, height)));
             }
         }
 
-        // FIXME: Window dimensions depend on the view size, but view size depends on window dimensions, so this happens twice
-        view.layout(&mut this.renderer, &this.global_theme, (this.gpu.sc_desc.width, this.gpu.sc_desc.height), true);
-
         this.timer.start();
 
         #[cfg(feature = "frame-time")]
-        let mut frame_time_accumulator: u128 = 0;
+        let mut frame_time_accumulator = std::time::Duration::from_secs(0);
         #[cfg(feature = "frame-time")]
         let mut num_frames: u64 = 0;
         
@@ -205,6 +225,7 @@ impl Application {
         let mut should_resize = false;
 
         let mut mouse_position: (i32, i32) = (0, 0);
+        let mut modifiers = crate::event::Modifiers::default();
 
         let event_loop = this.window_system.event_loop.take().unwrap();
 
@@ -221,7 +242,10 @@ impl Application {
                 // Window close
                 Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
                     #[cfg(feature = "frame-time")]
-                    println!("Average frame time: {}ms", frame_time_accumulator as f64 / num_frames as f64);
+                    if num_frames > 0 {
+                        // Sub-millisecond precision (batched frames are typically faster than 1ms)
+                        println!("Average frame time: {:.3}ms over {} frames", frame_time_accumulator.as_secs_f64() * 1000.0 / num_frames as f64, num_frames);
+                    }
                     
                     println!("Exiting main loop...");
                     *control_flow = ControlFlow::Exit;
@@ -336,6 +360,22 @@ impl Application {
                     };
                 }
 
+                // Scroll wheel & trackpad
+                Event::WindowEvent { event: WindowEvent::MouseWheel { delta, .. }, .. } => {
+                    let delta = match delta {
+                        winit::event::MouseScrollDelta::LineDelta(x, y) => crate::event::ScrollDelta::Lines { x, y },
+                        winit::event::MouseScrollDelta::PixelDelta(position) => {
+                            let logical = position.to_logical::<f64>(this.window_system.window.scale_factor());
+                            crate::event::ScrollDelta::Pixels { x: logical.x as f32, y: logical.y as f32 }
+                        }
+                    };
+
+                    application_event = crate::event::ApplicationEvent::MouseWheel {
+                        delta,
+                        position: mouse_position,
+                    };
+                }
+
                 // Draw to window
                 Event::RedrawRequested(_) => {
                     // Time since last frame
@@ -346,7 +386,12 @@ impl Application {
         
                     if should_resize {
                         view._init(&mut this.renderer, &this.global_theme, false);
-                        view.layout(&mut this.renderer, &this.global_theme, (this.gpu.sc_desc.width, this.gpu.sc_desc.height), true);
+                        // Elements created since (such as by a `ForEach`) may reuse the ids of other widgets
+                        if let Err(error) = crate::view::validate_ids(view) {
+                            panic!("{}", error);
+                        }
+                        view.measure(&mut this.renderer, &this.global_theme, BoxConstraints::tight((this.gpu.sc_desc.width, this.gpu.sc_desc.height)), true);
+                        view.arrange(0, 0);
                         // render the updated view
                         should_render = true;
                     }
@@ -356,11 +401,19 @@ impl Application {
                         this.render_view(view);
                         should_render = false;
                         should_resize = false;
+
+                        // Only frames which were actually rendered are measured
+                        #[cfg(feature = "frame-time")] {
+                            frame_time_accumulator += start.elapsed();
+                            num_frames += 1;
+                        }
                     }
+                }
 
-                    #[cfg(feature = "frame-time")] {
-                        frame_time_accumulator += start.elapsed().as_millis();
-                        num_frames += 1;
+                // Keyboard and text input
+                Event::WindowEvent { event: ref window_event, .. } => {
+                    if let Some(event) = keyboard_event(window_event, &mut modifiers) {
+                        application_event = event;
                     }
                 }
 
@@ -369,13 +422,17 @@ impl Application {
                 }
             } // match event
 
+            // Keyboard focus (before widgets handle the event, so that a click can interact with the newly focused widget)
+            should_render |= crate::view::handle_focus_event(view, &mut this.focused_widget, &application_event);
+
             if !application_event.is_none() {
                 should_render |= view.propogate_event(&application_event, &mut message_queue);
+                // Widgets may change size while handling events (e.g. text input)
+                should_resize |= view.check_if_should_resize();
                 
                 // TODO: Should this happen only once during RedrawRequested?
                 for message in message_queue.drain() {
-                    // FIXME: I can't make `call_hook` part of `View`
-                    crate::view::call_hook(view, &message);
+                    crate::view::call_hooks(view, &message);
                     // If view resized, render the view
                     should_resize |= view.propogate_message(&message);
                 }
@@ -391,53 +448,61 @@ impl Application {
         });        
     }
 
-    fn render_view<Msg: crate::EmptyMessage + 'static>(&mut self, view: &mut dyn View<Msg>) {
+    fn render_view<Msg: crate::EmptyMessage + 'static, S>(&mut self, view: &mut dyn View<Msg, S>) {
         let frame = self.gpu.swap_chain.get_current_frame().unwrap();
-                
-        let mut encoder = self.gpu.device.create_command_encoder(&CommandEncoderDescriptor {
-            label: Some("render_encoder"),
-        });
-
-        // Initial frame clear
-        let _ = encoder.begin_render_pass(&RenderPassDescriptor {
-            color_attachments: &[
-                RenderPassColorAttachmentDescriptor {
-                    attachment: &frame.output.view,
-                    resolve_target: None,
-                    ops: Operations {
-                        load: LoadOp::Clear(self.global_theme.colors.background.into()),
-                        store: true,
-                    },
-                },
-            ],
-            depth_stencil_attachment: None,
-        });
-
-        // Bundle the renderer and context for user-side simplicity
-        let mut renderer_with_context = crate::render::ContextualRenderer {
-            renderer: &mut self.renderer,
-            device: &self.gpu.device,
-            queue: &self.gpu.queue,
-            target: &frame.output.view,
-            encoder: &mut encoder,
-            window_dimensions: (self.gpu.sc_desc.width, self.gpu.sc_desc.height),
-        };
 
-        // Render the entire view
-        view.render(&mut renderer_with_context, &self.global_theme);
+        self.renderer.render_view(
+            &self.gpu.device, 
+            &self.gpu.queue, 
+            &frame.output.view, 
+            (self.gpu.sc_desc.width, self.gpu.sc_desc.height), 
+            view, 
+            &self.global_theme
+        );
+    }
+}
+
+
+/// Maps winit's keyboard events to an `ApplicationEvent`, keeping track of the modifier keys held down.
+///
+/// Returns `None` for events which aren't passed on to the view: changes of modifiers, keys without a
+/// virtual key code, and control characters (such as backspace, which is handled as a `Key`).
+fn keyboard_event(event: &WindowEvent, modifiers: &mut crate::event::Modifiers) -> Option<crate::event::ApplicationEvent> {
+    match event {
+        WindowEvent::ModifiersChanged(state) => {
+            *modifiers = crate::event::Modifiers {
+                shift: state.shift(),
+                ctrl: state.ctrl(),
+                alt: state.alt(),
+                logo: state.logo(),
+            };
+
+            None
+        }
+
+        WindowEvent::KeyboardInput { input, .. } => {
+            // Keys without a virtual key code can't be represented
+            let key = input.virtual_keycode?;
+            let state = match input.state {
+                winit::event::ElementState::Pressed => crate::event::ButtonState::Pressed,
+                winit::event::ElementState::Released => crate::event::ButtonState::Released,
+            };
+
+            Some(crate::event::ApplicationEvent::Key {
+                key,
+                state,
+                modifiers: *modifiers,
+            })
+        }
 
-        // This function should be called only once per frame
-        // This is so wgpu_glyph can cache the text, meaning this call should not be made inside `View`
-        // Using individual draw calls per `Section` raises CPU usage from <1% to >5% (>22% in debug build)
-        // NOTE: Placing this here satisfies the above, but sacrifices layering/ordering (see GitHub card)
-        self.renderer.text_renderer.render_queue(&self.gpu.device, &frame.output.view, &mut encoder, self.gpu.sc_desc.width, self.gpu.sc_desc.height);
+        WindowEvent::ReceivedCharacter(character) if !character.is_control() => {
+            Some(crate::event::ApplicationEvent::Character(*character))
+        }
 
-        // Does everything requested by the ContextualRenderer
-        self.gpu.queue.submit(Some(encoder.finish()));
+        _ => None,
     }
 }
 
-
 async fn init_wgpu(window: &winit::window::Window, use_vsync: bool) -> GraphicsDevice {
     let (width, height) = window.inner_size().into();
 
@@ -485,4 +550,71 @@ async fn init_wgpu(window: &winit::window::Window, use_vsync: bool) -> GraphicsD
         sc_desc,
         swap_chain,
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{ApplicationEvent, ButtonState, Key, Modifiers};
+    use winit::event::{ElementState, KeyboardInput, ModifiersState};
+
+    fn keyboard_input(key: Option<Key>, state: ElementState) -> WindowEvent<'static> {
+        #[allow(deprecated)]
+        WindowEvent::KeyboardInput {
+            device_id: unsafe { winit::event::DeviceId::dummy() },
+            input: KeyboardInput { scancode: 0, state, virtual_keycode: key, modifiers: ModifiersState::empty() },
+            is_synthetic: false,
+        }
+    }
+
+    #[test]
+    fn keys_carry_the_modifiers_held_down() {
+        let mut modifiers = Modifiers::default();
+
+        assert!(keyboard_event(&WindowEvent::ModifiersChanged(ModifiersState::SHIFT | ModifiersState::CTRL), &mut modifiers).is_none());
+        assert_eq!(modifiers, Modifiers { shift: true, ctrl: true, alt: false, logo: false });
+
+        match keyboard_event(&keyboard_input(Some(Key::Tab), ElementState::Pressed), &mut modifiers) {
+            Some(ApplicationEvent::Key { key: Key::Tab, state: ButtonState::Pressed, modifiers }) => {
+                assert_eq!(modifiers, Modifiers { shift: true, ctrl: true, alt: false, logo: false });
+            }
+            _ => panic!("Expected a key press"),
+        }
+
+        // Releasing the modifiers
+        keyboard_event(&WindowEvent::ModifiersChanged(ModifiersState::LOGO), &mut modifiers);
+        match keyboard_event(&keyboard_input(Some(Key::A), ElementState::Released), &mut modifiers) {
+            Some(ApplicationEvent::Key { key: Key::A, state: ButtonState::Released, modifiers }) => {
+                assert_eq!(modifiers, Modifiers { logo: true, ..Modifiers::default() });
+            }
+            _ => panic!("Expected a key release"),
+        }
+    }
+
+    #[test]
+    fn unknown_keys_and_other_events_are_not_mapped() {
+        let mut modifiers = Modifiers::default();
+
+        assert!(keyboard_event(&keyboard_input(None, ElementState::Pressed), &mut modifiers).is_none());
+        assert!(keyboard_event(&WindowEvent::Focused(true), &mut modifiers).is_none());
+        assert!(keyboard_event(&WindowEvent::CloseRequested, &mut modifiers).is_none());
+        assert_eq!(modifiers, Modifiers::default());
+    }
+
+    #[test]
+    fn only_printable_characters_are_text_input() {
+        let mut modifiers = Modifiers::default();
+
+        for character in &['a', 'É', ' ', '\u{1F600}'] {
+            match keyboard_event(&WindowEvent::ReceivedCharacter(*character), &mut modifiers) {
+                Some(ApplicationEvent::Character(received)) => assert_eq!(received, *character),
+                _ => panic!("Expected {:?} to be text input", character),
+            }
+        }
+
+        // Backspace, tab, enter, escape and delete arrive as keys instead
+        for character in &['\u{8}', '\t', '\r', '\u{1b}', '\u{7f}'] {
+            assert!(keyboard_event(&WindowEvent::ReceivedCharacter(*character), &mut modifiers).is_none());
+        }
+    }
+}
@@ -1,4 +1,5 @@
 /// Defines the layout-bounds for view elements
+#[derive(Copy, Clone)]
 pub struct BoundingRect {
     /// Top-left x coord
     pub x: i32,
@@ -0,0 +1,149 @@
+use crate::bounding_rect::BoundingRect;
+use crate::view::Flex;
+use crate::view_element::*;
+
+use crate::widget::Widget;
+
+/// Line separating the elements of a stack.
+///
+/// Horizontal in a `VStack` and vertical in an `HStack`, spanning the width (or height) of the stack.
+/// Outside of stacks, dividers are horizontal.
+pub struct Divider<Msg, S> {
+    thickness: Option<u32>,
+    color: Option<crate::Color>,
+    // Negative -> unset
+    roundness: f32,
+    orientation: crate::Orientation,
+    bounds: BoundingRect,
+
+    should_resize: bool,
+    _phantom_marker: std::marker::PhantomData<(Msg, S)>,
+}
+
+impl<Msg, S> Divider<Msg, S> {
+    pub fn new() -> Self {
+        Self {
+            thickness: None,
+            color: None,
+            roundness: -1.0,
+            orientation: crate::Orientation::Vertical,
+            bounds: BoundingRect::new(),
+            should_resize: false,
+            _phantom_marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Width of the line in pixels. Defaults to the theme's divider thickness.
+    pub fn thickness(mut self, thickness: u32) -> Self {
+        self.thickness = Some(thickness);
+        self
+    }
+
+    pub fn color(mut self, color: crate::Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Roundness of the ends (0 -> solid bar)
+    pub fn roundness(mut self, roundness: f32) -> Self {
+        if roundness < 0.0 || roundness > 100.0 {
+            panic!("Roundness must be between 0 and 100 (percent). Divider got `{}`", roundness);
+        }
+
+        self.roundness = roundness;
+        self
+    }
+}
+
+/// Dividers are stretched across the stack, but never grow or shrink along it
+const DIVIDER_FLEX: Flex = Flex {
+    grow: 0.0,
+    shrink: 0.0,
+    stretch: true,
+    min_size: (0, 0),
+    max_size: (u32::MAX, u32::MAX),
+};
+
+impl<Msg: EmptyMessage, S> Widget<Msg, S> for Divider<Msg, S> where Msg: 'static, S: 'static {
+    fn id(&self) -> &'static str {
+        ""
+    }
+
+    fn should_resize(&mut self) -> &mut bool {
+        &mut self.should_resize
+    }
+
+    fn init(&mut self, _renderer: &mut crate::render::Renderer, theme: &crate::style::Theme) {
+        let style = &theme.widget_styles.dividers;
+
+        if self.thickness.is_none() {
+            self.thickness = Some(style.thickness);
+        }
+
+        if self.color.is_none() {
+            self.color = Some(theme.colors.secondary);
+        }
+
+        if self.roundness < 0.0 {
+            self.roundness = style.roundness;
+        }
+    }
+
+    fn flex(&self) -> Option<&Flex> {
+        Some(&DIVIDER_FLEX)
+    }
+
+    fn orient(&mut self, orientation: crate::Orientation) {
+        self.orientation = orientation;
+    }
+
+    /// The length is given by the stack when the divider is stretched
+    fn natural_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        let thickness = self.thickness.unwrap_or(0);
+
+        match self.orientation {
+            crate::Orientation::Vertical => (0, thickness),
+            crate::Orientation::Horizontal => (thickness, 0),
+        }
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.bounds.width = width;
+        self.bounds.height = height;
+    }
+
+    fn place(&mut self, x: i32, y: i32) {
+        self.bounds.x = x;
+        self.bounds.y = y;
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+    }
+
+    fn render_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        self.bounds.dimensions()
+    }
+
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme) {
+        let color = self.color.unwrap_or(theme.colors.secondary);
+
+        if self.roundness > 0.0 {
+            renderer.draw(crate::render::DrawCommand::RoundedRect {
+                top_left: self.bounds.top_left(),
+                width: self.bounds.width,
+                height: self.bounds.height,
+                roundness_percent: self.roundness,
+                color,
+            });
+        } else {
+            renderer.draw(crate::render::DrawCommand::Rect {
+                top_left: self.bounds.top_left(),
+                width: self.bounds.width,
+                height: self.bounds.height,
+                color,
+            });
+        }
+    }
+}
@@ -1,14 +1,7 @@
+//! View elements that do not respond to events
+
 pub mod divider;
 pub mod spacer;
 
-/*
-
-    TODO:
-
-    View elements that do not respond to events
-    - Divider bar
-      - Solid bar (rect or rounded rect)
-    - Spacer
-      - Explicit padding
-    
-*/
\ No newline at end of file
+pub use divider::Divider;
+pub use spacer::Spacer;
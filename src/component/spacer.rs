@@ -0,0 +1,106 @@
+use crate::bounding_rect::BoundingRect;
+use crate::view::Flex;
+use crate::view_element::*;
+
+use crate::widget::Widget;
+
+/// Empty space along a stack (vertical space in a `VStack`, horizontal space in an `HStack`).
+///
+/// Usage:
+/// ```ignore
+/// HStack! {
+///     Text::new("title").text("Title"),
+///     // Pushes the button to the right edge
+///     Spacer::flexible(),
+///     Button::new("close"),
+/// }
+/// ```
+pub struct Spacer<Msg, S> {
+    /// Length along the stack
+    size: u32,
+    flex: Option<Flex>,
+    orientation: Option<crate::Orientation>,
+    bounds: BoundingRect,
+
+    should_resize: bool,
+    _phantom_marker: std::marker::PhantomData<(Msg, S)>,
+}
+
+impl<Msg, S> Spacer<Msg, S> {
+    /// Space of `size` pixels
+    pub fn new(size: u32) -> Self {
+        Self {
+            size,
+            flex: None,
+            orientation: None,
+            bounds: BoundingRect::new(),
+            should_resize: false,
+            _phantom_marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Fills the remaining space of the stack (shared with other flexible elements)
+    pub fn flexible() -> Self {
+        let mut spacer = Self::new(0);
+        spacer.flex = Some(Flex {
+            grow: 1.0,
+            ..Flex::new()
+        });
+
+        spacer
+    }
+}
+
+impl<Msg: EmptyMessage, S> Widget<Msg, S> for Spacer<Msg, S> where Msg: 'static, S: 'static {
+    fn id(&self) -> &'static str {
+        ""
+    }
+
+    fn should_resize(&mut self) -> &mut bool {
+        &mut self.should_resize
+    }
+
+    fn init(&mut self, _renderer: &mut crate::render::Renderer, _theme: &crate::style::Theme) {
+
+    }
+
+    fn flex(&self) -> Option<&Flex> {
+        self.flex.as_ref()
+    }
+
+    fn orient(&mut self, orientation: crate::Orientation) {
+        self.orientation = Some(orientation);
+    }
+
+    /// Square outside of stacks
+    fn natural_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        match self.orientation {
+            Some(crate::Orientation::Vertical) => (0, self.size),
+            Some(crate::Orientation::Horizontal) => (self.size, 0),
+            None => (self.size, self.size),
+        }
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.bounds.width = width;
+        self.bounds.height = height;
+    }
+
+    fn place(&mut self, x: i32, y: i32) {
+        self.bounds.x = x;
+        self.bounds.y = y;
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+    }
+
+    fn render_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        self.bounds.dimensions()
+    }
+
+    fn render(&self, _renderer: &mut dyn crate::render::RenderTarget, _theme: &crate::style::Theme) {
+
+    }
+}
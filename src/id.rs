@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+/// Id of widgets which are never looked up or focused (such as decorations).
+/// Any number of widgets can share it.
+pub const ANONYMOUS: &str = "";
+
+/// Anonymous ids are empty or start with `__` (used for widgets created internally,
+/// such as the text of a `CircleButton`). They are ignored when checking that ids are unique.
+pub fn is_anonymous(id: &str) -> bool {
+    id.is_empty() || id.starts_with("__")
+}
+
+thread_local! {
+    static INTERNED: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::new());
+}
+
+/// Turns an id created at runtime (such as from an item's data) into an id widgets can use.
+///
+/// Each distinct id is stored once for the rest of the program, so interning the same id again is free.
+///
+/// Usage:
+/// ```ignore
+/// ForEach::new(|state| &@todos, |todo: &String| todo.clone(), |todo| {
+///     Button::new(id::intern(format!("remove_{}", todo)))
+/// })
+/// ```
+pub fn intern<S: AsRef<str>>(id: S) -> &'static str {
+    let id = id.as_ref();
+
+    INTERNED.with(|interned| {
+        let mut interned = interned.borrow_mut();
+
+        match interned.get(id) {
+            Some(id) => *id,
+            None => {
+                let id: &'static str = Box::leak(id.to_owned().into_boxed_str());
+                interned.insert(id);
+                id
+            }
+        }
+    })
+}
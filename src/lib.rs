@@ -11,6 +11,7 @@ pub mod component;
 
 pub mod render;
 pub mod bounding_rect;
+pub mod id;
 
 pub mod timing;
 
@@ -42,7 +43,10 @@ pub mod prelude {
         Orientation,
         ViewElement,
         IntoViewElement,
+        InsertViewElement,
         EmptyMessage,
+        component::*,
+        id,
     };
 }
 
@@ -58,6 +62,8 @@ pub mod surreal_macros {
     pub use crate::{
         VStack, 
         HStack, 
+        Grid,
+        TabView,
         State,
         GetWidget,
         include_fonts,
@@ -70,6 +76,9 @@ pub mod surreal_macros {
 // the overly complex, inflexible, and lacking winit events.
 // TODO: Consider just wrapping winit events and include mouse info
 pub mod event {
+    /// Keyboard key codes (virtual, layout-dependent)
+    pub use winit::event::VirtualKeyCode as Key;
+
     pub enum MouseButton {
         Left,
         Right,
@@ -82,6 +91,40 @@ pub mod event {
         Released,
     }
 
+    /// Modifier keys held down during an event
+    #[derive(Copy, Clone, Debug, Default, PartialEq)]
+    pub struct Modifiers {
+        pub shift: bool,
+        pub ctrl: bool,
+        pub alt: bool,
+        /// Windows key on Windows, Command key on Mac
+        pub logo: bool,
+    }
+
+    /// Amount scrolled by a mouse wheel or trackpad.
+    ///
+    /// Positive `y` scrolls up (the wheel moves away from the user) and positive `x` scrolls right.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub enum ScrollDelta {
+        /// Lines (or rows) scrolled, such as by a mouse wheel
+        Lines { x: f32, y: f32 },
+        /// Logical pixels scrolled, such as by a trackpad
+        Pixels { x: f32, y: f32 },
+    }
+
+    impl ScrollDelta {
+        /// Number of pixels scrolled per line
+        pub const PIXELS_PER_LINE: f32 = 20.0;
+
+        /// Returns the (x, y) delta in pixels
+        pub fn to_pixels(&self) -> (f32, f32) {
+            match *self {
+                ScrollDelta::Lines { x, y } => (x * Self::PIXELS_PER_LINE, y * Self::PIXELS_PER_LINE),
+                ScrollDelta::Pixels { x, y } => (x, y),
+            }
+        }
+    }
+
     pub enum ApplicationEvent {
         MouseMotion {
             position: (i32, i32),
@@ -94,6 +137,22 @@ pub mod event {
             position: (i32, i32),
         },
 
+        /// Mouse wheel or trackpad scroll. `position` is the mouse position.
+        MouseWheel {
+            delta: ScrollDelta,
+            position: (i32, i32),
+        },
+
+        /// A key was pressed or released. Use this for shortcuts and navigation.
+        Key {
+            key: Key,
+            state: ButtonState,
+            modifiers: Modifiers,
+        },
+
+        /// Text input (never a control character). Use this (rather than `Key`) for typing.
+        Character(char),
+
         None,
     }
 
@@ -108,7 +167,7 @@ pub mod event {
 
 /// Types and derive macro required when using `#derive(IntoViewElement)`
 pub mod view_element {
-    pub use crate::{IntoViewElement, ViewElement, EmptyMessage};
+    pub use crate::{IntoViewElement, InsertViewElement, ViewElement, EmptyMessage};
 }
 
 pub trait EmptyMessage {
@@ -159,7 +218,7 @@ pub enum EventResponse {
 /// f32 RGBA color
 // TODO: Consider making a `Color!` macro that accepts RGB, RGBA, hex, etc.
 // TODO: Should these be f64, or does it not matter?
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -173,6 +232,7 @@ impl Color {
     pub const BLACK:        Color = Color { r: 0.0,   g: 0.0,  b: 0.0,   a: 1.0 };
     pub const CLEAR:        Color = Color { r: 0.0,   g: 0.0,  b: 0.0,   a: 0.0 };
     pub const DARK_GRAY:    Color = Color { r: 0.01,  g: 0.01, b: 0.01,  a: 1.0 };
+    pub const LIGHT_BLUE:   Color = Color { r: 0.1,   g: 0.3,  b: 0.8,   a: 1.0 };
     pub const LIGHT_GRAY:   Color = Color { r: 0.3,   g: 0.3,  b: 0.3,   a: 1.0 };
     pub const WHITE:        Color = Color { r: 1.0,   g: 1.0,  b: 1.0,   a: 1.0 };
 
@@ -223,6 +283,7 @@ impl Alignment {
 }
 
 /// Element orientation
+#[derive(Copy, Clone)]
 pub enum Orientation {
     Vertical,
     Horizontal,
@@ -237,14 +298,14 @@ impl Orientation {
     }
 }
 
-pub enum ViewElement<Msg: EmptyMessage> {
-    Widget(Box<dyn crate::widget::Widget<Msg>>),
-    View(Box<dyn crate::view::View<Msg>>),
+pub enum ViewElement<Msg: EmptyMessage, S> {
+    Widget(Box<dyn crate::widget::Widget<Msg, S>>),
+    View(Box<dyn crate::view::View<Msg, S>>),
 }
 
 // TODO: Make sure the blanket impl hack doesn't break anything
-pub trait IntoViewElement<Msg: EmptyMessage, ImplConstraint> {
-    fn into_element(self) -> ViewElement<Msg>;
+pub trait IntoViewElement<Msg: EmptyMessage, S, ImplConstraint> {
+    fn into_element(self) -> ViewElement<Msg, S>;
 }
 
 // FIXME: These types are leaked by view macros (but not imported through prelude)
@@ -252,124 +313,55 @@ pub struct __WidgetBlanket;
 pub struct __ViewBlanket;
 
 // Unit struct hack from: https://jsdw.me/posts/rust-fn-traits/
-impl<Msg: EmptyMessage, T: widget::Widget<Msg> + 'static> IntoViewElement<Msg, __WidgetBlanket> for T {
-    fn into_element(self) -> ViewElement<Msg> {
+impl<Msg: EmptyMessage, S, T: widget::Widget<Msg, S> + 'static> IntoViewElement<Msg, S, __WidgetBlanket> for T {
+    fn into_element(self) -> ViewElement<Msg, S> {
         ViewElement::Widget(Box::new(self))
     }
 }
 
-impl<Msg: EmptyMessage, T: view::View<Msg> + 'static> IntoViewElement<Msg, __ViewBlanket> for T {
-    fn into_element(self) -> ViewElement<Msg> {
+impl<Msg: EmptyMessage, S, T: view::View<Msg, S> + 'static> IntoViewElement<Msg, S, __ViewBlanket> for T {
+    fn into_element(self) -> ViewElement<Msg, S> {
         ViewElement::View(Box::new(self))
     }
 }
 
 
-//////////// TESTING /////////////
-
-struct Hack1;struct Hack2;struct Hack3;struct Hack4;struct Hack5;
-
-trait InsertViewElement<Msg: EmptyMessage, ImplConstraint> {
-    fn insert_elements(self, list: &mut Vec<ViewElement<Msg>>);
+/// Adds elements to a list of children. Used by the view macros to accept single elements,
+/// lists of elements (`Vec<T>`), optional elements (`Option<T>`), and nothing (`()`).
+pub trait InsertViewElement<Msg: EmptyMessage, S, ImplConstraint> {
+    fn insert_elements(self, list: &mut Vec<ViewElement<Msg, S>>);
 }
 
-// Void type insert nothing
-impl<Msg: EmptyMessage> InsertViewElement<Msg, Hack1> for () {
-    fn insert_elements(self, _list: &mut Vec<ViewElement<Msg>>) {
-        // nothing
-    }
+// Also leaked by view macros
+pub struct __EmptyBlanket;
+pub struct __ElementBlanket<ImplConstraint>(std::marker::PhantomData<ImplConstraint>);
+pub struct __ListBlanket<ImplConstraint>(std::marker::PhantomData<ImplConstraint>);
+pub struct __OptionalBlanket<ImplConstraint>(std::marker::PhantomData<ImplConstraint>);
+
+// Void type inserts nothing
+impl<Msg: EmptyMessage, S> InsertViewElement<Msg, S, __EmptyBlanket> for () {
+    fn insert_elements(self, _list: &mut Vec<ViewElement<Msg, S>>) {}
 }
 
 // Widgets and Views insert themselves
-impl<Msg: EmptyMessage, T: IntoViewElement<Msg, __WidgetBlanket>> InsertViewElement<Msg, Hack2> for T {
-    fn insert_elements(self, list: &mut Vec<ViewElement<Msg>>) {
-        list.push(self.into_element())
-    }
-}
-impl<Msg: EmptyMessage, T: IntoViewElement<Msg, __ViewBlanket>> InsertViewElement<Msg, Hack3> for T {
-    fn insert_elements(self, list: &mut Vec<ViewElement<Msg>>) {
-        list.push(self.into_element())
+impl<Msg: EmptyMessage, S, C, T: IntoViewElement<Msg, S, C>> InsertViewElement<Msg, S, __ElementBlanket<C>> for T {
+    fn insert_elements(self, list: &mut Vec<ViewElement<Msg, S>>) {
+        list.push(self.into_element());
     }
 }
 
-// Lists of Widgets and Lists of Views insert their contents
-impl<Msg: EmptyMessage, T: IntoViewElement<Msg, __WidgetBlanket>> InsertViewElement<Msg, Hack4> for Vec<T> {
-    fn insert_elements(self, list: &mut Vec<ViewElement<Msg>>) {
-        for item in self.into_iter() {
-            list.push(item.into_element())
-        }
-    }
-}
-impl<Msg: EmptyMessage, T: IntoViewElement<Msg, __ViewBlanket>> InsertViewElement<Msg, Hack5> for Vec<T> {
-    fn insert_elements(self, list: &mut Vec<ViewElement<Msg>>) {
-        for item in self.into_iter() {
-            list.push(item.into_element())
-        }
+// Lists of Widgets and lists of Views insert their contents
+impl<Msg: EmptyMessage, S, C, T: IntoViewElement<Msg, S, C>> InsertViewElement<Msg, S, __ListBlanket<C>> for Vec<T> {
+    fn insert_elements(self, list: &mut Vec<ViewElement<Msg, S>>) {
+        list.extend(self.into_iter().map(|item| item.into_element()));
     }
 }
 
-macro_rules! TestStack {
-    ( $($component:expr),+ $(,)? ) => {{
-        let mut children = Vec::new();
-
-        $(
-            ($component).insert_elements(&mut children);
-        )+
-
-        Stack::new(Orientation::Vertical, children)
-    }};
-}
-
-// TODO: Support multiple expressions and an else case
-macro_rules! Conditional {
-    ( $cond:expr => $block:expr ) => {
-        if $cond {
-            vec![$block]
-        } else {
-            vec![]
+// Optional elements insert their contents if there is any
+impl<Msg: EmptyMessage, S, C, T: IntoViewElement<Msg, S, C>> InsertViewElement<Msg, S, __OptionalBlanket<C>> for Option<T> {
+    fn insert_elements(self, list: &mut Vec<ViewElement<Msg, S>>) {
+        if let Some(item) = self {
+            list.push(item.into_element());
         }
-    };
+    }
 }
-
-// TEMP: Here for testing purposes
-#[allow(unused)]
-fn test() {
-    use prelude::*;
-
-    // TODO: See if boxing within the macros rather than within IntoViewElement
-    // can allow for all 3 cases
-    // TODO: Create an `if` macro that returns an empty vec if no else block is given
-
-    let empty1: Vec<Button<()>> = Vec::new();
-    let empty2 = ();
-
-    let list: Vec<Box<dyn IntoViewElement<(), _>>> = vec![
-        Box::new(Button::new("01")),
-        Box::new(Text::new("02")),
-    ];
-
-    let condition = true;
-
-    let view = TestStack! {
-        // Base case
-        Button::new("1"),
-
-        // Nested case
-        TestStack! {
-            CircleButton::new("2"),
-            Text::new("3"),
-            empty2,
-        },
-
-        // List case
-        // list,
-
-        Conditional! {
-            condition => Button::new("test")
-        },
-
-        // Empty "if" case(s?)
-        empty1,
-        empty2,
-    };
-}
\ No newline at end of file
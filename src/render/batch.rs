@@ -0,0 +1,163 @@
+use super::ClipRect;
+use super::quad::QuadInstance;
+use super::texture::TextureInstance;
+
+/// What a batch's instances are drawn with
+#[derive(Debug, PartialEq)]
+pub(super) enum BatchKind {
+    Quads,
+    /// Instances of the same image resource
+    Image(String),
+}
+
+/// Consecutive instances which can be drawn by a single draw call
+pub(super) struct Batch {
+    pub kind: BatchKind,
+    /// Scissor rect within the render target (`None` -> entire target)
+    pub scissor: Option<ClipRect>,
+    /// Range within the kind's instance list
+    pub instances: std::ops::Range<u32>,
+}
+
+/// Draw commands collected until the renderer is flushed.
+///
+/// Commands are batched in the order they were drawn, so overlapping elements
+/// are layered the same way as if every command were drawn immediately.
+pub(super) struct DrawQueue {
+    pub quad_instances: Vec<QuadInstance>,
+    pub texture_instances: Vec<TextureInstance>,
+    pub batches: Vec<Batch>,
+}
+
+impl DrawQueue {
+    pub fn new() -> Self {
+        Self {
+            quad_instances: Vec::new(),
+            texture_instances: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.quad_instances.clear();
+        self.texture_instances.clear();
+        self.batches.clear();
+    }
+
+    pub fn push_quad(&mut self, instance: QuadInstance, scissor: Option<ClipRect>) {
+        let index = self.quad_instances.len() as u32;
+        self.quad_instances.push(instance);
+
+        Self::extend_batches(&mut self.batches, BatchKind::Quads, scissor, index);
+    }
+
+    pub fn push_image(&mut self, alias: &str, instance: TextureInstance, scissor: Option<ClipRect>) {
+        let index = self.texture_instances.len() as u32;
+        self.texture_instances.push(instance);
+
+        Self::extend_batches(&mut self.batches, BatchKind::Image(alias.to_owned()), scissor, index);
+    }
+
+    /// Adds the instance to the last batch if possible, otherwise starts a new batch
+    fn extend_batches(batches: &mut Vec<Batch>, kind: BatchKind, scissor: Option<ClipRect>, index: u32) {
+        if let Some(last) = batches.last_mut() {
+            if last.kind == kind && last.scissor == scissor && last.instances.end == index {
+                last.instances.end += 1;
+                return;
+            }
+        }
+
+        batches.push(Batch {
+            kind,
+            scissor,
+            instances: index..index + 1,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad() -> QuadInstance {
+        bytemuck::Zeroable::zeroed()
+    }
+
+    fn image() -> TextureInstance {
+        TextureInstance {
+            top_left: cgmath::vec2(0.0, 0.0),
+            size: cgmath::vec2(10.0, 10.0),
+        }
+    }
+
+    #[test]
+    fn consecutive_instances_of_one_kind_and_scissor_share_a_batch() {
+        let mut queue = DrawQueue::new();
+        assert!(queue.is_empty());
+
+        let scissor = Some(ClipRect::new((0, 0), 100, 100));
+        for _ in 0..3 {
+            queue.push_quad(quad(), scissor);
+        }
+        queue.push_image("a", image(), scissor);
+        queue.push_image("a", image(), scissor);
+
+        assert_eq!(queue.batches.len(), 2);
+        assert_eq!(queue.batches[0].kind, BatchKind::Quads);
+        assert_eq!(queue.batches[0].instances, 0..3);
+        assert_eq!(queue.batches[1].kind, BatchKind::Image("a".to_owned()));
+        assert_eq!(queue.batches[1].instances, 0..2);
+
+        queue.clear();
+        assert!(queue.is_empty());
+        assert!(queue.quad_instances.is_empty() && queue.texture_instances.is_empty());
+    }
+
+    #[test]
+    fn images_of_different_textures_are_split() {
+        let mut queue = DrawQueue::new();
+        queue.push_image("a", image(), None);
+        queue.push_image("b", image(), None);
+        queue.push_image("a", image(), None);
+
+        let batches: Vec<_> = queue.batches.iter().map(|batch| (&batch.kind, batch.instances.clone())).collect();
+        assert_eq!(batches, vec![
+            (&BatchKind::Image("a".to_owned()), 0..1),
+            (&BatchKind::Image("b".to_owned()), 1..2),
+            (&BatchKind::Image("a".to_owned()), 2..3),
+        ]);
+    }
+
+    #[test]
+    fn changing_the_scissor_starts_a_new_batch() {
+        let mut queue = DrawQueue::new();
+        let clip = Some(ClipRect::new((10, 10), 20, 20));
+        queue.push_quad(quad(), None);
+        queue.push_quad(quad(), clip);
+        queue.push_quad(quad(), clip);
+        queue.push_quad(quad(), None);
+
+        let batches: Vec<_> = queue.batches.iter().map(|batch| (batch.scissor, batch.instances.clone())).collect();
+        assert_eq!(batches, vec![(None, 0..1), (clip, 1..3), (None, 3..4)]);
+    }
+
+    #[test]
+    fn interleaved_kinds_keep_their_order() {
+        let mut queue = DrawQueue::new();
+        queue.push_quad(quad(), None);
+        queue.push_image("a", image(), None);
+        queue.push_quad(quad(), None);
+
+        // The second quad can't join the first batch without being drawn beneath the image
+        let batches: Vec<_> = queue.batches.iter().map(|batch| (&batch.kind, batch.instances.clone())).collect();
+        assert_eq!(batches, vec![
+            (&BatchKind::Quads, 0..1),
+            (&BatchKind::Image("a".to_owned()), 0..1),
+            (&BatchKind::Quads, 1..2),
+        ]);
+    }
+}
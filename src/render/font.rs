@@ -10,13 +10,19 @@ use std::collections::HashMap;
 // TODO: Need sizing functionality (text constraints) for element layout
 pub struct TextRenderer {
     /// Contains all fonts. Used to render text
-    brush: wgpu_glyph::GlyphBrush<()>,
+    brush: Brush,
     /// Map of (font_alias -> font_id)
     fonts: HashMap<&'static str, FontId>,
     // TODO: Consider adding a HashSet containing all the loaded font paths.
     // This would guarentee that no fonts are ever duplicated
 }
 
+/// Headless text renderers can measure text, but not draw it
+enum Brush {
+    Gpu(wgpu_glyph::GlyphBrush<()>),
+    Headless(glyph_brush::GlyphBrush<()>),
+}
+
 impl TextRenderer {
     // From https://docs.rs/wgpu_glyph/0.9.0/wgpu_glyph/struct.GlyphBrushBuilder.html#method.using_font
     const DEFAULT_FONT_ID: FontId = FontId(0);
@@ -30,8 +36,8 @@ impl TextRenderer {
         let font = ab_glyph::FontArc::try_from_vec(font_bytes)
             .unwrap();
 
-        let brush = GlyphBrushBuilder::using_font(font)
-            .build(device, render_format);
+        let brush = Brush::Gpu(GlyphBrushBuilder::using_font(font)
+            .build(device, render_format));
         
         let mut fonts = HashMap::new();
         fonts.insert(default_font_alias, Self::DEFAULT_FONT_ID);
@@ -55,7 +61,24 @@ impl TextRenderer {
         ).build(device, render_format);
 
         Self {
-            brush,
+            brush: Brush::Gpu(brush),
+            fonts: font_map,
+        }
+    }
+
+    /// Same as `from_fonts`, but without a GPU. Headless text renderers can only measure text.
+    pub fn headless(fonts: Vec<(&'static str, ab_glyph::FontArc)>) -> Self {
+        let mut font_map = HashMap::new();
+
+        let brush = glyph_brush::GlyphBrushBuilder::using_fonts(
+            fonts.into_iter().enumerate().map(|(index, (alias, font))| {
+                font_map.insert(alias, FontId(index));
+                font
+            }).collect()
+        ).build();
+
+        Self {
+            brush: Brush::Headless(brush),
             fonts: font_map,
         }
     }
@@ -67,7 +90,10 @@ impl TextRenderer {
         let font = ab_glyph::FontArc::try_from_vec(font_bytes)
             .unwrap();
 
-        let id = self.brush.add_font(font);
+        let id = match &mut self.brush {
+            Brush::Gpu(brush) => brush.add_font(font),
+            Brush::Headless(brush) => brush.add_font(font),
+        };
 
         if let Some(FontId(existing_id)) = self.fonts.insert(alias, id) {
             panic!("A font with alias `{}` already exists (FontId: {})", alias, existing_id);
@@ -91,7 +117,10 @@ impl TextRenderer {
     /// Returns the (width, height) of the text's bounding rect
     pub fn get_section_bounds(&mut self, section: &wgpu_glyph::Section) -> (u32, u32) {
         use wgpu_glyph::GlyphCruncher;
-        let bounds = self.brush.glyph_bounds(section);
+        let bounds = match &mut self.brush {
+            Brush::Gpu(brush) => brush.glyph_bounds(section),
+            Brush::Headless(brush) => brush.glyph_bounds(section),
+        };
         
         if let Some(rect) = bounds {
             // TODO: Use floats internally rather than casting to u32
@@ -102,18 +131,81 @@ impl TextRenderer {
         }
     }
 
-    pub fn render_queue(&mut self, device: &wgpu::Device, target_texture_view: &wgpu::TextureView, encoder: &mut wgpu::CommandEncoder, target_width: u32, target_height: u32) {
-        self.brush.draw_queued(
-            device,
-            encoder,
-            target_texture_view,
-            target_width,
-            target_height,
-        ).unwrap();
+    /// Returns the region covered by the section's glyphs (`None` if nothing is drawn)
+    pub fn get_section_region(&mut self, section: &glyph_brush::OwnedSection) -> Option<super::ClipRect> {
+        use wgpu_glyph::GlyphCruncher;
+        let section = section.to_borrowed();
+
+        let bounds = match &mut self.brush {
+            Brush::Gpu(brush) => brush.glyph_bounds(&section),
+            Brush::Headless(brush) => brush.glyph_bounds(&section),
+        };
+
+        bounds.map(|rect| {
+            let (left, top) = (rect.min.x.floor() as i32, rect.min.y.floor() as i32);
+            let (right, bottom) = (rect.max.x.ceil() as i32, rect.max.y.ceil() as i32);
+
+            super::ClipRect::new((left, top), (right - left) as u32, (bottom - top) as u32)
+        })
+    }
+
+    /// Returns the positioned glyphs of a section (used for drawing text without the GPU)
+    pub fn section_glyphs(&mut self, section: &glyph_brush::OwnedSection) -> Vec<glyph_brush::SectionGlyph> {
+        use wgpu_glyph::GlyphCruncher;
+        let section = section.to_borrowed();
+
+        match &mut self.brush {
+            Brush::Gpu(brush) => brush.glyphs(&section).cloned().collect(),
+            Brush::Headless(brush) => brush.glyphs(&section).cloned().collect(),
+        }
+    }
+
+    /// All loaded fonts, indexed by `FontId`
+    pub fn fonts(&self) -> &[ab_glyph::FontArc] {
+        use wgpu_glyph::GlyphCruncher;
+
+        match &self.brush {
+            Brush::Gpu(brush) => brush.fonts(),
+            Brush::Headless(brush) => brush.fonts(),
+        }
+    }
+
+    /// Draws all queued sections. Text is restricted to `clip` if given (it must be within the target).
+    pub fn render_queue(&mut self, device: &wgpu::Device, target_texture_view: &wgpu::TextureView, encoder: &mut wgpu::CommandEncoder, target_width: u32, target_height: u32, clip: Option<super::ClipRect>) {
+        if let Some(clip) = clip {
+            self.gpu_brush().draw_queued_with_transform_and_scissoring(
+                device,
+                encoder,
+                target_texture_view,
+                wgpu_glyph::orthographic_projection(target_width, target_height),
+                wgpu_glyph::Region {
+                    x: clip.top_left.0 as u32,
+                    y: clip.top_left.1 as u32,
+                    width: clip.width,
+                    height: clip.height,
+                },
+            ).unwrap();
+        } else {
+            self.gpu_brush().draw_queued(
+                device,
+                encoder,
+                target_texture_view,
+                target_width,
+                target_height,
+            ).unwrap();
+        }
     }
 
     pub fn queue_section(&mut self, section: &glyph_brush::OwnedSection) {   
-        self.brush.queue(section);
+        self.gpu_brush().queue(section);
+    }
+
+    fn gpu_brush(&mut self) -> &mut wgpu_glyph::GlyphBrush<()> {
+        if let Brush::Gpu(brush) = &mut self.brush {
+            brush
+        } else {
+            panic!("A headless text renderer cannot draw text");
+        }
     }
 }
 
@@ -128,7 +220,7 @@ pub type IncludedFonts = Vec<(&'static str, wgpu_glyph::ab_glyph::FontArc)>;
 /// Note that the default font **must** be specified.
 ///
 /// Usage:
-/// ```
+/// ```ignore
 /// let fonts = include_fonts! {
 ///     default => "path/to/default_font",
 ///     alias_1 => "path/to/font1",
@@ -0,0 +1,325 @@
+use super::{DrawCommand, RenderTarget, ClipRect};
+
+/// An owned `DrawCommand` (or clip change), as recorded by a `CommandRecorder`
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedCommand {
+    /// See `RenderTarget::push_clip`
+    PushClip(ClipRect),
+    /// See `RenderTarget::pop_clip`
+    PopClip,
+    Text(glyph_brush::OwnedSection),
+    Circle {
+        center: (i32, i32),
+        radius: u32,
+        color: crate::Color,
+    },
+    Rect {
+        top_left: (i32, i32),
+        width: u32,
+        height: u32,
+        color: crate::Color,
+    },
+    RoundedRect {
+        top_left: (i32, i32),
+        width: u32,
+        height: u32,
+        roundness_percent: f32,
+        color: crate::Color,
+    },
+    Image {
+        alias: String,
+        top_left: (i32, i32),
+        width: u32,
+        height: u32,
+    },
+}
+
+impl<'a> From<DrawCommand<'a>> for RecordedCommand {
+    fn from(command: DrawCommand<'a>) -> Self {
+        match command {
+            DrawCommand::Text(section) => {
+                RecordedCommand::Text(section.clone())
+            }
+
+            DrawCommand::Circle { center, radius, color } => {
+                RecordedCommand::Circle { center, radius, color }
+            }
+
+            DrawCommand::Rect { top_left, width, height, color } => {
+                RecordedCommand::Rect { top_left, width, height, color }
+            }
+
+            DrawCommand::RoundedRect { top_left, width, height, roundness_percent, color } => {
+                RecordedCommand::RoundedRect { top_left, width, height, roundness_percent, color }
+            }
+
+            DrawCommand::Image { alias, top_left, width, height } => {
+                RecordedCommand::Image { alias: alias.to_owned(), top_left, width, height }
+            }
+        }
+    }
+}
+
+/// Render target which records draw commands instead of drawing them.
+///
+/// Together with `Renderer::headless`, this allows views to be initialized,
+/// layed out, and rendered without a GPU or window (such as in tests).
+///
+/// Usage:
+/// ```ignore
+/// let mut renderer = Renderer::headless(fonts, images);
+/// let mut recorder = CommandRecorder::new();
+///
+/// let commands = recorder.record_view(&mut view, &mut renderer, &DEFAULT_THEME, (800, 600));
+/// assert_eq!(commands.len(), 3);
+/// ```
+pub struct CommandRecorder {
+    commands: Vec<RecordedCommand>,
+}
+
+impl CommandRecorder {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Commands recorded so far (in draw order)
+    pub fn commands(&self) -> &[RecordedCommand] {
+        &self.commands
+    }
+
+    /// Returns the recorded commands, leaving the recorder empty
+    pub fn take_commands(&mut self) -> Vec<RecordedCommand> {
+        std::mem::replace(&mut self.commands, Vec::new())
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Initializes, lays out, and renders `view` as a root view with the given dimensions.
+    /// Returns the commands recorded by this render.
+    pub fn record_view<Msg: crate::EmptyMessage, S>(&mut self, view: &mut dyn crate::view::View<Msg, S>, renderer: &mut super::Renderer, theme: &crate::style::Theme, dimensions: (u32, u32)) -> &[RecordedCommand] {
+        self.clear();
+
+        view._init(renderer, theme, true);
+        // After initializing, so elements created by views (such as `ForEach`) are checked as well
+        if let Err(error) = crate::view::validate_ids(view) {
+            panic!("{}", error);
+        }
+        view.measure(renderer, theme, crate::view::BoxConstraints::tight(dimensions), true);
+        view.arrange(0, 0);
+        view.render(self, theme);
+
+        &self.commands
+    }
+}
+
+impl RenderTarget for CommandRecorder {
+    fn draw(&mut self, command: DrawCommand) {
+        self.commands.push(command.into());
+    }
+
+    fn push_clip(&mut self, rect: ClipRect) {
+        self.commands.push(RecordedCommand::PushClip(rect));
+    }
+
+    fn pop_clip(&mut self) {
+        self.commands.push(RecordedCommand::PopClip);
+    }
+}
+
+/// The fonts and images in `res/`, for tests
+#[cfg(test)]
+pub(crate) fn test_resources() -> (super::font::IncludedFonts, crate::widget::IncludedImages) {
+    let fonts = crate::include_fonts! {
+        default => "../../res/JetBrainsMono/JetBrainsMono-Medium.ttf",
+    };
+    let images = crate::include_images! {
+        plus => "../../res/images/plus_thing.png",
+    };
+
+    (fonts, images)
+}
+
+/// Headless renderer with the `test_resources`
+#[cfg(test)]
+pub(crate) fn test_renderer() -> super::Renderer {
+    let (fonts, images) = test_resources();
+    super::Renderer::headless(fonts, images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn record<V: crate::view::View<(), ()>>(view: &mut V, dimensions: (u32, u32)) -> Vec<RecordedCommand> {
+        let mut renderer = test_renderer();
+        let mut recorder = CommandRecorder::new();
+
+        recorder.record_view(view, &mut renderer, &DEFAULT_THEME, dimensions).to_vec()
+    }
+
+    fn texts(commands: &[RecordedCommand]) -> Vec<String> {
+        commands.iter().filter_map(|command| match command {
+            RecordedCommand::Text(section) => Some(section.text.iter().map(|text| text.text.as_str()).collect()),
+            _ => None,
+        }).collect()
+    }
+
+    fn text_position(commands: &[RecordedCommand], text: &str) -> (f32, f32) {
+        commands.iter().find_map(|command| match command {
+            RecordedCommand::Text(section) if section.text[0].text == text => Some(section.screen_position),
+            _ => None,
+        }).unwrap_or_else(|| panic!("No text `{}` was drawn", text))
+    }
+
+    #[test]
+    fn button_draws_background_then_text() {
+        let mut view = VStack! {
+            Button::new("button").text(Text::new("").text("Click")),
+        };
+        let commands = record(&mut view, (200, 100));
+
+        assert_eq!(commands.len(), 2);
+        let (top_left, width, height) = match &commands[0] {
+            RecordedCommand::RoundedRect { top_left, width, height, .. } => (*top_left, *width, *height),
+            other => panic!("Expected the button background, got {:?}", other),
+        };
+        assert_eq!(texts(&commands), vec!["Click"]);
+
+        // The text is centered within the background
+        let (x, y) = text_position(&commands, "Click");
+        assert!(x > top_left.0 as f32 && x < (top_left.0 + width as i32) as f32);
+        assert!(y > top_left.1 as f32 && y < (top_left.1 + height as i32) as f32);
+    }
+
+    #[test]
+    fn text_draws_its_section() {
+        let mut view = VStack! {
+            Text::new("text").text("Hello").scale(20.0).color(Color::BLACK),
+        };
+        let commands = record(&mut view, (200, 100));
+
+        assert_eq!(commands.len(), 1);
+        match &commands[0] {
+            RecordedCommand::Text(section) => {
+                assert_eq!(section.text[0].text, "Hello");
+                assert_eq!(section.text[0].scale.y, 20.0);
+                assert_eq!(section.text[0].extra.color, Color::BLACK.as_array());
+            }
+            other => panic!("Expected text, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn text_input_clips_its_text() {
+        let mut view = VStack! {
+            TextInput::new("empty").placeholder("Name").width(100),
+            TextInput::new("filled").placeholder("Name").text("Ada").width(100),
+        };
+        let commands = record(&mut view, (300, 200));
+
+        // Placeholder is shown until there is text
+        assert_eq!(texts(&commands), vec!["Name", "Ada"]);
+
+        // Background, then the text within a clip (without focus -> no caret)
+        for input in commands.chunks(4) {
+            match input {
+                [RecordedCommand::RoundedRect { top_left, width, height, .. }, RecordedCommand::PushClip(clip), RecordedCommand::Text(_), RecordedCommand::PopClip] => {
+                    assert_eq!(*width, 100);
+                    assert!(clip.top_left.0 >= top_left.0 && clip.width <= *width);
+                    assert_eq!((clip.top_left.1, clip.height), (top_left.1, *height));
+                }
+                other => panic!("Unexpected commands for a text input: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn scroll_view_clips_and_scrolls_content() {
+        let mut view: ScrollView<(), ()> = ScrollView::new(VStack! {
+            Text::new("a").text("a"),
+            Text::new("b").text("b"),
+            Text::new("c").text("c"),
+        })
+        .height(40);
+
+        let mut renderer = test_renderer();
+        let mut recorder = CommandRecorder::new();
+        let commands = recorder.record_view(&mut view, &mut renderer, &DEFAULT_THEME, (200, 100)).to_vec();
+
+        // Content is drawn within the viewport's clip
+        match &commands[0] {
+            RecordedCommand::PushClip(clip) => assert_eq!(clip.height, 40),
+            other => panic!("Expected the viewport clip, got {:?}", other),
+        }
+        let pop = commands.iter().position(|command| *command == RecordedCommand::PopClip).unwrap();
+        assert_eq!(texts(&commands[..pop]), vec!["a", "b", "c"]);
+
+        // The content overflows -> a scroll bar is drawn after (on top of) the content
+        assert!(commands.len() > pop + 1);
+        assert!(texts(&commands[pop..]).is_empty());
+
+        let (_, before) = text_position(&commands, "b");
+        view.scroll_to(0.0, 20.0);
+        recorder.clear();
+        view.render(&mut recorder, &DEFAULT_THEME);
+        let (_, after) = text_position(recorder.commands(), "b");
+
+        assert_eq!(view.scroll_offset(), (0, 20));
+        assert_eq!(after, before - 20.0);
+    }
+
+    #[test]
+    fn nested_scroll_views_scroll_before_enclosing_views() {
+        use crate::event::{ApplicationEvent, ScrollDelta};
+
+        let mut view: ScrollView<(), ()> = ScrollView::new(VStack! {
+            ScrollView::new(VStack! {
+                Text::new("a").text("a"),
+                Text::new("b").text("b"),
+                Text::new("c").text("c"),
+            })
+            .height(30),
+            Text::new("d").text("d"),
+            Text::new("e").text("e"),
+            Text::new("f").text("f"),
+        })
+        .height(60);
+
+        let mut renderer = test_renderer();
+        let mut recorder = CommandRecorder::new();
+        let commands = recorder.record_view(&mut view, &mut renderer, &DEFAULT_THEME, (200, 200)).to_vec();
+        view.assign_state(crate::state::make_shared(crate::state::State::new(())));
+
+        let (a_x, a_y) = text_position(&commands, "a");
+        let (_, d_y) = text_position(&commands, "d");
+
+        let mut message_queue = crate::MessageQueue::new();
+        let mut wheel = |view: &mut ScrollView<(), ()>| {
+            let event = ApplicationEvent::MouseWheel { delta: ScrollDelta::Pixels { x: 0.0, y: -5.0 }, position: (a_x as i32 + 1, a_y as i32 + 1) };
+            let redraw = view.propogate_event(&event, &mut message_queue);
+
+            recorder.clear();
+            view.render(&mut recorder, &DEFAULT_THEME);
+            (redraw, text_position(recorder.commands(), "a").1, text_position(recorder.commands(), "d").1)
+        };
+
+        // The inner view scrolls first
+        let (redraw, a, d) = wheel(&mut view);
+        assert!(redraw);
+        assert_eq!((a, d), (a_y - 5.0, d_y));
+        assert_eq!(view.scroll_offset(), (0, 0));
+
+        // Once the inner view reached its end, the enclosing view scrolls
+        let mut a = a;
+        for _ in 0..40 {
+            a = wheel(&mut view).1;
+        }
+        assert!(view.scroll_offset().1 > 0);
+        assert!(a < a_y - view.scroll_offset().1 as f32);
+    }
+}
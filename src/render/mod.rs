@@ -15,7 +15,11 @@ macro_rules! size_of {
 
 pub mod font;
 pub mod texture;
+pub mod headless;
+pub mod software;
+pub mod offscreen;
 mod quad;
+mod batch;
 
 
 mod primitive {
@@ -68,6 +72,121 @@ pub enum DrawCommand<'a> {
     },
 }
 
+impl<'a> DrawCommand<'a> {
+    /// Region covered by a shape or image (`None` for text, which depends on the font)
+    fn shape_region(&self) -> Option<ClipRect> {
+        match *self {
+            DrawCommand::Text(_) => None,
+            DrawCommand::Circle { center, radius, .. } => Some(ClipRect::new((center.0 - radius as i32, center.1 - radius as i32), radius * 2, radius * 2)),
+            DrawCommand::Rect { top_left, width, height, .. } => Some(ClipRect::new(top_left, width, height)),
+            DrawCommand::RoundedRect { top_left, width, height, .. } => Some(ClipRect::new(top_left, width, height)),
+            DrawCommand::Image { top_left, width, height, .. } => Some(ClipRect::new(top_left, width, height)),
+        }
+    }
+}
+
+/// Rectangular region which drawing is restricted to (see `RenderTarget::push_clip`)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClipRect {
+    pub top_left: (i32, i32),
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ClipRect {
+    pub fn new(top_left: (i32, i32), width: u32, height: u32) -> Self {
+        Self {
+            top_left,
+            width,
+            height,
+        }
+    }
+
+    /// Region covered by both rects (empty if they do not overlap)
+    pub fn intersection(&self, other: &ClipRect) -> ClipRect {
+        let left = std::cmp::max(self.top_left.0, other.top_left.0);
+        let top = std::cmp::max(self.top_left.1, other.top_left.1);
+        let right = std::cmp::min(self.top_left.0 + self.width as i32, other.top_left.0 + other.width as i32);
+        let bottom = std::cmp::min(self.top_left.1 + self.height as i32, other.top_left.1 + other.height as i32);
+
+        ClipRect {
+            top_left: (left, top),
+            width: std::cmp::max(0, right - left) as u32,
+            height: std::cmp::max(0, bottom - top) as u32,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns whether a pixel is within the rect
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+           x >= self.top_left.0
+        && x < self.top_left.0 + self.width as i32
+        && y >= self.top_left.1
+        && y < self.top_left.1 + self.height as i32
+    }
+}
+
+/// Nested clip regions. Each pushed region is intersected with the current one.
+pub struct ClipStack {
+    stack: Vec<ClipRect>,
+}
+
+impl ClipStack {
+    pub fn new() -> Self {
+        Self {
+            stack: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, rect: ClipRect) {
+        let rect = match self.current() {
+            Some(current) => current.intersection(&rect),
+            None => rect,
+        };
+
+        self.stack.push(rect);
+    }
+
+    pub fn pop(&mut self) {
+        if self.stack.pop().is_none() {
+            panic!("`pop_clip` was called without a matching `push_clip`");
+        }
+    }
+
+    /// The region drawing is currently restricted to (`None` -> unrestricted)
+    pub fn current(&self) -> Option<ClipRect> {
+        self.stack.last().copied()
+    }
+}
+
+/// Returns whether the shape drawn by `command` (restricted to `clip`) overlaps any of the `text` regions.
+/// Text commands never cover text, since text is drawn in order.
+fn covers_text(command: &DrawCommand, clip: Option<ClipRect>, text: &[ClipRect]) -> bool {
+    let region = match command.shape_region() {
+        Some(region) => clip.map_or(region, |clip| clip.intersection(&region)),
+        None => return false,
+    };
+
+    !region.is_empty() && text.iter().any(|text| !text.intersection(&region).is_empty())
+}
+
+/// Anything able to consume `DrawCommand`s.
+///
+/// View elements render to this rather than to the GPU directly, allowing views
+/// to be drawn by something other than the window's `ContextualRenderer`
+/// (see `headless::CommandRecorder` and `software::SoftwareRenderer`).
+pub trait RenderTarget {
+    fn draw(&mut self, command: DrawCommand);
+
+    /// Restricts drawing to `rect` until the matching `pop_clip`.
+    /// Nested clips are intersected with the enclosing clip.
+    fn push_clip(&mut self, rect: ClipRect);
+    fn pop_clip(&mut self);
+}
+
 /// Bundles Renderer with required context for an easy-to-use construct.
 ///
 /// This is done to avoid needing to pass a context type to all `Renderer` functions 
@@ -75,44 +194,73 @@ pub enum DrawCommand<'a> {
 pub struct ContextualRenderer<'frame> {
     pub renderer: &'frame mut Renderer,
     pub device: &'frame wgpu::Device,
-    // TODO: See whether `queue.write_buffer` can be ordered properly
-    pub queue: &'frame wgpu::Queue,
     pub target: &'frame wgpu::TextureView,
     pub encoder: &'frame mut wgpu::CommandEncoder,
     pub window_dimensions: (u32, u32),
+    clip_stack: ClipStack,
 }
 
-impl<'frame> ContextualRenderer<'frame> {
-    pub fn draw(&mut self, command: DrawCommand) {
+impl<'frame> RenderTarget for ContextualRenderer<'frame> {
+    fn draw(&mut self, command: DrawCommand) {
+        // Text is drawn after the shapes of its layer -> shapes covering queued text begin a new layer
+        if self.renderer.covers_queued_text(&command, self.clip_stack.current()) {
+            self.flush();
+        }
+
         self.renderer.draw(
             command, 
-            self.device,
-            self.queue,
-            self.target,
-            self.encoder,
             self.window_dimensions,
+            self.clip_stack.current(),
         );
     }
 
+    fn push_clip(&mut self, rect: ClipRect) {
+        // Text queued so far belongs to the enclosing clip
+        self.flush();
+        self.clip_stack.push(rect);
+    }
+
+    fn pop_clip(&mut self) {
+        self.flush();
+        self.clip_stack.pop();
+    }
+}
+
+impl<'frame> ContextualRenderer<'frame> {
     pub fn get_font_id(&self, alias: &str) -> wgpu_glyph::FontId {
         self.renderer.text_renderer.get_font_id(alias)
     }
+
+    /// Draws the queued quads and images followed by the queued text (restricted to the current clip).
+    ///
+    /// Text is normally drawn once per frame (see `Renderer::render_view`), so this
+    /// only happens when the clip changes or when a shape is drawn on top of queued text.
+    fn flush(&mut self) {
+        self.renderer.flush(self.device, self.encoder, self.target, self.window_dimensions);
+        self.renderer.flush_text(self.device, self.encoder, self.target, self.window_dimensions, self.clip_stack.current());
+    }
 }
 
-/// Contains functionality for rendering to a target
-pub struct Renderer {
-    // Quad essentially serves as a brush with relevant information being
-    // passed through vertices/uniforms
+/// GPU resources used for drawing. Headless renderers do not have these.
+struct Pipelines {
+    // Every quad and image is an instance of this unit quad
+    // Per-frame information is passed through its uniforms
     ui_quad: quad::Quad,
-    // quad_bind_group_layout: wgpu::BindGroupLayout,
     quad_render_pipeline: wgpu::RenderPipeline,
 
-
-    pub texture_map: texture::TextureMap,
-    texture_quad: texture::TextureQuad,
     // texture_bind_group_layout: wgpu::BindGroupLayout,
     texture_render_pipeline: wgpu::RenderPipeline,
+}
+
+/// Contains functionality for rendering to a target
+pub struct Renderer {
+    pipelines: Option<Pipelines>,
+    /// Quads and images waiting to be drawn (see `Renderer::flush`)
+    draw_queue: batch::DrawQueue,
+    /// Regions of the text queued since the text was last drawn
+    queued_text: Vec<ClipRect>,
 
+    pub texture_map: texture::TextureMap,
     pub text_renderer: font::TextRenderer,
 }
 
@@ -126,14 +274,13 @@ impl Renderer {
         let ui_quad = quad::Quad::new(device, &quad_bind_group_layout);
 
         let texture_bind_group_layout = texture::Texture::bind_group_layout(device);
-        let texture_render_pipeline = texture::Texture::create_render_pipeline(device, &texture_bind_group_layout);
+        let texture_render_pipeline = texture::Texture::create_render_pipeline(device, &texture_bind_group_layout, &quad_bind_group_layout);
         let texture_map = texture::TextureMap::from_images(
             image_resources, 
             &texture_bind_group_layout, 
             device, 
             queue
         );
-        let texture_quad = texture::TextureQuad::new(device);
 
         let text_renderer = font::TextRenderer::from_fonts(
             fonts, 
@@ -141,93 +288,240 @@ impl Renderer {
             crate::TEXTURE_FORMAT
         );
 
-        Self {
+        let pipelines = Pipelines {
             ui_quad,
-            // quad_bind_group_layout,
             quad_render_pipeline,
 
-            texture_map,
-            texture_quad,
             // texture_bind_group_layout,
             texture_render_pipeline,
+        };
 
+        Self {
+            pipelines: Some(pipelines),
+            draw_queue: batch::DrawQueue::new(),
+            queued_text: Vec::new(),
+            texture_map,
             text_renderer,
         }
     }
 
-    pub fn draw(&mut self, command: DrawCommand, device: &wgpu::Device, _queue: &wgpu::Queue, target: &wgpu::TextureView, encoder: &mut wgpu::CommandEncoder, window_dimensions: (u32, u32)) {       
+    /// Creates a renderer without a GPU. 
+    ///
+    /// Fonts and images are still loaded so views can be initialized and layed out,
+    /// but the renderer cannot `draw`. Render views to a `RenderTarget` such as 
+    /// `headless::CommandRecorder` instead.
+    pub fn headless(fonts: font::IncludedFonts, image_resources: crate::widget::IncludedImages) -> Self {
+        Self {
+            pipelines: None,
+            draw_queue: batch::DrawQueue::new(),
+            queued_text: Vec::new(),
+            texture_map: texture::TextureMap::headless(image_resources),
+            text_renderer: font::TextRenderer::headless(fonts),
+        }
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.pipelines.is_none()
+    }
+
+    /// Queues the command to be drawn by the next `flush`, restricted to the `clip` region (if any).
+    /// Text is queued separately; the caller must render the text queue with the same clip.
+    pub fn draw(&mut self, command: DrawCommand, window_dimensions: (u32, u32), clip: Option<ClipRect>) {
+        if self.pipelines.is_none() {
+            panic!("A headless renderer cannot draw. Render to a `RenderTarget` instead.");
+        }
+
+        // Scissor rects must be within the render target
+        let scissor = clip.map(|clip| clip.intersection(&ClipRect::new((0, 0), window_dimensions.0, window_dimensions.1)));
+        if let Some(scissor) = scissor {
+            // Nothing would be drawn
+            if scissor.is_empty() {
+                return;
+            }
+        }
+
         match command {
             DrawCommand::Circle { center, radius, color } => {
-                self.ui_quad.update_vertices(device, window_dimensions, (center.0 - radius as i32, center.1 - radius as i32), radius*2, radius*2);
-                self.ui_quad.update_uniforms(device, encoder, quad::Uniforms {
+                self.draw_queue.push_quad(quad::QuadInstance {
+                    top_left: ((center.0 - radius as i32) as f32, (center.1 - radius as i32) as f32).into(),
+                    size: ((radius * 2) as f32, (radius * 2) as f32).into(),
                     color: color.into(),
-                    window_dimensions: (window_dimensions.0 as f32, window_dimensions.1 as f32).into(),
-                    center: (center.0 as f32, center.1 as f32).into(),
                     primitive_type: primitive::CIRCLE,
-                    circle_radius: radius as f32,
-                    primitive_width: 0.0,
-                    primitive_height: 0.0,
-                    rounded_rect_roundness: 0.0,
-                });
+                    roundness: radius as f32,
+                }, scissor);
             }
 
             DrawCommand::Rect { top_left, width, height, color } => {
-                self.ui_quad.update_vertices(device, window_dimensions, top_left, width, height);
-                self.ui_quad.update_uniforms(device, encoder,quad::Uniforms {
-                    window_dimensions: (window_dimensions.0 as f32, window_dimensions.1 as f32).into(),
+                self.draw_queue.push_quad(quad::QuadInstance {
+                    top_left: (top_left.0 as f32, top_left.1 as f32).into(),
+                    size: (width as f32, height as f32).into(),
                     color: color.into(),
                     primitive_type: primitive::RECTANGLE,
-                    center: (0.0, 0.0).into(),
-                    circle_radius: 0.0,
-                    primitive_width: 0.0,
-                    primitive_height: 0.0,
-                    rounded_rect_roundness: 0.0,
-                });
+                    roundness: 0.0,
+                }, scissor);
             }
 
             DrawCommand::RoundedRect { top_left, width, height, mut roundness_percent, color } => {
-                self.ui_quad.update_vertices(device, window_dimensions, top_left, width, height);
-                
                 // roundness = clamp(min(half_width, half_height), 0, 100)
                 if roundness_percent < 0.0 {roundness_percent = 0.0;} else if roundness_percent > 100.0 {roundness_percent = 100.0;}
                 let roundness = (0.01 * roundness_percent) * std::cmp::min::<u32>(width, height) as f32 / 2.0;
-                
-                self.ui_quad.update_uniforms(device, encoder, quad::Uniforms {
-                    window_dimensions: (window_dimensions.0 as f32, window_dimensions.1 as f32).into(),
+
+                self.draw_queue.push_quad(quad::QuadInstance {
+                    top_left: (top_left.0 as f32, top_left.1 as f32).into(),
+                    size: (width as f32, height as f32).into(),
                     color: color.into(),
                     primitive_type: primitive::ROUNDED_RECTANGLE,
-                    center: (
-                        top_left.0 as f32 + width as f32 / 2.0, 
-                        top_left.1 as f32 + height as f32 / 2.0,
-                    ).into(),
-                    circle_radius: 0.0,
-                    primitive_width: width as f32 / 2.0,
-                    primitive_height: height as f32 / 2.0,
-                    rounded_rect_roundness: roundness,
-                });
+                    roundness,
+                }, scissor);
             }
 
-            // Rendering is handled by the TextRenderer -> early return
+            // Rendering is handled by the TextRenderer
             DrawCommand::Text(section) => {
+                if let Some(region) = self.text_renderer.get_section_region(section) {
+                    let region = scissor.map_or(region, |scissor| scissor.intersection(&region));
+
+                    if !region.is_empty() {
+                        self.queued_text.push(region);
+                    }
+                }
+
                 self.text_renderer.queue_section(section);
-                return;
             }
 
             DrawCommand::Image { alias, top_left, width, height } => {
-                self.texture_quad.update_vertices(device, window_dimensions, top_left, width, height);
-                
-                let mut render_pass = Self::create_render_pass(encoder, target);
-                render_pass.set_pipeline(&self.texture_render_pipeline);
-                
-                let texture = self.texture_map.get(alias);
-                self.texture_quad.render(&mut render_pass, texture);
-                return;
+                self.draw_queue.push_image(alias, texture::TextureInstance {
+                    top_left: (top_left.0 as f32, top_left.1 as f32).into(),
+                    size: (width as f32, height as f32).into(),
+                }, scissor);
             }
-        } // match
+        }
+    }
+
+    /// Draws every queued quad and image using a single render pass.
+    ///
+    /// Consecutive commands of the same kind (and clip) are drawn with a single instanced draw call.
+    pub fn flush(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView, window_dimensions: (u32, u32)) {
+        if self.draw_queue.is_empty() {
+            return;
+        }
 
-        let mut render_pass = Self::create_render_pass(encoder, target);
-        render_pass.set_pipeline(&self.quad_render_pipeline);
-        self.ui_quad.render(&mut render_pass);
+        let pipelines = if let Some(pipelines) = &mut self.pipelines {
+            pipelines
+        } else {
+            panic!("A headless renderer cannot draw. Render to a `RenderTarget` instead.");
+        };
+
+        pipelines.ui_quad.update_globals(device, encoder, quad::Globals::new(window_dimensions));
+
+        // Buffers cannot be empty
+        let quad_instance_buffer = if self.draw_queue.quad_instances.is_empty() {
+            None
+        } else {
+            Some(quad::Quad::instance_buffer(device, "quad_instance_buffer", &self.draw_queue.quad_instances))
+        };
+        let texture_instance_buffer = if self.draw_queue.texture_instances.is_empty() {
+            None
+        } else {
+            Some(quad::Quad::instance_buffer(device, "texture_instance_buffer", &self.draw_queue.texture_instances))
+        };
+
+        {
+            let mut render_pass = Self::create_render_pass(encoder, target);
+            render_pass.set_index_buffer(pipelines.ui_quad.index_buffer.slice(..));
+            render_pass.set_vertex_buffer(0, pipelines.ui_quad.vertex_buffer.slice(..));
+
+            for batch in &self.draw_queue.batches {
+                let scissor = batch.scissor.unwrap_or(ClipRect::new((0, 0), window_dimensions.0, window_dimensions.1));
+                render_pass.set_scissor_rect(scissor.top_left.0 as u32, scissor.top_left.1 as u32, scissor.width, scissor.height);
+
+                match &batch.kind {
+                    batch::BatchKind::Quads => {
+                        render_pass.set_pipeline(&pipelines.quad_render_pipeline);
+                        render_pass.set_bind_group(0, &pipelines.ui_quad.globals_bind_group, &[]);
+                        render_pass.set_vertex_buffer(1, quad_instance_buffer.as_ref().unwrap().slice(..));
+                    }
+
+                    batch::BatchKind::Image(alias) => {
+                        render_pass.set_pipeline(&pipelines.texture_render_pipeline);
+                        render_pass.set_bind_group(0, &self.texture_map.get(alias).bind_group, &[]);
+                        render_pass.set_bind_group(1, &pipelines.ui_quad.globals_bind_group, &[]);
+                        render_pass.set_vertex_buffer(1, texture_instance_buffer.as_ref().unwrap().slice(..));
+                    }
+                }
+
+                render_pass.draw_indexed(0..6, 0, batch.instances.clone());
+            }
+        }
+
+        self.draw_queue.clear();
+    }
+
+    /// Draws the queued text, restricted to `clip` (if any).
+    ///
+    /// Quads and images are not drawn (see `flush`), so those should be flushed first.
+    pub fn flush_text(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView, window_dimensions: (u32, u32), clip: Option<ClipRect>) {
+        self.queued_text.clear();
+
+        // Scissor rects must be within the render target
+        let clip = clip.map(|clip| clip.intersection(&ClipRect::new((0, 0), window_dimensions.0, window_dimensions.1)));
+
+        // Nothing is queued while the clip is empty
+        if clip.map_or(false, |clip| clip.is_empty()) {
+            return;
+        }
+
+        self.text_renderer.render_queue(device, target, encoder, window_dimensions.0, window_dimensions.1, clip);
+    }
+
+    /// Returns whether the command (restricted to `clip`) would be drawn on top of text which has not been drawn yet
+    pub fn covers_queued_text(&self, command: &DrawCommand, clip: Option<ClipRect>) -> bool {
+        covers_text(command, clip, &self.queued_text)
+    }
+
+    /// Renders an entire view to `target`, clearing it with the theme's background color first
+    pub fn render_view<Msg: crate::EmptyMessage, S>(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, target: &wgpu::TextureView, target_dimensions: (u32, u32), view: &mut dyn crate::view::View<Msg, S>, theme: &crate::style::Theme) {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("render_encoder"),
+        });
+
+        // Initial frame clear
+        let _ = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[
+                wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(theme.colors.background.into()),
+                        store: true,
+                    },
+                },
+            ],
+            depth_stencil_attachment: None,
+        });
+
+        // Bundle the renderer and context for user-side simplicity
+        let mut renderer_with_context = ContextualRenderer {
+            renderer: self,
+            device,
+            target,
+            encoder: &mut encoder,
+            window_dimensions: target_dimensions,
+            clip_stack: ClipStack::new(),
+        };
+
+        // Render the entire view
+        view.render(&mut renderer_with_context, theme);
+
+        // Draws everything queued since the last layer (or clip) change
+        self.flush(device, &mut encoder, target, target_dimensions);
+
+        // Text is drawn once per frame unless shapes are layered over it or clips change (see `ContextualRenderer`)
+        // This is so wgpu_glyph can cache the text, meaning text should not be drawn per `Section`
+        // Using individual draw calls per `Section` raises CPU usage from <1% to >5% (>22% in debug build)
+        self.flush_text(device, &mut encoder, target, target_dimensions, None);
+
+        // Does everything requested by the ContextualRenderer
+        queue.submit(Some(encoder.finish()));
     }
 
     fn create_render_pass<'a>(encoder: &'a mut wgpu::CommandEncoder, target: &'a wgpu::TextureView) -> wgpu::RenderPass<'a> {
@@ -245,4 +539,47 @@ impl Renderer {
             depth_stencil_attachment: None,
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> DrawCommand<'static> {
+        DrawCommand::Rect { top_left: (x, y), width, height, color: crate::Color::BLACK }
+    }
+
+    #[test]
+    fn shapes_over_queued_text_cover_it() {
+        let text = [ClipRect::new((10, 10), 50, 20)];
+
+        assert!(covers_text(&rect(0, 0, 20, 20), None, &text));
+        assert!(covers_text(&DrawCommand::Circle { center: (70, 20), radius: 15, color: crate::Color::BLACK }, None, &text));
+        assert!(covers_text(&DrawCommand::Image { alias: "image", top_left: (30, 25), width: 10, height: 10 }, None, &text));
+
+        // Any of the queued text
+        assert!(covers_text(&rect(100, 100, 5, 5), None, &[text[0], ClipRect::new((90, 90), 20, 20)]));
+    }
+
+    #[test]
+    fn shapes_beside_queued_text_do_not_cover_it() {
+        let text = [ClipRect::new((10, 10), 50, 20)];
+
+        assert!(!covers_text(&rect(100, 100, 20, 20), None, &text));
+        // Touching edges do not overlap
+        assert!(!covers_text(&rect(60, 10, 20, 20), None, &text));
+        assert!(!covers_text(&rect(10, 0, 50, 10), None, &text));
+        assert!(!covers_text(&rect(0, 0, 20, 20), None, &[]));
+    }
+
+    #[test]
+    fn clipped_shapes_only_cover_text_within_the_clip() {
+        let text = [ClipRect::new((10, 10), 50, 20)];
+
+        // The overlapping part of the shape is clipped away
+        assert!(!covers_text(&rect(0, 0, 100, 100), Some(ClipRect::new((0, 50), 100, 50)), &text));
+        assert!(covers_text(&rect(0, 0, 100, 100), Some(ClipRect::new((0, 20), 100, 50)), &text));
+        // Nothing of the shape is drawn
+        assert!(!covers_text(&rect(0, 0, 100, 100), Some(ClipRect::new((200, 200), 10, 10)), &text));
+    }
+}
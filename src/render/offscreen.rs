@@ -0,0 +1,169 @@
+use wgpu::*;
+
+impl super::Renderer {
+    /// Renders a view into an offscreen texture and reads it back as an image.
+    ///
+    /// The view is initialized and layed out as a root view with the given dimensions.
+    pub fn render_to_image<Msg: crate::EmptyMessage, S>(&mut self, device: &Device, queue: &Queue, view: &mut dyn crate::view::View<Msg, S>, theme: &crate::style::Theme, dimensions: (u32, u32)) -> image::RgbaImage {
+        let (width, height) = dimensions;
+
+        if let Err(error) = crate::view::validate_ids(view) {
+            panic!("{}", error);
+        }
+        view._init(self, theme, true);
+        view.measure(self, theme, crate::view::BoxConstraints::tight(dimensions), true);
+        view.arrange(0, 0);
+
+        let size = Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("offscreen_target"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            // Pipelines are created for this format
+            format: crate::TEXTURE_FORMAT,
+            usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::COPY_SRC,
+        });
+
+        let target = texture.create_view(&TextureViewDescriptor::default());
+
+        self.render_view(device, queue, &target, dimensions, view, theme);
+
+        // Rows of a texture copy must be aligned
+        let unpadded_bytes_per_row = 4 * width;
+        let padding = (COPY_BYTES_PER_ROW_ALIGNMENT - unpadded_bytes_per_row % COPY_BYTES_PER_ROW_ALIGNMENT) % COPY_BYTES_PER_ROW_ALIGNMENT;
+        let bytes_per_row = unpadded_bytes_per_row + padding;
+
+        let output_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("offscreen_output_buffer"),
+            size: (bytes_per_row * height) as BufferAddress,
+            usage: BufferUsage::COPY_DST | BufferUsage::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("offscreen_copy_encoder"),
+        });
+
+        encoder.copy_texture_to_buffer(
+            TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+            },
+            BufferCopyView {
+                buffer: &output_buffer,
+                layout: TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row,
+                    rows_per_image: height,
+                },
+            },
+            size,
+        );
+
+        queue.submit(Some(encoder.finish()));
+
+        let buffer_slice = output_buffer.slice(..);
+        let mapping = buffer_slice.map_async(MapMode::Read);
+        device.poll(Maintain::Wait);
+        futures::executor::block_on(mapping).expect("Failed to read the offscreen texture");
+
+        let mut image = image::RgbaImage::new(width, height);
+        {
+            let data = buffer_slice.get_mapped_range();
+
+            let is_bgra = match crate::TEXTURE_FORMAT {
+                TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+                _ => false,
+            };
+
+            for (y, row) in data.chunks(bytes_per_row as usize).enumerate() {
+                for (x, pixel) in row[..unpadded_bytes_per_row as usize].chunks(4).enumerate() {
+                    let rgba = if is_bgra {
+                        [pixel[2], pixel[1], pixel[0], pixel[3]]
+                    } else {
+                        [pixel[0], pixel[1], pixel[2], pixel[3]]
+                    };
+
+                    image.put_pixel(x as u32, y as u32, image::Rgba(rgba));
+                }
+            }
+        }
+        output_buffer.unmap();
+
+        image
+    }
+}
+
+/// GPU renderer which does not require a window.
+///
+/// Use this to export UI snapshots and thumbnails. Works with software adapters.
+///
+/// Usage:
+/// ```ignore
+/// let mut offscreen = OffscreenRenderer::new(fonts, images);
+/// let snapshot = offscreen.render_to_image(&mut view, &DEFAULT_THEME, (800, 600));
+/// snapshot.save("snapshot.png").unwrap();
+/// ```
+pub struct OffscreenRenderer {
+    device: Device,
+    queue: Queue,
+    pub renderer: super::Renderer,
+}
+
+impl OffscreenRenderer {
+    pub fn new(fonts: super::font::IncludedFonts, images: crate::widget::IncludedImages) -> Self {
+        futures::executor::block_on(
+            Self::new_async(fonts, images)
+        )
+    }
+
+    pub async fn new_async(fonts: super::font::IncludedFonts, images: crate::widget::IncludedImages) -> Self {
+        Self::try_new_async(fonts, images).await.expect("No suitable graphics adapter was found")
+    }
+
+    /// Like `new`, but returns `None` if there is no graphics adapter (such as on CI machines)
+    pub fn try_new(fonts: super::font::IncludedFonts, images: crate::widget::IncludedImages) -> Option<Self> {
+        futures::executor::block_on(
+            Self::try_new_async(fonts, images)
+        )
+    }
+
+    pub async fn try_new_async(fonts: super::font::IncludedFonts, images: crate::widget::IncludedImages) -> Option<Self> {
+        let instance = Instance::new(BackendBit::PRIMARY);
+
+        let adapter = instance.request_adapter(&RequestAdapterOptions {
+            power_preference: PowerPreference::Default,
+            // No window -> no surface
+            compatible_surface: None,
+        }).await?;
+
+        let (device, queue) = adapter.request_device(
+            &DeviceDescriptor {
+                features: Features::empty(),
+                limits: Limits::default(),
+                shader_validation: false,
+            },
+            None,
+        ).await.ok()?;
+
+        let renderer = super::Renderer::new(&device, &queue, fonts, images);
+
+        Some(Self {
+            device,
+            queue,
+            renderer,
+        })
+    }
+
+    pub fn render_to_image<Msg: crate::EmptyMessage, S>(&mut self, view: &mut dyn crate::view::View<Msg, S>, theme: &crate::style::Theme, dimensions: (u32, u32)) -> image::RgbaImage {
+        self.renderer.render_to_image(&self.device, &self.queue, view, theme, dimensions)
+    }
+}
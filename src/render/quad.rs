@@ -1,34 +1,39 @@
 use wgpu::*;
 use wgpu::util::{DeviceExt, BufferInitDescriptor};
 
+/// Uniforms shared by every quad of a frame
 #[repr(C)]
 #[derive(Copy, Clone)]
-pub struct Uniforms {
-    pub color: cgmath::Vector4<f32>,
+pub struct Globals {
     pub window_dimensions: cgmath::Vector2<f32>,
-    pub center: cgmath::Vector2<f32>,
-    pub primitive_type: u32,
-    pub circle_radius: f32,
-    pub primitive_width: f32,
-    pub primitive_height: f32,
-    pub rounded_rect_roundness: f32,
+    // Uniform blocks are 16 byte aligned
+    _padding: cgmath::Vector2<f32>,
 }
 
-unsafe impl bytemuck::Pod for Uniforms {}
-unsafe impl bytemuck::Zeroable for Uniforms {}
+unsafe impl bytemuck::Pod for Globals {}
+unsafe impl bytemuck::Zeroable for Globals {}
+
+impl Globals {
+    pub fn new(window_dimensions: (u32, u32)) -> Self {
+        Self {
+            window_dimensions: (window_dimensions.0 as f32, window_dimensions.1 as f32).into(),
+            _padding: (0.0, 0.0).into(),
+        }
+    }
+}
 
 #[repr(C)]
 #[derive(Copy, Clone)]
-/// repr(C)
-struct QuadVertex {
-    position: cgmath::Vector3<f32>,
+/// Corner of the unit quad which every instance is stretched from
+pub(super) struct QuadVertex {
+    position: cgmath::Vector2<f32>,
 }
 
 unsafe impl bytemuck::Pod for QuadVertex {}
 unsafe impl bytemuck::Zeroable for QuadVertex {}
 
 impl QuadVertex {
-    pub fn new(position: (f32, f32, f32)) -> Self {
+    pub fn new(position: (f32, f32)) -> Self {
         Self {
             position: position.into(),
         }
@@ -39,135 +44,149 @@ impl QuadVertex {
             stride: size_of!(Self),
             step_mode: InputStepMode::Vertex,
             attributes: &vertex_attr_array![
-                // Vertex location
-                0 => Float3
+                // Corner location (0..1, down is +y)
+                0 => Float2
             ],
         }
     }
 }
 
+/// A single rectangle, rounded rectangle, or circle. Positions are in pixels.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct QuadInstance {
+    pub top_left: cgmath::Vector2<f32>,
+    pub size: cgmath::Vector2<f32>,
+    pub color: cgmath::Vector4<f32>,
+    pub primitive_type: u32,
+    /// Corner radius in pixels (the radius for circles)
+    pub roundness: f32,
+}
+
+unsafe impl bytemuck::Pod for QuadInstance {}
+unsafe impl bytemuck::Zeroable for QuadInstance {}
+
+impl QuadInstance {
+    pub fn descriptor<'a>() -> VertexBufferDescriptor<'a> {
+        VertexBufferDescriptor {
+            stride: size_of!(Self),
+            step_mode: InputStepMode::Instance,
+            // NOTE: Locations must be matched with quad.vert
+            // FIXME: Can't use `vertex_attr_array!` for instances (caused by VertexFormat::TYPE.size())
+            attributes: &[
+                VertexAttributeDescriptor {
+                    offset: 0,
+                    format: VertexFormat::Float2,
+                    shader_location: 1,
+                },
+                VertexAttributeDescriptor {
+                    offset: size_of!([f32; 2]),
+                    format: VertexFormat::Float2,
+                    shader_location: 2,
+                },
+                VertexAttributeDescriptor {
+                    offset: size_of!([f32; 4]),
+                    format: VertexFormat::Float4,
+                    shader_location: 3,
+                },
+                VertexAttributeDescriptor {
+                    offset: size_of!([f32; 8]),
+                    format: VertexFormat::Uint,
+                    shader_location: 4,
+                },
+                VertexAttributeDescriptor {
+                    offset: size_of!([f32; 9]),
+                    format: VertexFormat::Float,
+                    shader_location: 5,
+                },
+            ],
+        }
+    }
+}
+
+/// The unit quad and uniforms used by instanced pipelines
 pub struct Quad {
     pub vertex_buffer: Buffer,
-    pub uniform_buffer: Buffer,
     pub index_buffer: Buffer,
-    
-    pub uniform_bind_group: BindGroup,
+    pub globals_buffer: Buffer,
+
+    pub globals_bind_group: BindGroup,
 }
 
 impl Quad {
-    fn vertices_from_rect(window_dimensions: (u32, u32), mut top_left: (i32, i32), width: u32, height: u32) -> [QuadVertex; 4] {
-        // Make top-left (0, 0) where down is +y-axis
-        top_left.1 = window_dimensions.1 as i32 - top_left.1;
-        
-        let quad_top_left = super::screen_space_to_draw_space(top_left, window_dimensions);
-        
-        let top_right = (top_left.0 + width as i32, top_left.1);
-        let quad_top_right = super::screen_space_to_draw_space(top_right, window_dimensions);
-        
-        let bottom_left = (top_left.0, top_left.1 - height as i32);
-        let quad_bottom_left = super::screen_space_to_draw_space(bottom_left, window_dimensions);
-        
-        let bottom_right = (top_left.0 + width as i32, top_left.1 - height as i32);
-        let quad_bottom_right = super::screen_space_to_draw_space(bottom_right, window_dimensions);
-    
-        [
-            QuadVertex::new((quad_top_right.0, quad_top_right.1, 0.0)), // Top right
-            QuadVertex::new((quad_top_left.0, quad_top_left.1, 0.0)), // Top left
-            QuadVertex::new((quad_bottom_left.0, quad_bottom_left.1, 0.0)), // Bottom left
-            QuadVertex::new((quad_bottom_right.0, quad_bottom_right.1, 0.0)), // Bottom right
-        ]
-    }
-
     pub fn new(device: &Device, layout: &BindGroupLayout) -> Self {
         let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("quad_index_buffer"),
-            contents: bytemuck::cast_slice(&[0u32, 1, 2, 0, 2, 3]), 
+            contents: bytemuck::cast_slice(&[0u32, 1, 2, 0, 2, 3]),
             usage: BufferUsage::INDEX,
         });
 
-        let vertices = Self::vertices_from_rect((1, 1), (1, 1), 1, 1);
+        let vertices = [
+            QuadVertex::new((1.0, 0.0)), // Top right
+            QuadVertex::new((0.0, 0.0)), // Top left
+            QuadVertex::new((0.0, 1.0)), // Bottom left
+            QuadVertex::new((1.0, 1.0)), // Bottom right
+        ];
 
         let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("quad_vertex_buffer"),
             contents: bytemuck::cast_slice(&vertices),
-            usage: BufferUsage::VERTEX, // | BufferUsage::COPY_DST,
+            usage: BufferUsage::VERTEX,
         });
 
-        let uniform_data = Uniforms {
-            window_dimensions: (1.0, 1.0).into(),
-            color: (1.0, 0.0, 0.0, 1.0).into(),
-            primitive_type: 0,
-            center: (0.0, 0.0).into(),
-            circle_radius: 0.0,
-            primitive_width: 0.0,
-            primitive_height: 0.0,
-            rounded_rect_roundness: 0.0,
-        };
-
-        let uniform_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("quad_uniform_buffer"),
-            contents: bytemuck::cast_slice(&[uniform_data]),
-            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST, 
+        let globals_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("quad_globals_buffer"),
+            contents: bytemuck::cast_slice(&[Globals::new((1, 1))]),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
         });
 
-        let uniform_bind_group = Self::bind_group(device, layout, &uniform_buffer);
+        let globals_bind_group = Self::bind_group(device, layout, &globals_buffer);
 
         Self {
             vertex_buffer,
-            uniform_buffer,
             index_buffer,
-            uniform_bind_group,
+            globals_buffer,
+            globals_bind_group,
         }
     }
 
-    pub fn update_vertices(&mut self, device: &Device, window_dimensions: (u32, u32), top_left: (i32, i32), width: u32, height: u32) {
-        let vertices = Self::vertices_from_rect(window_dimensions, top_left, width, height);
-        
-        // FIXME: Should I use a staging buffer or map_write() instead?
-        // FIXME: Queue::write_buffer is ideal, but doesn't work
-        self.vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("quad_vertex_buffer"),
-            contents: bytemuck::cast_slice(&vertices),
-            usage: BufferUsage::VERTEX, // BufferUsage::COPY_SRC
-        });
-    }
-
-    pub fn update_uniforms(&mut self, device: &Device, encoder: &mut CommandEncoder, uniforms: Uniforms) {
-        // FIXME: Would map_write() be easier here?
+    pub fn update_globals(&mut self, device: &Device, encoder: &mut CommandEncoder, globals: Globals) {
         // FIXME: Queue::write_buffer is ideal, but doesn't work
 
         let staging_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("quad_uniform_staging_buffer"),
-            contents: bytemuck::cast_slice(&[uniforms]),
+            label: Some("quad_globals_staging_buffer"),
+            contents: bytemuck::cast_slice(&[globals]),
             usage: BufferUsage::COPY_SRC,
         });
 
         encoder.copy_buffer_to_buffer(
-            &staging_buffer, 0, 
-            &self.uniform_buffer, 0, 
-            size_of!(Uniforms)
+            &staging_buffer, 0,
+            &self.globals_buffer, 0,
+            size_of!(Globals)
         );
     }
 
-    // Quad will always outlive the RenderPass
-    pub fn render<'a, 'b>(&'a self, render_pass: &mut RenderPass<'b>) where 'a: 'b {
-        render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-        render_pass.set_index_buffer(self.index_buffer.slice(..));
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.draw_indexed(0..6, 0, 0..1);
+    /// Creates a vertex buffer containing `instances`
+    pub fn instance_buffer<T: bytemuck::Pod>(device: &Device, label: &str, instances: &[T]) -> Buffer {
+        // FIXME: Should I use a staging buffer or map_write() instead?
+        // FIXME: Queue::write_buffer is ideal, but doesn't work
+        device.create_buffer_init(&BufferInitDescriptor {
+            label: Some(label),
+            contents: bytemuck::cast_slice(instances),
+            usage: BufferUsage::VERTEX,
+        })
     }
 
     pub fn bind_group_layout(device: &Device) -> BindGroupLayout {
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             entries: &[
-                // Uniform buffer
+                // Globals
                 BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: ShaderStage::FRAGMENT,
+                    visibility: ShaderStage::VERTEX,
                     ty: BindingType::UniformBuffer {
                         dynamic: false,
-                        // TODO: Is this usage correct?
-                        min_binding_size: BufferSize::new(size_of!(Uniforms)),
+                        min_binding_size: BufferSize::new(size_of!(Globals)),
                     },
                     count: None, // This would specify an array entry's length
                 },
@@ -176,13 +195,13 @@ impl Quad {
         })
     }
 
-    fn bind_group(device: &Device, layout: &BindGroupLayout, uniform_buffer: &Buffer) -> BindGroup {      
+    fn bind_group(device: &Device, layout: &BindGroupLayout, globals_buffer: &Buffer) -> BindGroup {
         device.create_bind_group(&BindGroupDescriptor {
             layout,
             entries: &[
                 BindGroupEntry {
                     binding: 0,
-                    resource: BindingResource::Buffer(uniform_buffer.slice(..)),
+                    resource: BindingResource::Buffer(globals_buffer.slice(..)),
                 },
             ],
             label: Some("quad_bind_group"),
@@ -223,12 +242,12 @@ impl Quad {
                 color_blend: BlendDescriptor {
                     src_factor: BlendFactor::SrcAlpha,
                     dst_factor: BlendFactor::OneMinusSrcAlpha,
-                    operation: BlendOperation::Add,                 
+                    operation: BlendOperation::Add,
                 },
                 alpha_blend: BlendDescriptor {
                     src_factor: BlendFactor::One,
                     dst_factor: BlendFactor::One,
-                    operation: BlendOperation::Add,                 
+                    operation: BlendOperation::Add,
                 },
                 write_mask: ColorWrite::ALL,
             }],
@@ -237,6 +256,7 @@ impl Quad {
                 index_format: IndexFormat::Uint32,
                 vertex_buffers: &[
                     QuadVertex::descriptor(),
+                    QuadInstance::descriptor(),
                 ],
             },
             sample_count: 1,
@@ -244,4 +264,4 @@ impl Quad {
             alpha_to_coverage_enabled: false,
         })
     }
-}
\ No newline at end of file
+}
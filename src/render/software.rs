@@ -0,0 +1,357 @@
+use super::{DrawCommand, RenderTarget, ClipRect, ClipStack};
+
+// Reference: `shaders/ui/quad.frag`
+// The functions below mirror the shader so that CPU and GPU output match
+
+fn sd_circle(point: (f32, f32), center: (f32, f32), radius: f32) -> f32 {
+    ((point.0 - center.0).powi(2) + (point.1 - center.1).powi(2)).sqrt() - radius
+}
+
+fn sd_rounded_rect(point: (f32, f32), center: (f32, f32), half_width: f32, half_height: f32, roundness: f32) -> f32 {
+    let qx = ((point.0 - center.0).abs() - half_width + roundness).max(0.0);
+    let qy = ((point.1 - center.1).abs() - half_height + roundness).max(0.0);
+
+    (qx * qx + qy * qy).sqrt() - roundness
+}
+
+fn alpha_from_dist(dist: f32) -> f32 {
+    (1.0 - dist).min(1.0).max(0.0)
+}
+
+// The GPU renders to an sRGB surface, meaning colors are blended in linear space
+fn srgb_to_linear(value: u8) -> f32 {
+    let value = value as f32 / 255.0;
+
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.min(1.0).max(0.0);
+
+    let srgb = if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+
+    (srgb * 255.0).round() as u8
+}
+
+/// Render target which rasterizes draw commands on the CPU.
+///
+/// Requires a headless `Renderer` (see `Renderer::headless`) for its fonts and images.
+///
+/// Usage:
+/// ```ignore
+/// let mut renderer = Renderer::headless(fonts, images);
+/// let screenshot = render_view_to_image(&mut view, &mut renderer, &DEFAULT_THEME, (800, 600));
+/// screenshot.save("screenshot.png").unwrap();
+/// ```
+pub struct SoftwareRenderer<'a> {
+    renderer: &'a mut super::Renderer,
+    canvas: Canvas,
+}
+
+/// Linear RGBA pixels
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+    clip_stack: ClipStack,
+}
+
+impl Canvas {
+    /// Matches the quad pipeline's blend state
+    fn blend(&mut self, x: i32, y: i32, color: [f32; 4]) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+
+        if let Some(clip) = self.clip_stack.current() {
+            if !clip.contains(x, y) {
+                return;
+            }
+        }
+
+        let destination = &mut self.pixels[(y as u32 * self.width + x as u32) as usize];
+        let alpha = color[3].min(1.0).max(0.0);
+
+        for channel in 0..3 {
+            destination[channel] = color[channel] * alpha + destination[channel] * (1.0 - alpha);
+        }
+        destination[3] = (alpha + destination[3]).min(1.0);
+    }
+}
+
+impl<'a> SoftwareRenderer<'a> {
+    pub fn new(renderer: &'a mut super::Renderer, dimensions: (u32, u32), clear_color: crate::Color) -> Self {
+        let (width, height) = dimensions;
+
+        Self {
+            renderer,
+            canvas: Canvas {
+                width,
+                height,
+                pixels: vec![clear_color.as_array(); (width * height) as usize],
+                clip_stack: ClipStack::new(),
+            },
+        }
+    }
+
+    /// Converts the rendered pixels to an sRGB image
+    pub fn into_image(self) -> image::RgbaImage {
+        let mut image = image::RgbaImage::new(self.canvas.width, self.canvas.height);
+
+        for (pixel, color) in image.pixels_mut().zip(self.canvas.pixels.iter()) {
+            *pixel = image::Rgba([
+                linear_to_srgb(color[0]),
+                linear_to_srgb(color[1]),
+                linear_to_srgb(color[2]),
+                (color[3].min(1.0).max(0.0) * 255.0).round() as u8,
+            ]);
+        }
+
+        image
+    }
+
+    /// Shades every pixel of a quad. `shader` receives the pixel's center and returns its alpha.
+    fn fill_quad<F: Fn((f32, f32)) -> f32>(&mut self, top_left: (i32, i32), width: u32, height: u32, color: crate::Color, shader: F) {
+        for y in top_left.1 .. top_left.1 + height as i32 {
+            for x in top_left.0 .. top_left.0 + width as i32 {
+                let alpha = shader((x as f32 + 0.5, y as f32 + 0.5));
+
+                if alpha > 0.0 {
+                    self.canvas.blend(x, y, [color.r, color.g, color.b, alpha]);
+                }
+            }
+        }
+    }
+
+    fn draw_text(&mut self, section: &glyph_brush::OwnedSection) {
+        use wgpu_glyph::ab_glyph::Font;
+
+        let glyphs = self.renderer.text_renderer.section_glyphs(section);
+        let fonts = self.renderer.text_renderer.fonts();
+        let canvas = &mut self.canvas;
+
+        for section_glyph in glyphs {
+            let color = section.text[section_glyph.section_index].extra.color;
+            let font = &fonts[section_glyph.font_id.0];
+
+            if let Some(outline) = font.outline_glyph(section_glyph.glyph) {
+                let bounds = outline.px_bounds();
+
+                outline.draw(|x, y, coverage| {
+                    canvas.blend(
+                        bounds.min.x as i32 + x as i32,
+                        bounds.min.y as i32 + y as i32,
+                        [color[0], color[1], color[2], color[3] * coverage],
+                    );
+                });
+            }
+        }
+    }
+
+    fn draw_image(&mut self, alias: &str, top_left: (i32, i32), width: u32, height: u32) {
+        // Sampled with bilinear filtering like the texture pipeline's sampler
+        let image = self.renderer.texture_map.get_image(alias);
+        let (image_width, image_height) = image.dimensions();
+
+        let texel = |x: i32, y: i32| -> [f32; 4] {
+            let x = x.max(0).min(image_width as i32 - 1) as u32;
+            let y = y.max(0).min(image_height as i32 - 1) as u32;
+            let image::Rgba([r, g, b, a]) = *image.get_pixel(x, y);
+
+            [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a as f32 / 255.0]
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                // Texel space. The texture pipeline samples v = 1 (the last row) at the top of the quad.
+                // Reference: `shaders/image/texture.vert`
+                let u = (x as f32 + 0.5) / width as f32 * image_width as f32 - 0.5;
+                let v = (1.0 - (y as f32 + 0.5) / height as f32) * image_height as f32 - 0.5;
+
+                let (u0, v0) = (u.floor() as i32, v.floor() as i32);
+                let (fu, fv) = (u - u.floor(), v - v.floor());
+
+                let (c00, c10) = (texel(u0, v0), texel(u0 + 1, v0));
+                let (c01, c11) = (texel(u0, v0 + 1), texel(u0 + 1, v0 + 1));
+
+                let mut color = [0.0; 4];
+                for channel in 0..4 {
+                    let top = c00[channel] * (1.0 - fu) + c10[channel] * fu;
+                    let bottom = c01[channel] * (1.0 - fu) + c11[channel] * fu;
+                    color[channel] = top * (1.0 - fv) + bottom * fv;
+                }
+
+                self.canvas.blend(top_left.0 + x as i32, top_left.1 + y as i32, color);
+            }
+        }
+    }
+}
+
+impl<'a> RenderTarget for SoftwareRenderer<'a> {
+    fn draw(&mut self, command: DrawCommand) {
+        match command {
+            DrawCommand::Rect { top_left, width, height, color } => {
+                self.fill_quad(top_left, width, height, color, |_| color.a);
+            }
+
+            DrawCommand::RoundedRect { top_left, width, height, mut roundness_percent, color } => {
+                // Same as `Renderer::draw`
+                if roundness_percent < 0.0 {roundness_percent = 0.0;} else if roundness_percent > 100.0 {roundness_percent = 100.0;}
+                let roundness = (0.01 * roundness_percent) * std::cmp::min::<u32>(width, height) as f32 / 2.0;
+
+                let center = (
+                    top_left.0 as f32 + width as f32 / 2.0,
+                    top_left.1 as f32 + height as f32 / 2.0,
+                );
+
+                self.fill_quad(top_left, width, height, color, |point| {
+                    let dist = sd_rounded_rect(point, center, width as f32 / 2.0, height as f32 / 2.0, roundness);
+                    alpha_from_dist(dist + roundness.min(0.5))
+                });
+            }
+
+            DrawCommand::Circle { center, radius, color } => {
+                let top_left = (center.0 - radius as i32, center.1 - radius as i32);
+                let center = (center.0 as f32, center.1 as f32);
+
+                self.fill_quad(top_left, radius * 2, radius * 2, color, |point| {
+                    let dist = sd_circle(point, center, radius as f32);
+                    alpha_from_dist(dist + 0.5)
+                });
+            }
+
+            DrawCommand::Text(section) => {
+                self.draw_text(section);
+            }
+
+            DrawCommand::Image { alias, top_left, width, height } => {
+                self.draw_image(alias, top_left, width, height);
+            }
+        }
+    }
+
+    fn push_clip(&mut self, rect: ClipRect) {
+        self.canvas.clip_stack.push(rect);
+    }
+
+    fn pop_clip(&mut self) {
+        self.canvas.clip_stack.pop();
+    }
+}
+
+/// Initializes, lays out, and renders `view` as a root view on the CPU.
+/// The theme's background color is used as the clear color.
+pub fn render_view_to_image<Msg: crate::EmptyMessage, S>(view: &mut dyn crate::view::View<Msg, S>, renderer: &mut super::Renderer, theme: &crate::style::Theme, dimensions: (u32, u32)) -> image::RgbaImage {
+    if let Err(error) = crate::view::validate_ids(view) {
+        panic!("{}", error);
+    }
+    view._init(renderer, theme, true);
+    view.measure(renderer, theme, crate::view::BoxConstraints::tight(dimensions), true);
+    view.arrange(0, 0);
+
+    let mut software_renderer = SoftwareRenderer::new(renderer, dimensions, theme.colors.background);
+    view.render(&mut software_renderer, theme);
+
+    software_renderer.into_image()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    /// Top half red, bottom half blue
+    fn halves() -> image::DynamicImage {
+        image::DynamicImage::ImageRgba8(image::RgbaImage::from_fn(8, 8, |_x, y| {
+            if y < 4 { image::Rgba([255, 0, 0, 255]) } else { image::Rgba([0, 0, 255, 255]) }
+        }))
+    }
+
+    fn resources() -> (crate::render::font::IncludedFonts, crate::widget::IncludedImages) {
+        let (fonts, mut images) = crate::render::headless::test_resources();
+        images.push(("halves", halves()));
+
+        (fonts, images)
+    }
+
+    /// Shapes and images (no text, which is rasterized differently by the GPU's glyph cache)
+    fn shapes_view() -> Stack<(), ()> {
+        VStack! {
+            Button::new("button").color(Color::new(0.2, 0.6, 0.3, 1.0)),
+            Image::new("halves").resource("halves").fit_to_width(40),
+            Image::new("plus").resource("plus").fit_to_width(60),
+        }
+    }
+
+    /// Largest difference of any channel of any pixel
+    fn max_difference(a: &image::RgbaImage, b: &image::RgbaImage) -> u8 {
+        assert_eq!(a.dimensions(), b.dimensions());
+
+        a.pixels().zip(b.pixels())
+            .flat_map(|(a, b)| (0..4).map(move |channel| (a[channel] as i16 - b[channel] as i16).abs() as u8))
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn images_are_sampled_like_the_texture_pipeline() {
+        let (fonts, images) = resources();
+        let mut renderer = crate::render::Renderer::headless(fonts, images);
+
+        let mut software = SoftwareRenderer::new(&mut renderer, (40, 40), Color::BLACK);
+        software.draw(DrawCommand::Image { alias: "halves", top_left: (0, 0), width: 40, height: 40 });
+        let image = software.into_image();
+
+        // v = 1 (the bottom of the image) is at the top of the quad
+        assert_eq!(*image.get_pixel(20, 2), image::Rgba([0, 0, 255, 255]));
+        assert_eq!(*image.get_pixel(20, 37), image::Rgba([255, 0, 0, 255]));
+    }
+
+    /// Set `SURREAL_UPDATE_GOLDEN=1` to regenerate the golden image after an intended change
+    #[test]
+    fn matches_golden_image() {
+        let (fonts, images) = resources();
+        let mut renderer = crate::render::Renderer::headless(fonts, images);
+
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("text").text("Golden"),
+            TextInput::new("input").text("Input").width(150),
+            Button::new("button").text(Text::new("").text("Button")),
+            Image::new("halves").resource("halves").fit_to_width(40),
+        };
+        let image = render_view_to_image(&mut view, &mut renderer, &DEFAULT_THEME, (240, 320));
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/res/golden/software.png");
+        if std::env::var("SURREAL_UPDATE_GOLDEN").is_ok() {
+            image.save(path).unwrap();
+        }
+
+        let golden = image::open(path).unwrap().to_rgba8();
+        assert_eq!(max_difference(&image, &golden), 0, "Software output differs from `{}`", path);
+    }
+
+    /// Run with `cargo test -- --ignored` on a machine with a graphics adapter
+    #[test]
+    #[ignore = "requires a graphics adapter"]
+    fn matches_gpu_output() {
+        let (fonts, images) = resources();
+        let mut offscreen = crate::render::offscreen::OffscreenRenderer::try_new(fonts, images)
+            .expect("No graphics adapter");
+        let gpu = offscreen.render_to_image(&mut shapes_view(), &DEFAULT_THEME, (200, 300));
+
+        let (fonts, images) = resources();
+        let mut renderer = crate::render::Renderer::headless(fonts, images);
+        let cpu = render_view_to_image(&mut shapes_view(), &mut renderer, &DEFAULT_THEME, (200, 300));
+
+        // Allows for rounding differences between GPUs
+        assert!(max_difference(&cpu, &gpu) <= 2);
+    }
+}
@@ -1,22 +1,47 @@
 use wgpu::*;
-use wgpu::util::{DeviceExt, BufferInitDescriptor};
 // use wgpu::Texture as wgpu_Texture;
 
 pub struct TextureMap {
     textures: std::collections::HashMap<&'static str, Texture>,
+    /// Known with or without a GPU (headless maps have no textures)
+    dimensions: std::collections::HashMap<&'static str, (u32, u32)>,
+    /// Decoded images. Only kept by headless maps (for the software renderer)
+    images: std::collections::HashMap<&'static str, image::RgbaImage>,
 }
 
 impl TextureMap {
     pub fn from_images(images: crate::widget::IncludedImages, bind_group_layout: &BindGroupLayout, device: &Device, queue: &Queue) -> Self {
         let mut textures = std::collections::HashMap::new();
+        let mut dimensions = std::collections::HashMap::new();
 
         for (alias, image) in images {
             let texture = Texture::new(image, bind_group_layout, device, queue);
+            dimensions.insert(alias, (texture.width, texture.height));
             textures.insert(alias, texture);
         }
 
         Self {
             textures,
+            dimensions,
+            images: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Keeps the images on the CPU rather than uploading them as textures.
+    /// These can be drawn by the software renderer, but not by the GPU.
+    pub fn headless(images: crate::widget::IncludedImages) -> Self {
+        let images: std::collections::HashMap<_, _> = images.into_iter()
+            .map(|(alias, image)| (alias, image.to_rgba8()))
+            .collect();
+
+        let dimensions = images.iter()
+            .map(|(alias, image)| (*alias, image.dimensions()))
+            .collect();
+
+        Self {
+            textures: std::collections::HashMap::new(),
+            dimensions,
+            images,
         }
     }
 
@@ -28,131 +53,59 @@ impl TextureMap {
         }
     }
 
-    pub fn get_resource_dimensions(&self, alias: &str) -> (u32, u32) {
-        let texture = self.get(alias);
-
-        (texture.width, texture.height)
-    }
-}
-
-// TODO: Uniforms
-pub(super) struct TextureQuad {
-    vertex_buffer: Buffer,
-    index_buffer: Buffer,
-}
-
-impl TextureQuad {
-    // TODO: Account for texture coords
-    fn vertices_from_rect(window_dimensions: (u32, u32), mut top_left: (i32, i32), width: u32, height: u32) -> [TextureVertex; 4] {
-        // Make top-left (0, 0) where down is +y-axis
-        top_left.1 = window_dimensions.1 as i32 - top_left.1;
-        
-        let quad_top_left = super::screen_space_to_draw_space(top_left, window_dimensions);
-        
-        let top_right = (top_left.0 + width as i32, top_left.1);
-        let quad_top_right = super::screen_space_to_draw_space(top_right, window_dimensions);
-        
-        let bottom_left = (top_left.0, top_left.1 - height as i32);
-        let quad_bottom_left = super::screen_space_to_draw_space(bottom_left, window_dimensions);
-        
-        let bottom_right = (top_left.0 + width as i32, top_left.1 - height as i32);
-        let quad_bottom_right = super::screen_space_to_draw_space(bottom_right, window_dimensions);
-    
-        [
-            TextureVertex::new((quad_top_right.0, quad_top_right.1, 0.0), (1.0, 1.0)), // Top right
-            TextureVertex::new((quad_top_left.0, quad_top_left.1, 0.0), (0.0, 1.0)), // Top left
-            TextureVertex::new((quad_bottom_left.0, quad_bottom_left.1, 0.0), (0.0, 0.0)), // Bottom left
-            TextureVertex::new((quad_bottom_right.0, quad_bottom_right.1, 0.0), (1.0, 0.0)), // Bottom right
-        ]
-    }
-
-    pub fn new(device: &Device) -> Self {
-        let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("texture_quad_index_buffer"),
-            contents: bytemuck::cast_slice(&[0u32, 1, 2, 0, 2, 3]), 
-            usage: BufferUsage::INDEX,
-        });
-
-        let vertices = Self::vertices_from_rect((1,1), (1,1), 1, 1);
-
-        let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("quad_vertex_buffer"),
-            contents: bytemuck::cast_slice(&vertices),
-            usage: BufferUsage::VERTEX, // | BufferUsage::COPY_DST,
-        });
-
-        Self {
-            vertex_buffer,
-            index_buffer,
+    /// Returns the decoded image. Only available for headless texture maps.
+    pub fn get_image<'a>(&'a self, alias: &str) -> &'a image::RgbaImage {
+        if let Some(image) = self.images.get(alias) {
+            image
+        } else if self.dimensions.contains_key(alias) {
+            panic!("Image `{}` was uploaded to the GPU. Use a headless renderer to access images on the CPU.", alias);
+        } else {
+            panic!("No such texture exists: `{}`", alias);
         }
     }
 
-    pub fn update_vertices(&mut self, device: &Device, window_dimensions: (u32, u32), top_left: (i32, i32), width: u32, height: u32) {
-        let vertices = Self::vertices_from_rect(window_dimensions, top_left, width, height);
-        
-        // FIXME: Should I use a staging buffer or map_write() instead?
-        // FIXME: Queue::write_buffer is ideal, but doesn't work
-        self.vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("quad_vertex_buffer"),
-            contents: bytemuck::cast_slice(&vertices),
-            usage: BufferUsage::VERTEX, // BufferUsage::COPY_SRC
-        });
-    }
-
-    // Data always outlives render_pass
-    pub fn render<'a, 'b>(&'a mut self, render_pass: &mut RenderPass<'b>, texture: &'a Texture) where 'a: 'b {
-        render_pass.set_bind_group(0, &texture.bind_group, &[]);
-        render_pass.set_index_buffer(self.index_buffer.slice(..));
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.draw_indexed(0..6, 0, 0..1);
+    pub fn get_resource_dimensions(&self, alias: &str) -> (u32, u32) {
+        if let Some(dimensions) = self.dimensions.get(alias) {
+            *dimensions
+        } else {
+            panic!("No such texture exists: `{}`", alias);
+        }
     }
 }
 
-
 #[repr(C)]
 #[derive(Copy, Clone)]
-/// repr(C)
-struct TextureVertex {
-    position: cgmath::Vector3<f32>,
-    texture_coord: cgmath::Vector2<f32>,
+/// Position and size of a drawn image (in pixels)
+pub(super) struct TextureInstance {
+    pub top_left: cgmath::Vector2<f32>,
+    pub size: cgmath::Vector2<f32>,
 }
 
-impl TextureVertex {
-    pub fn new(position: (f32, f32, f32), texture_coord: (f32, f32)) -> Self {
-        Self {
-            position: position.into(),
-            texture_coord: texture_coord.into(),
-        }
-    }
-
+impl TextureInstance {
     pub fn descriptor<'a>() -> VertexBufferDescriptor<'a> {
-        // FIXME: Can't use the macro  (caused by VertexFormat::TYPE.size())
-        // let attributes = vertex_attr_array![
-        //     0 => Float3,
-        //     1 => Float2
-        // ];
-
         VertexBufferDescriptor {
             stride: size_of!(Self),
-            step_mode: InputStepMode::Vertex,
+            step_mode: InputStepMode::Instance,
+            // NOTE: Locations must be matched with texture.vert
+            // FIXME: Can't use `vertex_attr_array!` for instances (caused by VertexFormat::TYPE.size())
             attributes: &[
                 VertexAttributeDescriptor {
                     offset: 0,
-                    format: VertexFormat::Float3,
-                    shader_location: 0,
+                    format: VertexFormat::Float2,
+                    shader_location: 1,
                 },
                 VertexAttributeDescriptor {
-                    offset: size_of!([f32; 3]),
+                    offset: size_of!([f32; 2]),
                     format: VertexFormat::Float2,
-                    shader_location: 1,
+                    shader_location: 2,
                 },
             ],
         }
     }
 }
 
-unsafe impl bytemuck::Pod for TextureVertex {}
-unsafe impl bytemuck::Zeroable for TextureVertex {}
+unsafe impl bytemuck::Pod for TextureInstance {}
+unsafe impl bytemuck::Zeroable for TextureInstance {}
 
 
 // References: https://github.com/sotrh/learn-wgpu/blob/master/code/intermediate/tutorial13-threading/src/model.rs
@@ -165,7 +118,7 @@ pub struct Texture {
     // texture: wgpu_Texture,
     // view: TextureView,
     // sampler: Sampler,
-    bind_group: BindGroup,
+    pub(super) bind_group: BindGroup,
 }
 
 impl Texture {
@@ -284,13 +237,14 @@ impl Texture {
         })
     }
 
-    pub(crate) fn create_render_pipeline(device: &Device, bind_group_layout: &BindGroupLayout) -> RenderPipeline {
+    /// The texture is bound to set 0 and the quad globals (see `quad::Globals`) to set 1
+    pub(crate) fn create_render_pipeline(device: &Device, bind_group_layout: &BindGroupLayout, globals_bind_group_layout: &BindGroupLayout) -> RenderPipeline {
         let vert_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/image/texture.vert.spv"));
         let frag_module = device.create_shader_module(wgpu::include_spirv!("../../shaders/image/texture.frag.spv"));
         
         let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("texture_pipeline_layout"),
-            bind_group_layouts: &[bind_group_layout],
+            bind_group_layouts: &[bind_group_layout, globals_bind_group_layout],
             push_constant_ranges: &[],
         });
         
@@ -330,7 +284,8 @@ impl Texture {
             vertex_state: VertexStateDescriptor {
                 index_format: IndexFormat::Uint32,
                 vertex_buffers: &[
-                    TextureVertex::descriptor(),
+                    super::quad::QuadVertex::descriptor(),
+                    TextureInstance::descriptor(),
                 ],
             },
             sample_count: 1,
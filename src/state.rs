@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::any::Any;
 
 pub type Shared<T> = std::rc::Rc<std::cell::RefCell<T>>;
@@ -6,70 +5,135 @@ pub fn make_shared<T>(value: T) -> Shared<T> {
     std::rc::Rc::new(std::cell::RefCell::new(value))
 }
 
-pub struct State {
-    vars: HashMap<&'static str, Box<dyn Any>>,
+/// Struct holding a view's state variables by name. Implemented by the struct generated by `State!` (and `Stateful!`).
+pub trait Fields {
+    /// The variable with the given name, for code which only knows a variable by name
+    fn field(&self, name: &str) -> Option<&dyn Any>;
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Any>;
 }
 
-impl State {
-    pub fn new() -> Self {
-        State {
-            vars: HashMap::new(),
-        }
-    }
-
-    pub fn add_var(&mut self, id: &'static str, var: Box<dyn Any>) {
-        if let Some(_old) = self.vars.insert(id, var) {
-            panic!("A variable with id `{}` already exists", id);
-        }
-    }
+/// State shared by the views and widgets of a view tree.
+///
+/// `S` is the struct holding the state variables, so `state.fields.name` (which `@name` in `Stateful!` becomes)
+/// is a plain field access, checked at compile time:
+/// ```
+/// use surreal::prelude::*;
+///
+/// let _view: Stack<(), _> = Stateful! {
+///     @State { count: u32 = 0, },
+///     VStack! {
+///         Button::new("add").on_click(|mut state| {
+///             @count += 1;
+///         }),
+///     }
+/// };
+/// ```
+/// A value of the wrong type is a compile error (rather than a panic when the callback runs):
+/// ```compile_fail
+/// use surreal::prelude::*;
+///
+/// let _view: Stack<(), _> = Stateful! {
+///     @State { count: u32 = 0, },
+///     VStack! {
+///         Button::new("add").on_click(|mut state| {
+///             @count += "one";
+///         }),
+///     }
+/// };
+/// ```
+/// As is a misspelled field:
+/// ```compile_fail
+/// use surreal::prelude::*;
+///
+/// let _view: Stack<(), _> = Stateful! {
+///     @State { count: u32 = 0, },
+///     VStack! {
+///         Button::new("add").on_click(|mut state| {
+///             @cuont += 1;
+///         }),
+///     }
+/// };
+/// ```
+pub struct State<S> {
+    pub fields: S,
+}
 
-    fn get_any_mut(&mut self, id: &'static str) -> &mut Box<dyn Any> {
-        if let Some(var) = self.vars.get_mut(id) {
-            var
-        } else {
-            panic!("No such variable exists: `{}`", id);
-        }
-    }
-    
-    fn get_any(&self, id: &'static str) -> &Box<dyn Any> {
-        if let Some(var) = self.vars.get(id) {
-            var
-        } else {
-            panic!("No such variable exists: `{}`", id);
+impl<S> State<S> {
+    pub fn new(fields: S) -> Self {
+        State {
+            fields,
         }
     }
+}
 
+impl<S: Fields> State<S> {
+    /// Variable by name. Prefer `@field` (checked at compile time) where possible.
     pub fn get<T: 'static>(&mut self, id: &'static str) -> &mut T {
-        if let Some(var) = self.get_any_mut(id).downcast_mut::<T>() {
-            var
+        if let Some(var) = self.fields.field_mut(id) {
+            if let Some(var) = var.downcast_mut::<T>() {
+                var
+            } else {
+                panic!("Downcast of `{}` failed. Double check its type.", id);
+            }
         } else {
-            panic!("Downcast of `{}` failed. Double check its type.", id);
+            panic!("No such variable exists: `{}`", id);
         }
     }
 
+    /// Variable by name. Prefer `@field` (checked at compile time) where possible.
     pub fn peek<T: 'static>(&self, id: &'static str) -> &T {
-        if let Some(var) = self.get_any(id).downcast_ref::<T>() {
-            var
+        if let Some(var) = self.fields.field(id) {
+            if let Some(var) = var.downcast_ref::<T>() {
+                var
+            } else {
+                panic!("Downcast of `{}` failed. Double check its type.", id);
+            }
         } else {
-            panic!("Downcast of `{}` failed. Double check its type.", id);
+            panic!("No such variable exists: `{}`", id);
         }
     }
 }
 
-// TODO: It might be worth storing the type information somewhere since it
-//       is required anyway. This can be used for debugging (and perhaps even
-//       for an in-app variable editor).
+/// Creates a `State` from `name: type = initial_value` pairs.
+///
+/// `State! { struct Name; ... }` instead defines the struct holding the variables (named `Name`),
+/// which is then created with `State::new(Name { ... })`. `Stateful!` uses this so `@field` can access the struct directly.
 #[macro_export]
 macro_rules! State {
-    ( $($name:ident : $type:ty = $value:expr),+ $(,)? ) => {{
-        let mut state = state::State::new();
+    ( struct $fields:ident; $($name:ident : $type:ty = $value:expr),+ $(,)? ) => {
+        struct $fields {
+            $( $name: $type, )+
+        }
+
+        impl $crate::state::Fields for $fields {
+            fn field(&self, name: &str) -> Option<&dyn std::any::Any> {
+                $(
+                    if name == stringify!($name) {
+                        return Some(&self.$name);
+                    }
+                )+
 
-        $(
-            // Force proper type (don't want "12" to become an i32 when meant for u32)
-            let __temp: $type = $value;
-            state.add_var(stringify!($name), Box::new(__temp));
-        )+
+                None
+            }
 
-        state
+            fn field_mut(&mut self, name: &str) -> Option<&mut dyn std::any::Any> {
+                $(
+                    if name == stringify!($name) {
+                        return Some(&mut self.$name);
+                    }
+                )+
+
+                None
+            }
+        }
+    };
+
+    ( $($name:ident : $type:ty = $value:expr),+ $(,)? ) => {{
+        $crate::State! { struct Fields; $($name: $type = $value),+ }
+
+        // The field types force the proper type (don't want "12" to become an i32 when meant for u32)
+        $crate::state::State::new(Fields {
+            $( $name: $value, )+
+        })
     }};
-}
\ No newline at end of file
+}
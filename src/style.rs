@@ -57,6 +57,7 @@ pub const DEFAULT_THEME: Theme = Theme {
     colors: Colors {
         primary: Color::ALMOST_WHITE,
         secondary: Color::DARK_GRAY,
+        accent: Color::LIGHT_BLUE,
         background: Color::AUBERGINE,
         text: Color::ALMOST_WHITE,
     },
@@ -74,7 +75,14 @@ pub const DEFAULT_THEME: Theme = Theme {
         internal_padding: Padding {
             vertical: 5,
             horizontal: 5,
-        }
+        },
+
+        focus_ring_thickness: 3,
+
+        dividers: Dividers {
+            thickness: 2,
+            roundness: 100.0,
+        },
     },
 };
 
@@ -113,6 +121,15 @@ pub struct Buttons {
 pub struct Widgets {
     pub buttons: Buttons,
     pub internal_padding: Padding,
+    /// Width of the ring drawn around focused widgets (in pixels)
+    pub focus_ring_thickness: u32,
+    pub dividers: Dividers,
+}
+
+pub struct Dividers {
+    /// Width of the line (in pixels)
+    pub thickness: u32,
+    pub roundness: f32,
 }
 
 pub struct Padding {
@@ -123,7 +140,8 @@ pub struct Padding {
 pub struct Colors {
     pub primary: Color,
     pub secondary: Color,
-    // accent: Color,
+    /// Highlights (such as selected text)
+    pub accent: Color,
     pub background: Color,
     pub text: Color,
 }
\ No newline at end of file
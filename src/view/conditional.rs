@@ -0,0 +1,266 @@
+use crate::state::{Shared, State};
+use crate::Orientation;
+use crate::view_element::*;
+
+use std::cell::RefMut;
+
+use super::{View, Stack, BoxConstraints};
+
+/// Shows one of two groups of elements depending on a condition in the state.
+///
+/// The condition is checked again after every event and message, and the view
+/// is re-laid out when the shown group changes. Hidden elements keep their contents.
+/// Within a `Stack`, the shown elements are laid out as the stack's own (with its alignment and spacing).
+///
+/// Usually created by the `if` syntax of `VStack!` and `HStack!`:
+/// ```ignore
+/// Stateful! {
+///     @State { logged_in: bool = false, },
+///     VStack! {
+///         if |state| @logged_in {
+///             Text::new("welcome").text("Welcome back"),
+///             Button::new("logout"),
+///         } else {
+///             Button::new("login"),
+///         },
+///     }
+/// }
+/// ```
+pub struct Conditional<Msg: EmptyMessage, S> {
+    state: Option<Shared<State<S>>>,
+    condition: Box<dyn Fn(RefMut<State<S>>) -> bool>,
+
+    /// Lays out the shown elements
+    stack: Stack<Msg, S>,
+    /// Elements of the group which is not shown
+    hidden: Vec<ViewElement<Msg, S>>,
+    showing_then: bool,
+    /// Whether each group (`else`, then `if`) has been initialized
+    initialized: [bool; 2],
+}
+
+impl<Msg: EmptyMessage + 'static, S: 'static> Conditional<Msg, S> {
+    /// `then` is shown while the condition is true, and `otherwise` while it is false.
+    /// Groups are stacked in the direction of `orientation`.
+    pub fn new<F>(orientation: Orientation, condition: F, then: Vec<ViewElement<Msg, S>>, otherwise: Vec<ViewElement<Msg, S>>) -> Self
+    where
+        F: Fn(RefMut<State<S>>) -> bool + 'static,
+    {
+        Self {
+            state: None,
+            condition: Box::new(condition),
+            stack: Stack::new(orientation, then),
+            hidden: otherwise,
+            showing_then: true,
+            initialized: [false, false],
+        }
+    }
+
+    /// Shows the group matching the condition. Returns true if the shown group changed.
+    fn update(&mut self) -> bool {
+        let state = match &self.state {
+            Some(state) => state.clone(),
+            // Not part of a stateful view yet
+            None => return false,
+        };
+
+        let condition = (self.condition)(state.borrow_mut());
+        if condition == self.showing_then {
+            return false;
+        }
+
+        std::mem::swap(self.stack.children(), &mut self.hidden);
+        self.showing_then = condition;
+        self.stack.map().unwrap().invalidate();
+
+        true
+    }
+}
+
+impl<Msg: EmptyMessage + 'static, S: 'static> View<Msg, S> for Conditional<Msg, S> {
+    fn state(&self) -> Shared<State<S>> {
+        self.state.as_ref().unwrap().clone()
+    }
+
+    fn assign_state(&mut self, state: Shared<State<S>>) {
+        self.stack.assign_state(state.clone());
+        for child in &mut self.hidden {
+            if let ViewElement::View(view) = child {
+                view.assign_state(state.clone());
+            }
+        }
+
+        self.state = Some(state);
+    }
+
+    fn hooks(&mut self) -> &mut Vec<super::ViewHook<Msg, S>> {
+        self.stack.hooks()
+    }
+
+    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg, S>> {
+        self.stack.children()
+    }
+
+    fn map(&mut self) -> Option<&mut super::ViewMap> {
+        self.stack.map()
+    }
+
+    fn init(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme) {
+        self.update();
+        self.stack.init(renderer, theme);
+
+        // Groups are fully initialized the first time they are shown
+        let group = self.showing_then as usize;
+        if !self.initialized[group] {
+            self.initialized[group] = true;
+
+            for child in self.stack.children() {
+                match child {
+                    ViewElement::Widget(widget) => widget.init(renderer, theme),
+                    ViewElement::View(view) => view._init(renderer, theme, true),
+                }
+            }
+        }
+    }
+
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: BoxConstraints, is_root: bool) -> (u32, u32) {
+        self.stack.measure(renderer, theme, constraints, is_root)
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.stack.translate(dx, dy);
+    }
+
+    fn render_width(&self) -> u32 {
+        self.stack.render_width()
+    }
+
+    fn render_height(&self) -> u32 {
+        self.stack.render_height()
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    fn is_inline(&self) -> bool {
+        true
+    }
+
+    fn hidden_children(&mut self) -> &mut [ViewElement<Msg, S>] {
+        &mut self.hidden
+    }
+
+    /// The condition is checked after every event
+    fn check_if_should_resize(&mut self) -> bool {
+        let changed = self.update();
+        self.stack.check_if_should_resize() | changed
+    }
+
+    fn propogate_message(&mut self, message: &Msg) -> bool {
+        let should_resize = self.stack.propogate_message(message);
+        self.update() | should_resize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::view::{Block, layout_blocks, BoxConstraints};
+
+    crate::State! { struct Flag; flag: bool = true, }
+
+    fn set_flag(view: &mut Stack<(), Flag>, flag: bool) {
+        *view.state().borrow_mut().get::<bool>("flag") = flag;
+    }
+
+    fn stack() -> Stack<(), Flag> {
+        let mut view: Stack<(), Flag> = VStack! {
+            Block::new("a", 100, 10),
+            if |state| *state.get::<bool>("flag") {
+                Block::new("b", 40, 10),
+                Block::new("c", 60, 10),
+            } else {
+                Block::new("d", 20, 10),
+            },
+        };
+        view.assign_state(crate::state::make_shared(crate::state::State::new(Flag { flag: true })));
+
+        view
+    }
+
+    #[test]
+    fn groups_are_shown_by_the_condition() {
+        let mut view = stack();
+        assert_eq!(layout_blocks(&mut view, BoxConstraints::loose((400, 400))).len(), 3);
+        assert!(!view.check_if_should_resize());
+
+        set_flag(&mut view, false);
+        assert!(view.check_if_should_resize());
+        assert_eq!(layout_blocks(&mut view, BoxConstraints::loose((400, 400))), vec![(0, 0, 100, 10), (0, 20, 20, 10)]);
+
+        // Only the shown group can be found
+        assert!(get_widget_by_id::<Block, (), Flag>(&mut view, "d").is_ok());
+        assert!(get_widget_by_id::<Block, (), Flag>(&mut view, "b").is_err());
+
+        set_flag(&mut view, true);
+        assert!(view.check_if_should_resize());
+        assert_eq!(layout_blocks(&mut view, BoxConstraints::loose((400, 400))).len(), 3);
+        assert!(get_widget_by_id::<Block, (), Flag>(&mut view, "b").is_ok());
+        assert!(get_widget_by_id::<Block, (), Flag>(&mut view, "d").is_err());
+
+        // Unchanged conditions don't cause a layout
+        set_flag(&mut view, true);
+        assert!(!view.check_if_should_resize());
+    }
+
+    #[test]
+    fn shown_elements_are_aligned_by_the_surrounding_stack() {
+        let mut view = stack().cross_alignment(CrossAxisAlignment::Center).main_alignment(MainAxisAlignment::SpaceBetween);
+
+        // Spread over the height, and centered on the widest element (rather than within the group)
+        assert_eq!(layout_blocks(&mut view, BoxConstraints::tight((100, 100))), vec![(0, 0, 100, 10), (30, 45, 40, 10), (20, 90, 60, 10)]);
+
+        set_flag(&mut view, false);
+        view.check_if_should_resize();
+        assert_eq!(layout_blocks(&mut view, BoxConstraints::tight((100, 100))), vec![(0, 0, 100, 10), (40, 90, 20, 10)]);
+    }
+
+    #[test]
+    fn ids_of_the_hidden_group_are_validated() {
+        let mut view: Stack<(), Flag> = VStack! {
+            Block::new("a", 100, 10),
+            if |state| *state.get::<bool>("flag") {
+                Block::new("b", 40, 10),
+            } else {
+                Block::new("a", 20, 10),
+            },
+        };
+        view.assign_state(crate::state::make_shared(crate::state::State::new(Flag { flag: true })));
+
+        assert_eq!(ViewMap::ids(&mut view), vec!["a", "b", "a"]);
+        assert!(validate_ids(&mut view).unwrap_err().contains("`a` (2 widgets)"));
+    }
+
+    #[test]
+    fn ids_of_the_hidden_group_are_not_reused() {
+        let mut view = stack();
+        let hidden_id = MutationError::DuplicateId { id: "d".to_string() };
+
+        // "d" is hidden, and not found by id
+        assert!(get_widget_by_id::<Block, (), Flag>(&mut view, "d").is_err());
+        assert_eq!(view.append(Block::new("d", 10, 10).into_element()).err(), Some(hidden_id.clone()));
+        assert_eq!(view.insert_before("a", VStack! { Block::new("d", 10, 10) }.into_element()).err(), Some(hidden_id.clone()));
+        assert_eq!(view.replace("a", Block::new("d", 10, 10).into_element()).err(), Some(hidden_id));
+
+        // Within a nested view as well
+        let mut outer: Stack<(), Flag> = VStack! {
+            Block::new("e", 10, 10),
+        };
+        outer.append(view.into_element()).unwrap();
+        assert!(outer.append(Block::new("d", 10, 10).into_element()).is_err());
+        assert!(outer.replace("e", Block::new("c", 10, 10).into_element()).is_err());
+        assert!(outer.append(Block::new("f", 10, 10).into_element()).is_ok());
+        assert!(validate_ids(&mut outer).is_ok());
+    }
+}
@@ -0,0 +1,105 @@
+/// Smallest and largest size an element may be measured at (in pixels).
+///
+/// `u32::MAX` is used for an unbounded maximum.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoxConstraints {
+    pub min: (u32, u32),
+    pub max: (u32, u32),
+}
+
+impl BoxConstraints {
+    pub const UNBOUNDED: u32 = u32::MAX;
+
+    pub fn new(min: (u32, u32), max: (u32, u32)) -> Self {
+        if min.0 > max.0 || min.1 > max.1 {
+            panic!("Minimum size {:?} is larger than maximum size {:?}", min, max);
+        }
+
+        Self { min, max }
+    }
+
+    /// Exactly `size`
+    pub fn tight(size: (u32, u32)) -> Self {
+        Self::new(size, size)
+    }
+
+    /// Anywhere from nothing up to `max`
+    pub fn loose(max: (u32, u32)) -> Self {
+        Self::new((0, 0), max)
+    }
+
+    /// Any size
+    pub fn unbounded() -> Self {
+        Self::loose((Self::UNBOUNDED, Self::UNBOUNDED))
+    }
+
+    pub fn is_tight(&self) -> bool {
+        self.min == self.max
+    }
+
+    pub fn has_bounded_width(&self) -> bool {
+        self.max.0 != Self::UNBOUNDED
+    }
+
+    pub fn has_bounded_height(&self) -> bool {
+        self.max.1 != Self::UNBOUNDED
+    }
+
+    /// The size closest to `size` which satisfies the constraints
+    pub fn constrain(&self, size: (u32, u32)) -> (u32, u32) {
+        (
+            std::cmp::min(std::cmp::max(size.0, self.min.0), self.max.0),
+            std::cmp::min(std::cmp::max(size.1, self.min.1), self.max.1),
+        )
+    }
+
+    /// Same maximum size without a minimum size
+    pub fn loosen(&self) -> Self {
+        Self::loose(self.max)
+    }
+
+    /// Removes `horizontal` and `vertical` space (such as padding) from both sizes.
+    /// Unbounded sizes stay unbounded.
+    pub fn deflate(&self, horizontal: u32, vertical: u32) -> Self {
+        let shrink = |size: u32, amount: u32| {
+            if size == Self::UNBOUNDED { size } else { size.saturating_sub(amount) }
+        };
+
+        Self {
+            min: (shrink(self.min.0, horizontal), shrink(self.min.1, vertical)),
+            max: (shrink(self.max.0, horizontal), shrink(self.max.1, vertical)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_are_constrained_to_the_minimum_and_maximum() {
+        let constraints = BoxConstraints::new((10, 20), (100, 200));
+
+        assert_eq!(constraints.constrain((5, 300)), (10, 200));
+        assert_eq!(constraints.constrain((50, 50)), (50, 50));
+        assert_eq!(BoxConstraints::unbounded().constrain((5000, 7)), (5000, 7));
+        assert!(BoxConstraints::tight((3, 4)).is_tight());
+        assert_eq!(constraints.loosen(), BoxConstraints::loose((100, 200)));
+    }
+
+    #[test]
+    fn deflating_keeps_unbounded_sizes() {
+        let constraints = BoxConstraints::new((10, 20), (100, BoxConstraints::UNBOUNDED)).deflate(40, 30);
+
+        assert_eq!(constraints.min, (0, 0));
+        assert_eq!(constraints.max, (60, BoxConstraints::UNBOUNDED));
+        assert!(constraints.has_bounded_width());
+        assert!(!constraints.has_bounded_height());
+    }
+
+    #[test]
+    #[should_panic]
+    fn minimum_cannot_exceed_maximum() {
+        BoxConstraints::new((20, 0), (10, 10));
+    }
+}
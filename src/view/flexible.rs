@@ -0,0 +1,198 @@
+use crate::state::{Shared, State};
+use crate::bounding_rect::BoundingRect;
+use crate::view_element::*;
+
+use super::{View, MutationError};
+
+/// How a `Flexible` element is sized by its parent `Stack`
+#[derive(Clone, Copy, Debug)]
+pub struct Flex {
+    /// Share of the stack's free space given to the element (0 -> does not grow)
+    pub grow: f32,
+    /// How much the element shrinks (relative to its size) when the stack overflows (0 -> does not shrink)
+    pub shrink: f32,
+    /// Fill the stack's cross axis (width of a `VStack`, height of an `HStack`)
+    pub stretch: bool,
+    pub min_size: (u32, u32),
+    pub max_size: (u32, u32),
+}
+
+impl Flex {
+    pub fn new() -> Self {
+        Self {
+            grow: 0.0,
+            shrink: 1.0,
+            stretch: false,
+            min_size: (0, 0),
+            max_size: (u32::MAX, u32::MAX),
+        }
+    }
+
+    /// Limits `size` to the minimum and maximum size
+    pub fn clamp(&self, size: (u32, u32)) -> (u32, u32) {
+        (
+            std::cmp::max(std::cmp::min(size.0, self.max_size.0), self.min_size.0),
+            std::cmp::max(std::cmp::min(size.1, self.max_size.1), self.min_size.1),
+        )
+    }
+}
+
+/// Lets a `Stack` grow, shrink, or stretch the contained element.
+///
+/// The element starts at its own size. Remaining space in the stack is split
+/// between the growing elements (by their `grow` factors), and elements shrink
+/// when the stack is too small for its contents.
+///
+/// Usage:
+/// ```ignore
+/// HStack! {
+///     Flexible::new(TextInput::new("search")).fill(),
+///     Button::new("go"),
+/// }
+/// ```
+pub struct Flexible<Msg: EmptyMessage, S> {
+    state: Option<Shared<State<S>>>,
+    /// A single element
+    children: Vec<ViewElement<Msg, S>>,
+
+    hooks: Vec<super::ViewHook<Msg, S>>,
+
+    bounds: BoundingRect,
+    flex: Flex,
+}
+
+impl<Msg: EmptyMessage + 'static, S: 'static> Flexible<Msg, S> {
+    pub fn new<ImplConstraint, E: IntoViewElement<Msg, S, ImplConstraint>>(child: E) -> Self {
+        Self {
+            state: None,
+            children: vec![child.into_element()],
+            hooks: Vec::new(),
+            bounds: BoundingRect::new(),
+            flex: Flex::new(),
+        }
+    }
+
+    /// Share of the free space given to this element. Defaults to 0 (keeps its size).
+    pub fn grow(mut self, factor: f32) -> Self {
+        if factor < 0.0 {
+            panic!("Grow factor cannot be negative (got {})", factor);
+        }
+
+        self.flex.grow = factor;
+        self
+    }
+
+    /// How much this element shrinks when there is not enough space. Defaults to 1.
+    pub fn shrink(mut self, factor: f32) -> Self {
+        if factor < 0.0 {
+            panic!("Shrink factor cannot be negative (got {})", factor);
+        }
+
+        self.flex.shrink = factor;
+        self
+    }
+
+    /// Fills the remaining space of the stack (same as `.grow(1.0)`)
+    pub fn fill(self) -> Self {
+        self.grow(1.0)
+    }
+
+    /// Fills the stack's cross axis (width of a `VStack`, height of an `HStack`)
+    pub fn stretch(mut self) -> Self {
+        self.flex.stretch = true;
+        self
+    }
+
+    pub fn min_size(mut self, width: u32, height: u32) -> Self {
+        self.flex.min_size = (width, height);
+        self
+    }
+
+    pub fn max_size(mut self, width: u32, height: u32) -> Self {
+        self.flex.max_size = (width, height);
+        self
+    }
+}
+
+impl<Msg: EmptyMessage, S> View<Msg, S> for Flexible<Msg, S> where Msg: 'static, S: 'static {
+    fn state(&self) -> Shared<State<S>> {
+        self.state.as_ref().unwrap().clone()
+    }
+
+    fn assign_state(&mut self, state: Shared<State<S>>) {
+        for child in &mut self.children {
+            if let ViewElement::View(view) = child {
+                view.assign_state(state.clone());
+            }
+        }
+
+        self.state = Some(state);
+    }
+
+    fn hooks(&mut self) -> &mut Vec<super::ViewHook<Msg, S>> {
+        &mut self.hooks
+    }
+
+    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg, S>> {
+        &mut self.children
+    }
+
+    fn flex(&self) -> Option<&Flex> {
+        Some(&self.flex)
+    }
+
+    /// Only the first element is laid out, so a second one cannot be added
+    fn can_insert_child(&mut self) -> Result<(), MutationError> {
+        if self.children.is_empty() {
+            Ok(())
+        } else {
+            Err(MutationError::Unsupported { view: "Flexible", reason: "it holds a single element, so insert elements into the surrounding view instead" })
+        }
+    }
+
+    /// Measures the element within the constraints (limited by the min/max size)
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: super::BoxConstraints, _is_root: bool) -> (u32, u32) {
+        let constraints = super::BoxConstraints::new(self.flex.clamp(constraints.min), self.flex.clamp(constraints.max));
+
+        let size = match self.children.first_mut() {
+            Some(ViewElement::View(view)) => view.measure(renderer, theme, constraints, false),
+            Some(ViewElement::Widget(widget)) => {
+                let size = widget.measure(renderer, theme, constraints);
+                widget.place(0, 0);
+                size
+            }
+            // The element was deleted
+            None => (0, 0),
+        };
+
+        // Fixed size elements are placed at the top left of the space they were given
+        let (width, height) = constraints.constrain(size);
+
+        self.bounds.x = 0;
+        self.bounds.y = 0;
+        self.bounds.width = width;
+        self.bounds.height = height;
+
+        (width, height)
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+
+        for child in &mut self.children {
+            match child {
+                ViewElement::View(view) => view.translate(dx, dy),
+                ViewElement::Widget(widget) => widget.translate(dx, dy),
+            }
+        }
+    }
+
+    fn render_width(&self) -> u32 {
+        self.bounds.width
+    }
+
+    fn render_height(&self) -> u32 {
+        self.bounds.height
+    }
+}
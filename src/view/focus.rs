@@ -0,0 +1,230 @@
+use crate::event::{ApplicationEvent, ButtonState, Key, MouseButton};
+
+use super::View;
+
+/// Moves keyboard focus in response to an event, before the view's widgets handle it
+/// (so that a click can interact with the newly focused widget).
+///
+/// Pressing the left mouse button focuses the widget under the cursor (or removes focus),
+/// while Tab and Shift+Tab cycle through the focusable widgets.
+/// `focused` is the id of the widget which has focus. Returns true if focus changed.
+pub(crate) fn handle_focus_event<Msg: crate::EmptyMessage, S>(view: &mut dyn View<Msg, S>, focused: &mut Option<&'static str>, event: &ApplicationEvent) -> bool {
+    match event {
+        ApplicationEvent::MouseButton { state: ButtonState::Pressed, button: MouseButton::Left, position: (x, y) } => {
+            let target = view.focusable_widget_at(*x, *y);
+            set_focus(view, focused, target)
+        }
+
+        ApplicationEvent::Key { key: Key::Tab, state: ButtonState::Pressed, modifiers } => {
+            cycle_focus(view, focused, modifiers.shift)
+        }
+
+        _ => false,
+    }
+}
+
+/// Moves keyboard focus to the widget with the given id (or removes focus if `None`).
+/// Returns true if focus changed.
+fn set_focus<Msg: crate::EmptyMessage, S>(view: &mut dyn View<Msg, S>, focused: &mut Option<&'static str>, id: Option<&'static str>) -> bool {
+    if *focused == id {
+        return false;
+    }
+
+    if let Some(previous) = *focused {
+        // The widget may have been deleted
+        view.set_widget_focus(previous, false);
+    }
+
+    *focused = match id {
+        Some(id) if view.set_widget_focus(id, true) => Some(id),
+        _ => None,
+    };
+
+    true
+}
+
+/// Focuses the next (or previous) focusable widget in tree order, wrapping around.
+/// Returns true if focus changed.
+fn cycle_focus<Msg: crate::EmptyMessage, S>(view: &mut dyn View<Msg, S>, focused: &mut Option<&'static str>, reverse: bool) -> bool {
+    let ids = view.focusable_widgets();
+    if ids.is_empty() {
+        return false;
+    }
+
+    let current = focused.and_then(|focused| ids.iter().position(|id| *id == focused));
+
+    let next = match (current, reverse) {
+        (Some(index), false) => (index + 1) % ids.len(),
+        (Some(index), true) => (index + ids.len() - 1) % ids.len(),
+        (None, false) => 0,
+        (None, true) => ids.len() - 1,
+    };
+
+    set_focus(view, focused, Some(ids[next]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::event::Modifiers;
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Clicked {
+        Ok,
+        Cancel,
+    }
+
+    impl EmptyMessage for Clicked {
+        fn is_message(&self) -> bool {
+            true
+        }
+    }
+
+    /// Buttons within nested stacks, between widgets which can't be focused
+    fn buttons() -> Stack<Clicked, ()> {
+        let mut view = VStack! {
+            Text::new("title").text("Title"),
+            HStack! {
+                Button::new("ok").on_click(|_| Clicked::Ok),
+                Button::new(id::ANONYMOUS),
+                VStack! {
+                    Button::new("cancel").on_click(|_| Clicked::Cancel),
+                },
+            },
+            Button::new("__internal"),
+            Button::new("help"),
+        };
+
+        view.assign_state(crate::state::make_shared(crate::state::State::new(())));
+
+        let mut renderer = crate::render::headless::test_renderer();
+        let mut recorder = crate::render::headless::CommandRecorder::new();
+        recorder.record_view(&mut view, &mut renderer, &DEFAULT_THEME, (300, 300));
+
+        view
+    }
+
+    fn key(key: Key, shift: bool) -> ApplicationEvent {
+        ApplicationEvent::Key { key, state: ButtonState::Pressed, modifiers: Modifiers { shift, ..Modifiers::default() } }
+    }
+
+    fn press(x: i32, y: i32) -> ApplicationEvent {
+        ApplicationEvent::MouseButton { state: ButtonState::Pressed, button: MouseButton::Left, position: (x, y) }
+    }
+
+    fn center(widget: &dyn Widget<Clicked, ()>) -> (i32, i32) {
+        let bounds = widget.focus_bounds().unwrap();
+        (bounds.x + bounds.width as i32 / 2, bounds.y + bounds.height as i32 / 2)
+    }
+
+    /// Center of the button with the given id
+    fn button_center(view: &mut Stack<Clicked, ()>, id: &str) -> (i32, i32) {
+        center(get_widget_by_id::<Button<Clicked, ()>, Clicked, ()>(view, id).unwrap())
+    }
+
+    /// Messages sent by pressing Enter
+    fn activate(view: &mut Stack<Clicked, ()>) -> Vec<Clicked> {
+        let mut message_queue = crate::MessageQueue::new();
+        view.propogate_event(&key(Key::Return, false), &mut message_queue);
+        message_queue.drain().collect()
+    }
+
+    /// Ids focused by pressing the key `count` times
+    fn cycle(view: &mut Stack<Clicked, ()>, focused: &mut Option<&'static str>, shift: bool, count: usize) -> Vec<&'static str> {
+        (0..count).map(|_| {
+            assert!(handle_focus_event(view, focused, &key(Key::Tab, shift)));
+            focused.unwrap()
+        }).collect()
+    }
+
+    #[test]
+    fn tab_cycles_forward_and_wraps_around() {
+        let mut view = buttons();
+        let mut focused = None;
+
+        assert_eq!(cycle(&mut view, &mut focused, false, 4), vec!["ok", "cancel", "help", "ok"]);
+    }
+
+    #[test]
+    fn shift_tab_cycles_backward_and_wraps_around() {
+        let mut view = buttons();
+        let mut focused = None;
+
+        assert_eq!(cycle(&mut view, &mut focused, true, 4), vec!["help", "cancel", "ok", "help"]);
+
+        // Back and forth from the middle
+        focused = None;
+        assert!(set_focus(&mut view, &mut focused, Some("cancel")));
+        assert_eq!(cycle(&mut view, &mut focused, true, 1), vec!["ok"]);
+        assert_eq!(cycle(&mut view, &mut focused, false, 2), vec!["cancel", "help"]);
+    }
+
+    #[test]
+    fn only_one_widget_has_focus() {
+        let mut view = buttons();
+        let mut focused = None;
+
+        // "ok" loses focus to "cancel"
+        cycle(&mut view, &mut focused, false, 2);
+        assert_eq!(activate(&mut view), vec![Clicked::Cancel]);
+
+        // Focusing the focused widget changes nothing
+        assert!(!set_focus(&mut view, &mut focused, Some("cancel")));
+
+        // Focusing a widget which doesn't exist (such as a deleted one) removes focus
+        assert!(set_focus(&mut view, &mut focused, Some("missing")));
+        assert_eq!(focused, None);
+        assert_eq!(activate(&mut view), vec![]);
+    }
+
+    #[test]
+    fn clicking_focuses_the_widget_under_the_cursor() {
+        let mut view = buttons();
+        let mut focused = None;
+
+        let (x, y) = button_center(&mut view, "cancel");
+        assert!(handle_focus_event(&mut view, &mut focused, &press(x, y)));
+        assert_eq!(focused, Some("cancel"));
+
+        // Anonymous widgets can't be focused
+        let (x, y) = match &view.children()[2] {
+            ViewElement::Widget(widget) => center(widget.as_ref()),
+            ViewElement::View(_) => unreachable!(),
+        };
+        assert!(handle_focus_event(&mut view, &mut focused, &press(x, y)));
+        assert_eq!(focused, None);
+
+        let (x, y) = button_center(&mut view, "help");
+        assert!(handle_focus_event(&mut view, &mut focused, &press(x, y)));
+        assert_eq!(focused, Some("help"));
+
+        // Releasing the button and other keys don't move focus
+        let release = ApplicationEvent::MouseButton { state: ButtonState::Released, button: MouseButton::Left, position: (x, y) };
+        assert!(!handle_focus_event(&mut view, &mut focused, &release));
+        assert!(!handle_focus_event(&mut view, &mut focused, &key(Key::Return, false)));
+        assert_eq!(focused, Some("help"));
+
+        // Outside of any widget
+        assert!(handle_focus_event(&mut view, &mut focused, &press(-10, -10)));
+        assert_eq!(focused, None);
+    }
+
+    #[test]
+    fn enter_and_space_activate_the_focused_button() {
+        let mut view = buttons();
+        let mut focused = None;
+
+        // Nothing has focus
+        assert_eq!(activate(&mut view), vec![]);
+
+        cycle(&mut view, &mut focused, false, 1);
+        assert_eq!(activate(&mut view), vec![Clicked::Ok]);
+
+        let mut message_queue = crate::MessageQueue::new();
+        cycle(&mut view, &mut focused, false, 1);
+        view.propogate_event(&key(Key::Space, false), &mut message_queue);
+        view.propogate_event(&key(Key::NumpadEnter, false), &mut message_queue);
+        assert_eq!(message_queue.drain().collect::<Vec<_>>(), vec![Clicked::Cancel, Clicked::Cancel]);
+    }
+}
@@ -0,0 +1,416 @@
+use crate::state::{Shared, State};
+use crate::{Orientation, Alignment};
+use crate::view_element::*;
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use super::{View, Stack, BoxConstraints, MainAxisAlignment, CrossAxisAlignment, MutationError};
+
+/// Stacks one element per item of a `Vec<T>` in the state.
+///
+/// Elements are matched to items by key. When the collection changes, elements of removed items
+/// are dropped, new and changed items are built, and the remaining elements are reused (and moved into the new order).
+///
+/// Items with a duplicate key, or whose element reuses an id of another element, are left out
+/// and reported by `error` (see `id::intern` for creating ids from items). Elements reusing the id of
+/// a widget outside of the `ForEach` are reported by `validate_ids` once the view is initialized.
+///
+/// Usage:
+/// ```ignore
+/// Stateful! {
+///     @State { todos: Vec<String> = Vec::new(), },
+///     VStack! {
+///         ForEach::new(|state| &@todos, |todo: &String| todo.clone(), |todo| {
+///             Text::new("").text(todo)
+///         }),
+///     }
+/// }
+/// ```
+pub struct ForEach<Msg: EmptyMessage, S, T, K> {
+    state: Option<Shared<State<S>>>,
+    /// The collection within the state
+    collection: Box<dyn Fn(&State<S>) -> &Vec<T>>,
+    key: Box<dyn Fn(&T) -> K>,
+    builder: Box<dyn FnMut(&T) -> ViewElement<Msg, S>>,
+
+    /// Lays out the elements
+    stack: Stack<Msg, S>,
+    /// The collection when the elements were last updated
+    items: Vec<T>,
+    /// Key and item of each element
+    entries: Vec<(K, T)>,
+    /// Elements which were built since the view was last initialized
+    uninitialized: HashSet<K>,
+    error: Option<String>,
+}
+
+impl<Msg: EmptyMessage + 'static, S: 'static, T: Clone + PartialEq + 'static, K: Hash + Eq + Clone + std::fmt::Debug + 'static> ForEach<Msg, S, T, K> {
+    /// `collection` gets the collection from the state (such as `|state| &@todos` in `Stateful!`),
+    /// `key` identifies an item (keys must be unique) and `builder` creates an item's element
+    pub fn new<ImplConstraint, E, CollectionFn, KeyFn, BuilderFn>(collection: CollectionFn, key: KeyFn, mut builder: BuilderFn) -> Self
+    where
+        E: IntoViewElement<Msg, S, ImplConstraint>,
+        CollectionFn: Fn(&State<S>) -> &Vec<T> + 'static,
+        KeyFn: Fn(&T) -> K + 'static,
+        BuilderFn: FnMut(&T) -> E + 'static,
+    {
+        Self {
+            state: None,
+            collection: Box::new(collection),
+            key: Box::new(key),
+            builder: Box::new(move |item| builder(item).into_element()),
+            stack: Stack::new(Orientation::Vertical, Vec::new()),
+            items: Vec::new(),
+            entries: Vec::new(),
+            uninitialized: HashSet::new(),
+            error: None,
+        }
+    }
+
+    /// Direction the elements are stacked in. Defaults to `Orientation::Vertical`.
+    /// Resets the alignment, so this should be called before the alignment builders.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.stack = Stack::new(orientation, Vec::new());
+        self
+    }
+
+    pub fn alignment(self, alignment: Alignment) -> Self {
+        self.map_stack(|stack| stack.alignment(alignment))
+    }
+
+    pub fn main_alignment(self, alignment: MainAxisAlignment) -> Self {
+        self.map_stack(|stack| stack.main_alignment(alignment))
+    }
+
+    pub fn cross_alignment(self, alignment: CrossAxisAlignment) -> Self {
+        self.map_stack(|stack| stack.cross_alignment(alignment))
+    }
+
+    /// Describes the items which were left out when the elements were last updated (if any)
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn map_stack<F: FnOnce(Stack<Msg, S>) -> Stack<Msg, S>>(mut self, f: F) -> Self {
+        let stack = std::mem::replace(&mut self.stack, Stack::new(Orientation::Vertical, Vec::new()));
+        self.stack = f(stack);
+        self
+    }
+
+    /// Matches the elements to the collection. Returns true if any elements were added, removed, or moved.
+    fn update(&mut self) -> bool {
+        let state = match &self.state {
+            Some(state) => state.clone(),
+            // Not part of a stateful view yet
+            None => return false,
+        };
+        let state = state.borrow();
+        let items = (self.collection)(&state);
+
+        if *items == self.items {
+            return false;
+        }
+
+        // Elements which can be reused
+        let mut previous: HashMap<K, (T, ViewElement<Msg, S>)> = self.entries.drain(..)
+            .zip(self.stack.children().drain(..))
+            .map(|((key, item), element)| (key, (item, element)))
+            .collect();
+
+        let mut errors = Vec::new();
+        let mut keys = HashSet::with_capacity(items.len());
+        // Key, item, and reused element (if any) of each item with a unique key
+        let mut matched = Vec::with_capacity(items.len());
+
+        for item in items {
+            let key = (self.key)(item);
+
+            if !keys.insert(key.clone()) {
+                errors.push(format!("duplicate key {:?}", key));
+                continue;
+            }
+
+            // Unchanged items keep their elements
+            let element = match previous.remove(&key) {
+                Some((previous_item, element)) if previous_item == *item => Some(element),
+                _ => None,
+            };
+
+            matched.push((key, item, element));
+        }
+
+        // New elements may not reuse the ids of other elements
+        let mut ids: HashSet<&'static str> = matched.iter_mut()
+            .filter_map(|(_, _, element)| element.as_mut())
+            .flat_map(|element| element_ids(element))
+            .collect();
+
+        let mut entries = Vec::with_capacity(matched.len());
+        let mut children = Vec::with_capacity(matched.len());
+
+        for (key, item, element) in matched {
+            let element = match element {
+                Some(element) => element,
+                None => {
+                    let mut element = (self.builder)(item);
+
+                    let new_ids = element_ids(&mut element);
+                    let mut unique = HashSet::with_capacity(new_ids.len());
+                    if let Some(id) = new_ids.iter().find(|id| ids.contains(*id) || !unique.insert(**id)) {
+                        errors.push(format!("id `{}` of the element for key {:?} is already in use", id, key));
+                        continue;
+                    }
+                    ids.extend(new_ids);
+
+                    if let ViewElement::View(view) = &mut element {
+                        view.assign_state(self.state.as_ref().unwrap().clone());
+                    }
+
+                    self.uninitialized.insert(key.clone());
+                    element
+                }
+            };
+
+            entries.push((key, item.clone()));
+            children.push(element);
+        }
+
+        *self.stack.children() = children;
+        self.stack.map().unwrap().invalidate();
+        self.uninitialized.retain(|key| keys.contains(key));
+        self.entries = entries;
+        self.items = items.clone();
+
+        self.error = if errors.is_empty() {
+            None
+        } else {
+            Some(format!("ForEach left out items: {}", errors.join(", ")))
+        };
+
+        true
+    }
+}
+
+/// Ids of the widgets in an element (except anonymous ids)
+fn element_ids<Msg: EmptyMessage, S>(element: &mut ViewElement<Msg, S>) -> Vec<&'static str> {
+    let ids = match element {
+        ViewElement::Widget(widget) => vec![widget.id()],
+        ViewElement::View(view) => super::ViewMap::ids(&mut **view),
+    };
+
+    ids.into_iter().filter(|id| !crate::id::is_anonymous(id)).collect()
+}
+
+impl<Msg: EmptyMessage + 'static, S: 'static, T: Clone + PartialEq + 'static, K: Hash + Eq + Clone + std::fmt::Debug + 'static> View<Msg, S> for ForEach<Msg, S, T, K> {
+    fn state(&self) -> Shared<State<S>> {
+        self.state.as_ref().unwrap().clone()
+    }
+
+    fn assign_state(&mut self, state: Shared<State<S>>) {
+        self.stack.assign_state(state.clone());
+        self.state = Some(state);
+    }
+
+    fn hooks(&mut self) -> &mut Vec<super::ViewHook<Msg, S>> {
+        self.stack.hooks()
+    }
+
+    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg, S>> {
+        self.stack.children()
+    }
+
+    fn map(&mut self) -> Option<&mut super::ViewMap> {
+        self.stack.map()
+    }
+
+    /// Elements are added by adding items to the collection
+    fn can_insert_child(&mut self) -> Result<(), MutationError> {
+        Err(MutationError::Unsupported { view: "ForEach", reason: "elements are created from the collection, so add an item to it instead" })
+    }
+
+    /// Elements are removed by removing items from the collection
+    fn remove_child(&mut self, _index: usize) -> Result<ViewElement<Msg, S>, MutationError> {
+        Err(MutationError::Unsupported { view: "ForEach", reason: "elements are created from the collection, so remove the item from it instead" })
+    }
+
+    /// Elements are changed by changing items in the collection
+    fn replace_child(&mut self, _index: usize, _item: ViewElement<Msg, S>) -> Result<ViewElement<Msg, S>, MutationError> {
+        Err(MutationError::Unsupported { view: "ForEach", reason: "elements are created from the collection, so change the item in it instead" })
+    }
+
+    fn init(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme) {
+        self.update();
+        // The elements are laid out along with the rest of the view
+        self.stack.map().unwrap().take_changed();
+        self.stack.init(renderer, theme);
+
+        // New elements are always initialized (regardless of `Widget::should_reinit_before_layout`)
+        let uninitialized = std::mem::take(&mut self.uninitialized);
+        for ((key, _), child) in self.entries.iter().zip(self.stack.children().iter_mut()) {
+            if !uninitialized.contains(key) {
+                continue;
+            }
+
+            match child {
+                ViewElement::Widget(widget) => widget.init(renderer, theme),
+                ViewElement::View(view) => view._init(renderer, theme, true),
+            }
+        }
+    }
+
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: BoxConstraints, is_root: bool) -> (u32, u32) {
+        self.stack.measure(renderer, theme, constraints, is_root)
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.stack.translate(dx, dy);
+    }
+
+    fn render_width(&self) -> u32 {
+        self.stack.render_width()
+    }
+
+    fn render_height(&self) -> u32 {
+        self.stack.render_height()
+    }
+
+    /// An empty collection takes up no space
+    fn is_hidden(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The collection is checked for changes after every event
+    fn check_if_should_resize(&mut self) -> bool {
+        let changed = self.update();
+        self.stack.check_if_should_resize() | changed
+    }
+
+    fn propogate_message(&mut self, message: &Msg) -> bool {
+        let should_resize = self.stack.propogate_message(message);
+        self.update() | should_resize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::render::headless::{CommandRecorder, RecordedCommand};
+
+    type Todo = (u32, String);
+
+    struct TodoList {
+        todos: Vec<Todo>,
+    }
+
+    fn todos(items: &[(u32, &str)]) -> Vec<Todo> {
+        items.iter().map(|(key, text)| (*key, text.to_string())).collect()
+    }
+
+    fn texts<Msg: EmptyMessage, S>(view: &mut dyn View<Msg, S>) -> Vec<String> {
+        let mut renderer = crate::render::headless::test_renderer();
+        let mut recorder = CommandRecorder::new();
+
+        recorder.record_view(view, &mut renderer, &DEFAULT_THEME, (400, 400)).iter()
+            .filter_map(|command| match command {
+                RecordedCommand::Text(section) => Some(section.text[0].text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Address of the element at `index` (to check whether it was reused)
+    fn element_address(view: &mut ForEach<(), TodoList, Todo, u32>, index: usize) -> usize {
+        match &view.children()[index] {
+            ViewElement::Widget(widget) => &**widget as *const dyn Widget<(), TodoList> as *const () as usize,
+            ViewElement::View(view) => &**view as *const dyn View<(), TodoList> as *const () as usize,
+        }
+    }
+
+    fn for_each(items: &[(u32, &str)]) -> (ForEach<(), TodoList, Todo, u32>, Shared<State<TodoList>>) {
+        let state = crate::state::make_shared(State::new(TodoList { todos: todos(items) }));
+
+        let mut view = ForEach::new(|state: &State<TodoList>| &state.fields.todos, |todo: &Todo| todo.0, |todo: &Todo| Text::new("").text(&todo.1));
+        view.assign_state(state.clone());
+
+        (view, state)
+    }
+
+    #[test]
+    fn elements_follow_the_collection() {
+        let (mut view, state) = for_each(&[(1, "a"), (2, "b"), (3, "c")]);
+        assert_eq!(texts(&mut view), vec!["a", "b", "c"]);
+        assert!(!view.check_if_should_resize());
+
+        let b = element_address(&mut view, 1);
+
+        // Remove, move, and add items
+        state.borrow_mut().fields.todos = todos(&[(2, "b"), (4, "d"), (1, "a")]);
+        assert!(view.check_if_should_resize());
+        assert_eq!(texts(&mut view), vec!["b", "d", "a"]);
+
+        // Unchanged items keep their elements
+        assert_eq!(element_address(&mut view, 0), b);
+
+        state.borrow_mut().fields.todos = Vec::new();
+        assert!(view.check_if_should_resize());
+        assert!(view.is_hidden());
+    }
+
+    #[test]
+    fn changed_items_are_built_again() {
+        let (mut view, state) = for_each(&[(1, "a"), (2, "b")]);
+        assert_eq!(texts(&mut view), vec!["a", "b"]);
+
+        let a = element_address(&mut view, 0);
+
+        state.borrow_mut().fields.todos[1].1 = "changed".to_string();
+        assert!(view.check_if_should_resize());
+        assert_eq!(texts(&mut view), vec!["a", "changed"]);
+        assert_eq!(element_address(&mut view, 0), a);
+    }
+
+    #[test]
+    fn duplicate_keys_are_reported() {
+        let (mut view, _state) = for_each(&[(1, "a"), (2, "b"), (1, "again")]);
+
+        assert_eq!(texts(&mut view), vec!["a", "b"]);
+        assert!(view.error().unwrap().contains("duplicate key 1"));
+    }
+
+    #[test]
+    fn duplicate_ids_are_reported() {
+        let state = crate::state::make_shared(State::new(TodoList { todos: todos(&[(1, "a"), (2, "b")]) }));
+
+        let mut view: ForEach<(), TodoList, Todo, u32> = ForEach::new(|state: &State<TodoList>| &state.fields.todos, |todo: &Todo| todo.0, |todo: &Todo| Text::new("same").text(&todo.1));
+        view.assign_state(state.clone());
+
+        assert_eq!(texts(&mut view), vec!["a"]);
+        assert!(view.error().unwrap().contains("`same`"));
+
+        // The error is cleared once the items can be built
+        state.borrow_mut().fields.todos = todos(&[(2, "b")]);
+        assert!(view.check_if_should_resize());
+        assert_eq!(texts(&mut view), vec!["b"]);
+        assert_eq!(view.error(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "`todo_2` (2 widgets)")]
+    fn ids_already_used_by_the_enclosing_view_are_reported() {
+        let state = crate::state::make_shared(State::new(TodoList { todos: todos(&[(1, "a"), (2, "b")]) }));
+
+        let mut view: Stack<(), TodoList> = VStack! {
+            Text::new("todo_2"),
+            ForEach::new(|state: &State<TodoList>| &state.fields.todos, |todo: &Todo| todo.0, |todo: &Todo| {
+                Text::new(crate::id::intern(format!("todo_{}", todo.0))).text(&todo.1)
+            }),
+        };
+        view.assign_state(state);
+
+        // The elements are only built when the view is initialized.
+        // The second one reuses the id of a widget outside of the `ForEach`.
+        texts(&mut view);
+    }
+}
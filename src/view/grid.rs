@@ -0,0 +1,552 @@
+use crate::state::{Shared, State};
+use crate::view_element::*;
+
+/// Size of a grid row or column
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Track {
+    /// Exact size in pixels
+    Fixed(u32),
+    /// Share of the space left over by the other tracks (relative to the other fractions).
+    /// Fractional tracks are never smaller than their contents.
+    Fraction(f32),
+    /// Sized to fit the largest element in the track
+    Auto,
+}
+
+/// Alignment of an element within its cell
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CellAlignment {
+    Start,
+    Center,
+    End,
+}
+
+impl CellAlignment {
+    /// Offset of an element of size `size` within `available` space
+    fn offset(&self, size: u32, available: u32) -> u32 {
+        match self {
+            CellAlignment::Start => 0,
+            CellAlignment::Center => available.saturating_sub(size) / 2,
+            CellAlignment::End => available.saturating_sub(size),
+        }
+    }
+}
+
+impl From<crate::Alignment> for CellAlignment {
+    fn from(alignment: crate::Alignment) -> Self {
+        match alignment {
+            crate::Alignment::Left => CellAlignment::Start,
+            crate::Alignment::Center => CellAlignment::Center,
+            crate::Alignment::Right => CellAlignment::End,
+        }
+    }
+}
+
+/// Placement of an element within a `Grid`.
+///
+/// `(row, column)` tuples can be used in place of this for single cells with default alignment.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GridCell {
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    /// Defaults to the theme's alignment
+    horizontal_alignment: Option<CellAlignment>,
+    vertical_alignment: CellAlignment,
+}
+
+impl GridCell {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            horizontal_alignment: None,
+            vertical_alignment: CellAlignment::Center,
+        }
+    }
+
+    /// Number of rows covered by the element
+    pub fn row_span(mut self, span: usize) -> Self {
+        if span == 0 {
+            panic!("A grid cell must span at least one row");
+        }
+
+        self.row_span = span;
+        self
+    }
+
+    /// Number of columns covered by the element
+    pub fn column_span(mut self, span: usize) -> Self {
+        if span == 0 {
+            panic!("A grid cell must span at least one column");
+        }
+
+        self.column_span = span;
+        self
+    }
+
+    /// Horizontal alignment within the cell. Defaults to the theme's alignment.
+    pub fn horizontal_alignment(mut self, alignment: CellAlignment) -> Self {
+        self.horizontal_alignment = Some(alignment);
+        self
+    }
+
+    /// Vertical alignment within the cell. Defaults to `CellAlignment::Center`.
+    pub fn vertical_alignment(mut self, alignment: CellAlignment) -> Self {
+        self.vertical_alignment = alignment;
+        self
+    }
+
+    /// Sets both the horizontal and vertical alignment
+    pub fn alignment(self, alignment: CellAlignment) -> Self {
+        self.horizontal_alignment(alignment).vertical_alignment(alignment)
+    }
+}
+
+impl From<(usize, usize)> for GridCell {
+    fn from((row, column): (usize, usize)) -> Self {
+        GridCell::new(row, column)
+    }
+}
+
+/// Arranges elements in rows and columns.
+///
+/// Rows which are not specified are sized automatically.
+/// Gaps between tracks default to the theme's widget padding.
+///
+/// Usage:
+/// ```ignore
+/// Grid! {
+///     columns: [Track::Auto, Track::Fraction(1.0)],
+///     rows: [Track::Auto, Track::Fixed(50)],
+///
+///     (0, 0) => Text::new("label"),
+///     (0, 1) => TextInput::new("input"),
+///     GridCell::new(1, 0).column_span(2).alignment(CellAlignment::Center) => Button::new("submit"),
+/// }
+/// ```
+pub struct Grid<Msg: EmptyMessage, S> {
+    columns: Vec<Track>,
+    rows: Vec<Track>,
+    /// Placement of each child (by index). Children added without a cell are placed automatically (see `placements`).
+    cells: Vec<Option<GridCell>>,
+    /// (horizontal, vertical) space between tracks
+    gap: Option<(u32, u32)>,
+
+    state: Option<Shared<State<S>>>,
+    children: Vec<ViewElement<Msg, S>>,
+
+    hooks: Vec<super::ViewHook<Msg, S>>,
+    map: super::ViewMap,
+
+    bounds: crate::bounding_rect::BoundingRect,
+}
+
+impl<Msg: EmptyMessage, S> Grid<Msg, S> {
+    pub fn new(columns: Vec<Track>, rows: Vec<Track>) -> Self {
+        Self {
+            columns,
+            rows,
+            cells: Vec::new(),
+            gap: None,
+            state: None,
+            children: Vec::new(),
+            hooks: Vec::new(),
+            map: super::ViewMap::new(),
+            bounds: crate::bounding_rect::BoundingRect::new(),
+        }
+    }
+
+    /// Space between columns and between rows (in pixels)
+    pub fn gap(mut self, horizontal: u32, vertical: u32) -> Self {
+        self.gap = Some((horizontal, vertical));
+        self
+    }
+
+    /// Places an element in the grid
+    pub fn push<C: Into<GridCell>>(&mut self, cell: C, element: ViewElement<Msg, S>) {
+        self.cells.push(Some(cell.into()));
+        self.children.push(element);
+    }
+
+    /// Builder version of `push`
+    pub fn cell<C: Into<GridCell>>(mut self, cell: C, element: ViewElement<Msg, S>) -> Self {
+        self.push(cell, element);
+        self
+    }
+
+    /// Placement of every child.
+    /// Children added without a cell (such as by `View::append`) are placed in new rows below the grid.
+    fn placements(&self) -> Vec<GridCell> {
+        let mut next_free_row = self.cells.iter()
+            .flatten()
+            .map(|cell| cell.row + cell.row_span)
+            .max()
+            .unwrap_or(0);
+
+        (0..self.children.len())
+            .map(|index| match self.cells.get(index).copied().flatten() {
+                Some(cell) => cell,
+                None => {
+                    next_free_row += 1;
+                    GridCell::new(next_free_row - 1, 0)
+                }
+            })
+            .collect()
+    }
+
+    /// Resolves the size of each track along one axis.
+    ///
+    /// `spans` contains the (first track, span, element size) of every element along this axis.
+    fn resolve_tracks(tracks: &[Track], spans: &[(usize, usize, u32)], available: u32, gap: u32) -> Vec<u32> {
+        let mut sizes: Vec<u32> = tracks.iter()
+            .map(|track| if let Track::Fixed(size) = track { *size } else { 0 })
+            .collect();
+
+        // Content sizes of single tracks (fractional tracks are never smaller than their contents)
+        for &(start, span, size) in spans {
+            if span == 1 {
+                match tracks[start] {
+                    Track::Auto | Track::Fraction(_) => sizes[start] = std::cmp::max(sizes[start], size),
+                    Track::Fixed(_) => {}
+                }
+            }
+        }
+
+        // Spanning elements grow the auto tracks they cover (if they do not already fit)
+        for &(start, span, size) in spans {
+            if span > 1 {
+                let range = start..start + span;
+                let covered = sizes[range.clone()].iter().sum::<u32>() + gap * (span as u32 - 1);
+                let auto_tracks: Vec<usize> = range.filter(|&index| tracks[index] == Track::Auto).collect();
+
+                if covered < size && !auto_tracks.is_empty() {
+                    let extra = size - covered;
+                    for (i, &index) in auto_tracks.iter().enumerate() {
+                        // Distribute the remainder to the first tracks
+                        let share = extra / auto_tracks.len() as u32 + if (i as u32) < extra % auto_tracks.len() as u32 { 1 } else { 0 };
+                        sizes[index] += share;
+                    }
+                }
+            }
+        }
+
+        // Fractional tracks split the remaining space
+        let total_fraction: f32 = tracks.iter()
+            .map(|track| if let Track::Fraction(fraction) = track { fraction.max(0.0) } else { 0.0 })
+            .sum();
+
+        if total_fraction > 0.0 {
+            let used: u32 = tracks.iter().zip(&sizes)
+                .filter(|(track, _)| if let Track::Fraction(_) = track { false } else { true })
+                .map(|(_, size)| *size)
+                .sum::<u32>() + gap * (tracks.len() as u32).saturating_sub(1);
+            let remaining = available.saturating_sub(used) as f32;
+
+            for (track, size) in tracks.iter().zip(sizes.iter_mut()) {
+                if let Track::Fraction(fraction) = track {
+                    let share = (remaining * fraction.max(0.0) / total_fraction).floor() as u32;
+                    *size = std::cmp::max(*size, share);
+                }
+            }
+        }
+
+        sizes
+    }
+
+    /// Position of each track, starting at 0
+    fn track_offsets(sizes: &[u32], gap: u32) -> Vec<u32> {
+        let mut offsets = Vec::with_capacity(sizes.len());
+        let mut offset = 0;
+
+        for size in sizes {
+            offsets.push(offset);
+            offset += size + gap;
+        }
+
+        offsets
+    }
+
+    /// Total length of the tracks including gaps
+    fn track_length(sizes: &[u32], gap: u32) -> u32 {
+        sizes.iter().sum::<u32>() + gap * (sizes.len() as u32).saturating_sub(1)
+    }
+}
+
+impl<Msg: EmptyMessage, S> super::View<Msg, S> for Grid<Msg, S> where Msg: 'static, S: 'static {
+    fn assign_state(&mut self, state: Shared<State<S>>) {
+        for child in &mut self.children {
+            if let ViewElement::View(view) = child {
+                view.assign_state(state.clone());
+            }
+        }
+
+        self.state = Some(state);
+    }
+
+    fn state(&self) -> Shared<State<S>> {
+        self.state.as_ref().unwrap().clone()
+    }
+
+    fn hooks(&mut self) -> &mut Vec<super::ViewHook<Msg, S>> {
+        &mut self.hooks
+    }
+
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: super::BoxConstraints, is_root: bool) -> (u32, u32) {
+        let (column_gap, row_gap) = self.gap.unwrap_or((theme.widget_padding.horizontal, theme.widget_padding.vertical));
+
+        // Initial padding within window
+        let padding = if is_root {
+            (theme.view_padding.horizontal, theme.view_padding.vertical)
+        } else {
+            (0, 0)
+        };
+        let inner = constraints.deflate(2 * padding.0, 2 * padding.1);
+        let loose = inner.loosen();
+
+        // Fractional tracks fill the maximum size. Without a maximum they only fill the minimum size.
+        let available = (
+            if inner.has_bounded_width() { inner.max.0 } else { inner.min.0 },
+            if inner.has_bounded_height() { inner.max.1 } else { inner.min.1 },
+        );
+
+        let cells = self.placements();
+
+        // Tracks which are not specified are sized automatically
+        let mut columns = self.columns.clone();
+        let mut rows = self.rows.clone();
+        for cell in &cells {
+            while columns.len() < cell.column + cell.column_span {
+                columns.push(Track::Auto);
+            }
+            while rows.len() < cell.row + cell.row_span {
+                rows.push(Track::Auto);
+            }
+        }
+
+        // Measure the elements at their natural size
+        let mut sizes = Vec::with_capacity(self.children.len());
+        for child in &mut self.children {
+            let size = match child {
+                ViewElement::View(view) => view.measure(renderer, theme, loose, false),
+                ViewElement::Widget(widget) => widget.measure(renderer, theme, loose),
+            };
+
+            sizes.push(size);
+        }
+
+        let column_spans: Vec<_> = cells.iter().zip(&sizes).map(|(cell, size)| (cell.column, cell.column_span, size.0)).collect();
+        let row_spans: Vec<_> = cells.iter().zip(&sizes).map(|(cell, size)| (cell.row, cell.row_span, size.1)).collect();
+
+        let column_sizes = Self::resolve_tracks(&columns, &column_spans, available.0, column_gap);
+        let row_sizes = Self::resolve_tracks(&rows, &row_spans, available.1, row_gap);
+
+        let column_offsets = Self::track_offsets(&column_sizes, column_gap);
+        let row_offsets = Self::track_offsets(&row_sizes, row_gap);
+
+        let default_alignment = CellAlignment::from(theme.default_alignment);
+
+        for (child, cell) in self.children.iter_mut().zip(&cells) {
+            let cell_width = Self::track_length(&column_sizes[cell.column..cell.column + cell.column_span], column_gap);
+            let cell_height = Self::track_length(&row_sizes[cell.row..cell.row + cell.row_span], row_gap);
+            let cell_x = padding.0 + column_offsets[cell.column];
+            let cell_y = padding.1 + row_offsets[cell.row];
+
+            // Measured again within the cell (text may wrap to the cell's width)
+            let cell_constraints = super::BoxConstraints::loose((cell_width, cell_height));
+            let (width, height) = match child {
+                ViewElement::View(view) => view.measure(renderer, theme, cell_constraints, false),
+                ViewElement::Widget(widget) => widget.measure(renderer, theme, cell_constraints),
+            };
+
+            let x = cell_x + cell.horizontal_alignment.unwrap_or(default_alignment).offset(width, cell_width);
+            let y = cell_y + cell.vertical_alignment.offset(height, cell_height);
+
+            match child {
+                ViewElement::View(view) => view.arrange(x as i32, y as i32),
+                ViewElement::Widget(widget) => widget.place(x as i32, y as i32),
+            }
+        }
+
+        let (width, height) = constraints.constrain((
+            Self::track_length(&column_sizes, column_gap) + 2 * padding.0,
+            Self::track_length(&row_sizes, row_gap) + 2 * padding.1,
+        ));
+
+        self.bounds.x = 0;
+        self.bounds.y = 0;
+        self.bounds.width = width;
+        self.bounds.height = height;
+
+        (width, height)
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+
+        for child in &mut self.children {
+            match child {
+                ViewElement::Widget(widget) => {
+                    widget.translate(dx, dy);
+                }
+                ViewElement::View(view) => {
+                    view.translate(dx, dy);
+                }
+            }
+        }
+    }
+
+    fn render_width(&self) -> u32 {
+        self.bounds.width
+    }
+
+    fn render_height(&self) -> u32 {
+        self.bounds.height
+    }
+
+    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg, S>> {
+        &mut self.children
+    }
+
+    fn map(&mut self) -> Option<&mut super::ViewMap> {
+        Some(&mut self.map)
+    }
+
+    /// The element is placed below the grid (see `Grid::placements`)
+    fn insert_child(&mut self, index: usize, item: ViewElement<Msg, S>) -> Result<(), super::MutationError> {
+        self.cells.insert(index, None);
+        self.children.insert(index, item);
+
+        Ok(())
+    }
+
+    fn remove_child(&mut self, index: usize) -> Result<ViewElement<Msg, S>, super::MutationError> {
+        self.cells.remove(index);
+        Ok(self.children.remove(index))
+    }
+}
+
+/// Builds a `Grid` from column and row tracks followed by `cell => element` pairs.
+/// Rows may be omitted, in which case they are sized automatically.
+#[macro_export]
+macro_rules! Grid {
+    (
+        columns: [ $($column:expr),* $(,)? ],
+        rows: [ $($row:expr),* $(,)? ],
+        $($cell:expr => $component:expr),+ $(,)?
+    ) => {{
+        let mut grid = Grid::new(vec![$($column),*], vec![$($row),*]);
+
+        $(
+            grid.push($cell, $component.into_element());
+        )+
+
+        grid
+    }};
+
+    (
+        columns: [ $($column:expr),* $(,)? ],
+        $($cell:expr => $component:expr),+ $(,)?
+    ) => {
+        Grid! {
+            columns: [$($column),*],
+            rows: [],
+            $($cell => $component),+
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::view::{Block, layout_blocks};
+
+    fn loose(width: u32, height: u32) -> BoxConstraints {
+        BoxConstraints::loose((width, height))
+    }
+
+    fn start(row: usize, column: usize) -> GridCell {
+        GridCell::new(row, column).alignment(CellAlignment::Start)
+    }
+
+    #[test]
+    fn tracks_are_fixed_fitted_or_fractional() {
+        let mut view: Grid<(), ()> = Grid! {
+            columns: [Track::Fixed(50), Track::Auto, Track::Fraction(1.0), Track::Fraction(3.0)],
+
+            start(0, 0) => Block::new("", 20, 10),
+            start(0, 1) => Block::new("", 30, 20),
+            start(0, 2) => Block::new("", 10, 10),
+            start(0, 3) => Block::new("", 10, 10),
+        }
+        .gap(10, 5);
+
+        // 400 - (50 + 30 + 3 gaps of 10) -> 290 pixels split 1:3
+        assert_eq!(layout_blocks(&mut view, loose(400, 100)), vec![
+            (0, 0, 20, 10),
+            (60, 0, 30, 20),
+            (100, 0, 10, 10),
+            (182, 0, 10, 10),
+        ]);
+        assert_eq!(view.render_size(), (399, 20));
+    }
+
+    #[test]
+    fn spanning_elements_grow_the_auto_tracks_they_cover() {
+        let mut view: Grid<(), ()> = Grid! {
+            columns: [Track::Auto, Track::Auto, Track::Auto],
+
+            start(0, 0) => Block::new("", 40, 10),
+            start(0, 1) => Block::new("", 40, 10),
+            start(1, 0).column_span(2) => Block::new("", 120, 10),
+            start(0, 2).row_span(2) => Block::new("", 10, 40),
+        }
+        .gap(10, 5);
+
+        // The columns grow by 30 (15 each) and the rows by 15 (8 and 7)
+        assert_eq!(layout_blocks(&mut view, loose(400, 400)), vec![
+            (0, 0, 40, 10),
+            (65, 0, 40, 10),
+            (0, 23, 120, 10),
+            (130, 0, 10, 40),
+        ]);
+    }
+
+    #[test]
+    fn children_without_a_cell_are_placed_below_the_grid() {
+        let mut view: Grid<(), ()> = Grid! {
+            columns: [Track::Auto],
+
+            start(0, 0) => Block::new("a", 20, 10),
+        }
+        .gap(10, 5);
+
+        view.append(Block::new("b", 20, 10).into_element()).unwrap();
+        view.append(Block::new("c", 20, 10).into_element()).unwrap();
+        assert_eq!(layout_blocks(&mut view, loose(400, 400)), vec![
+            (0, 0, 20, 10),
+            (0, 15, 20, 10),
+            (0, 30, 20, 10),
+        ]);
+
+        // Removing a child without a cell moves up the ones after it
+        view.delete("b").unwrap();
+        assert_eq!(layout_blocks(&mut view, loose(400, 400)), vec![
+            (0, 0, 20, 10),
+            (0, 15, 20, 10),
+        ]);
+
+        // Removing a child with a cell keeps the cells of the others
+        view.delete("a").unwrap();
+        view.insert_before("c", Block::new("d", 20, 10).into_element()).unwrap();
+        assert_eq!(layout_blocks(&mut view, loose(400, 400)), vec![
+            (0, 0, 20, 10),
+            (0, 15, 20, 10),
+        ]);
+        assert!(get_widget_by_id::<Block, (), ()>(&mut view, "d").is_ok());
+    }
+}
@@ -1,92 +1,251 @@
 pub mod stack;
+pub mod scroll_view;
+pub mod grid;
+pub mod tab_view;
+pub mod flexible;
+pub mod constraints;
+pub mod for_each;
+pub mod conditional;
+mod focus;
 
-pub use stack::Stack;
+pub use stack::{Stack, MainAxisAlignment, CrossAxisAlignment};
+pub use scroll_view::ScrollView;
+pub use grid::{Grid, GridCell, Track, CellAlignment};
+pub use tab_view::TabView;
+pub use flexible::{Flexible, Flex};
+pub use constraints::BoxConstraints;
+pub use for_each::ForEach;
+pub use conditional::Conditional;
+pub(crate) use focus::handle_focus_event;
 
-pub type ViewHook<Msg> = fn(&mut dyn View<Msg>, &Msg);
+/// Called with the view and each message before the message reaches the view's widgets.
+/// Hooks may capture their environment (such as channels or counters).
+pub type ViewHook<Msg, S> = Box<dyn FnMut(&mut dyn View<Msg, S>, &Msg)>;
 
-// TODO: Integrate this. Also see if `get_by_id` can work for this type
-// struct ViewMap<Msg: crate::EmptyMessage> {
-//     // Map of element id to a sequence of child indices
-//     index_sequence: std::collections::HashMap<&'static str, Vec<usize>>,
-//     // Ordered list of child elements
-//     pub children: Vec<crate::ViewElement<Msg>>,
-// }
+/// Index of the widgets in a view tree by id.
+///
+/// Each id maps to the sequence of child indices leading to the widget, so lookups
+/// don't walk the tree. The map is rebuilt by `get_widget_by_id` after it was invalidated.
+pub struct ViewMap {
+    paths: std::collections::HashMap<&'static str, Vec<usize>>,
+    /// The paths match the tree (see `invalidate`)
+    is_valid: bool,
+    /// The tree changed since the view was last laid out
+    changed: bool,
+    /// The tree changed since the parent view's map was updated (see `mark_stale_if_nested_changed`)
+    changed_for_parent: bool,
+}
 
-// VStack, HStack, ListView, and more can all be created using just the `Stack` struct,
-// but other views may be desired such as TabView, GridView, ScrollView, and so on
-pub trait View<Msg: crate::EmptyMessage> {
-    fn state(&self) -> crate::state::Shared<crate::state::State>;
-    /// Assigns the state to all views in the view tree
-    fn assign_state(&mut self, state: crate::state::Shared<crate::state::State>);
+impl ViewMap {
+    /// An invalid map, which is built on the first lookup
+    pub fn new() -> Self {
+        Self { paths: std::collections::HashMap::new(), is_valid: false, changed: false, changed_for_parent: false }
+    }
 
-    // TODO: See ViewMap
-    // fn map(&mut self) -> &mut ViewMap<Msg>;
-    // fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg>> {
-    //     &mut self.map().children
-    // }
+    /// Indexes every widget with an id in `view`. If an id is used more than once, the first widget (in tree order) is used.
+    /// The maps of nested views are rebuilt as well.
+    pub fn build<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V) -> Self {
+        let mut map = Self::new();
 
-    fn delete(&mut self, id: &str) -> Result<(), String> {
-        let length = self.children().len();
-        let mut index = 0;
+        for (index, child) in view.children().iter_mut().enumerate() {
+            map.add_element(index, child);
+        }
 
-        for item in self.children() {
-            match item {
-                crate::ViewElement::Widget(widget) => {
-                    if widget.id() == id {
-                        break;
-                    }
-                    index += 1;
+        map.is_valid = true;
+        map
+    }
+
+    /// Adds the paths of the widgets in the element at `index` (keeping existing paths of duplicate ids).
+    /// The maps of nested views are rebuilt as well.
+    fn add_element<Msg: crate::EmptyMessage, S>(&mut self, index: usize, element: &mut crate::ViewElement<Msg, S>) {
+        match element {
+            crate::ViewElement::Widget(widget) => {
+                if !crate::id::is_anonymous(widget.id()) {
+                    self.paths.entry(widget.id()).or_insert_with(|| vec![index]);
                 }
-                crate::ViewElement::View(view) => {
-                    //TODO: todo!("`delete` handle View")
-                    index += 1;
-                    continue;
+            }
+            crate::ViewElement::View(view) => {
+                let nested = Self::build(&mut **view);
+
+                for (id, path) in &nested.paths {
+                    self.paths.entry(*id).or_insert_with(|| std::iter::once(index).chain(path.iter().copied()).collect());
+                }
+
+                if let Some(nested_map) = view.map() {
+                    nested_map.paths = nested.paths;
+                    nested_map.is_valid = true;
+                    nested_map.changed_for_parent = false;
                 }
             }
+        }
+    }
 
-            if index == length { return Err(format!("Item `{}` was not found", id)) }
+    /// Ids of every widget in `view` in tree order (including duplicates and anonymous ids).
+    /// The ids of hidden elements (see `View::hidden_children`) follow those of each view's children.
+    pub fn ids<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V) -> Vec<&'static str> {
+        fn add<Msg: crate::EmptyMessage, S>(elements: &mut [crate::ViewElement<Msg, S>], ids: &mut Vec<&'static str>) {
+            for element in elements {
+                match element {
+                    crate::ViewElement::Widget(widget) => ids.push(widget.id()),
+                    crate::ViewElement::View(view) => ids.append(&mut ViewMap::ids(&mut **view)),
+                }
+            }
         }
 
-        self.children().remove(index);
-        Ok(())
+        let mut ids = Vec::new();
+        add(view.children(), &mut ids);
+        add(view.hidden_children(), &mut ids);
+
+        ids
+    }
+
+    /// Called when elements are added, removed, or moved.
+    /// The map is rebuilt on the next lookup, and the view is re-initialized and re-laid out.
+    pub fn invalidate(&mut self) {
+        self.paths.clear();
+        self.is_valid = false;
+        self.changed = true;
+        self.changed_for_parent = true;
     }
 
-    /// Appends a `ViewElement` to the end of the view
-    // TODO: Need to signal re-layout
-    fn append(&mut self, item: crate::ViewElement<Msg>) {
-        // TODO: Ensure id is unique
-        // TODO: Add type-check info whenever that is added
-        self.children().push(item);
+    /// Called when the elements of a nested view changed. The map is rebuilt on the next lookup,
+    /// but the view is not re-laid out for it (the nested view reports its own changes).
+    pub fn mark_stale(&mut self) {
+        self.paths.clear();
+        self.is_valid = false;
+        self.changed_for_parent = true;
     }
 
-    /// Inserts a `ViewElement` after the specified item (by id).
-    // TODO: Need to signal re-layout
-    fn insert_after(&mut self, after_id: &str, item: crate::ViewElement<Msg>) -> Result<(), String> {
-        let length = self.children().len();
-        let mut index = 0;
+    pub fn is_valid(&self) -> bool {
+        self.is_valid
+    }
 
-        for child in self.children() {
-            match child {
-                crate::ViewElement::Widget(widget) => {
-                    index += 1;
-                    if widget.id() == after_id {
-                        break;
-                    }
-                }
-                crate::ViewElement::View(view) => {
-                    todo!("`insert_after` handle View")
-                }
-            }
+    /// Whether the tree changed since the last call
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.changed, false)
+    }
+
+    /// Child indices leading to the widget
+    pub fn path(&self, id: &str) -> Option<&[usize]> {
+        self.paths.get(id).map(|path| path.as_slice())
+    }
+}
+
+// VStack, HStack, ListView, and more can all be created using just the `Stack` struct,
+// but other views may be desired such as TabView, Grid, ScrollView, and so on
+pub trait View<Msg: crate::EmptyMessage, S> {
+    fn state(&self) -> crate::state::Shared<crate::state::State<S>>;
+    /// Assigns the state to all views in the view tree
+    fn assign_state(&mut self, state: crate::state::Shared<crate::state::State<S>>);
+
+    /// Widget index used by `get_widget_by_id` (`None` -> lookups walk the tree)
+    fn map(&mut self) -> Option<&mut ViewMap> {
+        None
+    }
+
+    // Elements are found by the id of a widget anywhere in the view tree.
+    // After a change, the view re-initializes and re-lays out its tree (see `ViewMap::invalidate`).
+
+    /// Removes the widget with the given id
+    fn delete(&mut self, id: &str) -> Result<(), MutationError> {
+        self.remove(id).map(|_| ())
+    }
+
+    /// Removes the widget with the given id and returns it (such as to insert it elsewhere)
+    fn remove(&mut self, id: &str) -> Result<crate::ViewElement<Msg, S>, MutationError> {
+        let (parent, index) = locate(self, id)?;
+        let item = remove_at(self, &parent, index)?;
 
-            // Not found
-            if index == length { return Err(format!("Item `{}` was not found", after_id)) }
+        invalidate(self, &parent);
+        Ok(item)
+    }
+
+    /// Appends a `ViewElement` to the end of the view.
+    /// Fails if any of the item's ids is already used in the view.
+    fn append(&mut self, mut item: crate::ViewElement<Msg, S>) -> Result<(), MutationError> {
+        check_unique_ids(self, &mut item, None)?;
+
+        let index = self.children().len();
+        self.insert_child(index, item)?;
+
+        // Other paths are unchanged, so a valid map only needs the new element's paths
+        let mut added = ViewMap::new();
+        added.add_element(index, &mut self.children()[index]);
+
+        match self.map() {
+            Some(map) if map.is_valid() => {
+                map.paths.extend(added.paths);
+                map.changed = true;
+                map.changed_for_parent = true;
+            }
+            Some(map) => map.invalidate(),
+            None => {}
         }
 
+        Ok(())
+    }
+
+    /// Inserts a `ViewElement` before the specified widget (by id).
+    /// Fails if any of the item's ids is already used in the view.
+    fn insert_before(&mut self, before_id: &str, item: crate::ViewElement<Msg, S>) -> Result<(), MutationError> {
+        insert_relative(self, before_id, 0, item)
+    }
+
+    /// Inserts a `ViewElement` after the specified widget (by id).
+    /// Fails if any of the item's ids is already used in the view.
+    fn insert_after(&mut self, after_id: &str, item: crate::ViewElement<Msg, S>) -> Result<(), MutationError> {
+        insert_relative(self, after_id, 1, item)
+    }
+
+    /// Replaces the widget with the given id and returns it. The item may reuse the replaced widget's id.
+    fn replace(&mut self, id: &str, mut item: crate::ViewElement<Msg, S>) -> Result<crate::ViewElement<Msg, S>, MutationError> {
+        let (parent, index) = locate(self, id)?;
+        check_unique_ids(self, &mut item, Some(id))?;
+
+        let old = match child_view(self, &parent) {
+            Some(view) => view.replace_child(index, item)?,
+            None => self.replace_child(index, item)?,
+        };
+
+        invalidate(self, &parent);
+        Ok(old)
+    }
+
+    /// Moves the widget with the given id to just before another widget
+    fn move_before(&mut self, id: &str, before_id: &str) -> Result<(), MutationError> {
+        move_relative(self, id, before_id, 0)
+    }
+
+    /// Moves the widget with the given id to just after another widget
+    fn move_after(&mut self, id: &str, after_id: &str) -> Result<(), MutationError> {
+        move_relative(self, id, after_id, 1)
+    }
+
+    /// Inserts a child at `index`. Views which keep information about each child override this.
+    fn insert_child(&mut self, index: usize, item: crate::ViewElement<Msg, S>) -> Result<(), MutationError> {
+        self.can_insert_child()?;
         self.children().insert(index, item);
+
         Ok(())
     }
 
-    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg>>;
+    /// Returns an error if the view does not accept new children (such as a `ScrollView`, which holds a single view).
+    /// Checked before an element is moved into the view, so it is not lost if the view refuses it.
+    fn can_insert_child(&mut self) -> Result<(), MutationError> {
+        Ok(())
+    }
+
+    /// Removes the child at `index`. Views which keep information about each child override this.
+    fn remove_child(&mut self, index: usize) -> Result<crate::ViewElement<Msg, S>, MutationError> {
+        Ok(self.children().remove(index))
+    }
+
+    /// Replaces the child at `index` (keeping any information the view has about that child)
+    fn replace_child(&mut self, index: usize, item: crate::ViewElement<Msg, S>) -> Result<crate::ViewElement<Msg, S>, MutationError> {
+        Ok(std::mem::replace(&mut self.children()[index], item))
+    }
+
+    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg, S>>;
 
     fn translate(&mut self, dx: i32, dy: i32);
 
@@ -112,9 +271,20 @@ pub trait View<Msg: crate::EmptyMessage> {
                 }
             }
         }
+
+        mark_stale_if_nested_changed(self);
     }
 
-    fn layout(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: (u32, u32), is_root: bool);
+    /// First layout pass: lays out the view's contents with its top left corner at the origin
+    /// and returns the view's size (within `constraints`). The view is then positioned with `arrange`.
+    ///
+    /// Views lay out their children the same way: each child is measured, then arranged.
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: BoxConstraints, is_root: bool) -> (u32, u32);
+
+    /// Second layout pass: moves the measured view (and its contents) from the origin to (x, y)
+    fn arrange(&mut self, x: i32, y: i32) {
+        self.translate(x, y);
+    }
 
     fn render_width(&self) -> u32;
     fn render_height(&self) -> u32;
@@ -122,10 +292,32 @@ pub trait View<Msg: crate::EmptyMessage> {
         (self.render_width(), self.render_height())
     }
 
+    /// How the view grows and shrinks within a `Stack` (`None` -> the view keeps its size)
+    fn flex(&self) -> Option<&Flex> {
+        None
+    }
+
+    /// Hidden views take up no space in a `Stack` (including the space between elements)
+    fn is_hidden(&self) -> bool {
+        false
+    }
+
+    /// Whether a surrounding `Stack` lays out the view's children as its own (such as those of a `Conditional`).
+    /// The view is then measured by the stack instead.
+    fn is_inline(&self) -> bool {
+        false
+    }
+
+    /// Elements which are not currently among the children, such as the hidden group of a `Conditional`.
+    /// Their ids must be unique as well (see `validate_ids`).
+    fn hidden_children(&mut self) -> &mut [crate::ViewElement<Msg, S>] {
+        &mut []
+    }
+
     // TODO: Should views serve only as containers?
     // Implementing this as part of the trait will not allow otherwise.
     // Might want to allow backgrounds or outlines for views.
-    fn render(&mut self, renderer: &mut crate::render::ContextualRenderer, theme: &crate::style::Theme) {
+    fn render(&mut self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme) {
         use crate::ViewElement::*;
         for child in self.children() {
             match child {
@@ -150,7 +342,7 @@ pub trait View<Msg: crate::EmptyMessage> {
         for child in self.children() {
             match child {
                 View(view) => {
-                    view.propogate_event(event, message_queue);
+                    redraw |= view.propogate_event(event, message_queue);
                 }
 
                 Widget(widget) => {
@@ -168,11 +360,105 @@ pub trait View<Msg: crate::EmptyMessage> {
         redraw
     }
 
+    /// Returns true if any widget requested resize (such as after handling an event).
+    /// Resets the widgets' resize requests.
+    fn check_if_should_resize(&mut self) -> bool {
+        // The tree was changed (see `View::delete`)
+        let mut should_resize = self.map().map_or(false, |map| map.take_changed());
+
+        for child in self.children() {
+            match child {
+                crate::ViewElement::View(view) => {
+                    should_resize |= view.check_if_should_resize();
+                }
+
+                crate::ViewElement::Widget(widget) => {
+                    should_resize |= widget.check_if_should_resize_then_reset_to_false();
+                }
+            }
+        }
+
+        mark_stale_if_nested_changed(self);
+        should_resize
+    }
+
+    /// Ids of the focusable widgets in tree order (see `Widget::is_focusable`)
+    fn focusable_widgets(&mut self) -> Vec<&'static str> {
+        let mut ids = Vec::new();
+
+        for child in self.children() {
+            match child {
+                crate::ViewElement::View(view) => {
+                    ids.append(&mut view.focusable_widgets());
+                }
+
+                crate::ViewElement::Widget(widget) => {
+                    if widget.is_focusable() && !crate::id::is_anonymous(widget.id()) {
+                        ids.push(widget.id());
+                    }
+                }
+            }
+        }
+
+        ids
+    }
+
+    /// Returns the id of the focusable widget containing the point, if any
+    fn focusable_widget_at(&mut self, x: i32, y: i32) -> Option<&'static str> {
+        for child in self.children() {
+            match child {
+                crate::ViewElement::View(view) => {
+                    let result = view.focusable_widget_at(x, y);
+                    if result.is_some() {
+                        return result;
+                    }
+                }
+
+                crate::ViewElement::Widget(widget) => {
+                    if widget.is_focusable() && !crate::id::is_anonymous(widget.id()) && widget.contains_point(x, y) {
+                        return Some(widget.id());
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Calls `on_focus` (or `on_blur`) on the widget with the given id.
+    /// Returns false if the widget was not found.
+    fn set_widget_focus(&mut self, id: &str, has_focus: bool) -> bool {
+        for child in self.children() {
+            match child {
+                crate::ViewElement::View(view) => {
+                    if view.set_widget_focus(id, has_focus) {
+                        return true;
+                    }
+                }
+
+                crate::ViewElement::Widget(widget) => {
+                    if widget.id() == id {
+                        if has_focus {
+                            widget.on_focus();
+                        } else {
+                            widget.on_blur();
+                        }
+
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
     // Returns true if the view should resize
     fn propogate_message(&mut self, message: &Msg) -> bool {
         let state = self.state();
 
-        let mut should_resize = false;
+        // The tree was changed by the hook (see `View::delete`)
+        let mut should_resize = self.map().map_or(false, |map| map.take_changed());
 
         for child in self.children() {
             match child {
@@ -188,56 +474,758 @@ pub trait View<Msg: crate::EmptyMessage> {
             }
         }
 
+        mark_stale_if_nested_changed(self);
         should_resize
     }
 
-    // FIXME: I want hook to be FnMut, but I can only do this if I require the
-    // function to come in here as Box<FnMut>.
-    // This would be fixed via generic parameters, but traits don't allow that.
-    fn set_hook(&mut self, hook: ViewHook<Msg>);
-    fn get_hook(&self) -> Option<&ViewHook<Msg>>;
+    /// Hooks of this view, called in order (see `call_hooks`)
+    fn hooks(&mut self) -> &mut Vec<ViewHook<Msg, S>>;
+
+    /// Replaces the view's hooks with `hook`.
+    /// Called by one of the view's hooks, this replaces the running hooks once they are done (the rest of them are still called this time).
+    fn set_hook(&mut self, hook: ViewHook<Msg, S>) {
+        *self.hooks() = vec![hook];
+    }
+
+    /// Adds a hook, which is called after the view's other hooks
+    fn add_hook(&mut self, hook: ViewHook<Msg, S>) {
+        self.hooks().push(hook);
+    }
 }
 
-pub(crate) fn call_hook<Msg: crate::EmptyMessage>(view: &mut dyn View<Msg>, message: &Msg) {
-    if let Some(hook) = view.get_hook() {
+/// Calls the hooks of the view, then the hooks of its nested views (in tree order)
+pub(crate) fn call_hooks<Msg: crate::EmptyMessage + 'static, S: 'static>(view: &mut dyn View<Msg, S>, message: &Msg) {
+    // Hooks are taken out of the view while they run, since they receive the view.
+    // A placeholder stands in for them, so hooks added meanwhile follow them and `set_hook` replaces them.
+    let placeholder_alive = std::rc::Rc::new(());
+    let marker = placeholder_alive.clone();
+    let placeholder: ViewHook<Msg, S> = Box::new(move |_, _| {
+        let _ = &marker;
+    });
+    let placeholder_address = &*placeholder as *const _ as *const ();
+
+    let mut hooks = std::mem::replace(view.hooks(), vec![placeholder]);
+    for hook in &mut hooks {
         (hook)(view, message);
     }
-}
 
-/// Returns `None` if the widget was not found
-pub fn get_widget_by_id<'a, T: crate::widget::Widget<Msg>, Msg: crate::EmptyMessage>(view: &'a mut dyn View<Msg>, id: &str) -> Option<&'a mut Box<T>> {
+    // Unless the hooks were replaced, the placeholder is still there (and keeps its box)
+    if std::rc::Rc::strong_count(&placeholder_alive) > 1 {
+        let index = view.hooks().iter()
+            .position(|hook| &**hook as *const _ as *const () == placeholder_address)
+            .unwrap();
+        view.hooks().splice(index..=index, hooks);
+    }
+
     for child in view.children() {
-        match child {
-            crate::ViewElement::Widget(widget) => {
+        if let crate::ViewElement::View(child) = child {
+            call_hooks(&mut **child, message);
+        }
+    }
+
+    mark_stale_if_nested_changed(view);
+}
+
+/// Marks the view's map as stale if the elements of a nested view changed (such as those of a `ForEach`),
+/// since its paths into that view may be wrong. Called after the nested views are visited.
+pub(crate) fn mark_stale_if_nested_changed<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V) {
+    if take_nested_changes(view) {
+        if let Some(map) = view.map() {
+            map.mark_stale();
+        }
+    }
+}
+
+/// Whether the elements of any nested view changed since the view's map was updated (resetting the nested views).
+/// Views without a map pass on the changes of their own nested views.
+fn take_nested_changes<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V) -> bool {
+    view.children().iter_mut().fold(false, |changed, child| match child {
+        crate::ViewElement::Widget(_) => changed,
+        crate::ViewElement::View(child) => {
+            let child_changed = match child.map() {
+                Some(map) => std::mem::replace(&mut map.changed_for_parent, false),
+                None => take_nested_changes(&mut **child),
+            };
+
+            changed | child_changed
+        }
+    })
+}
+
+/// Reason a change to a view tree failed (see `View::delete` and the other changes by id)
+#[derive(Clone, Debug, PartialEq)]
+pub enum MutationError {
+    /// No widget in the view has the id
+    NotFound { id: String },
+    /// The id of an added widget is already used in the view
+    DuplicateId { id: String },
+    /// A widget was moved relative to itself
+    MoveToItself { id: String },
+    /// The view holding the elements does not support the change (such as adding elements to a `ForEach`)
+    Unsupported { view: &'static str, reason: &'static str },
+}
+
+impl std::fmt::Display for MutationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MutationError::NotFound { id } => write!(f, "Item `{}` was not found", id),
+            MutationError::DuplicateId { id } => write!(f, "Id `{}` is already in use", id),
+            MutationError::MoveToItself { id } => write!(f, "Item `{}` cannot be moved relative to itself", id),
+            MutationError::Unsupported { view, reason } => write!(f, "A `{}` cannot be changed this way: {}", view, reason),
+        }
+    }
+}
+
+impl std::error::Error for MutationError {}
+
+/// Reason a widget could not be found by `get_widget_by_id`
+#[derive(Clone, Debug, PartialEq)]
+pub enum WidgetLookupError {
+    /// No widget in the view has the id
+    NotFound { id: String },
+    /// The widget with the id is not of the requested type
+    WrongType { id: String, expected: &'static str, found: &'static str },
+}
+
+impl std::fmt::Display for WidgetLookupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WidgetLookupError::NotFound { id } => write!(f, "No such widget `{}`", id),
+            WidgetLookupError::WrongType { id, expected, found } => write!(f, "Widget `{}` is a `{}`, not a `{}`", id, found, expected),
+        }
+    }
+}
+
+impl std::error::Error for WidgetLookupError {}
+
+/// The widget with the given id as its concrete type
+pub fn get_widget_by_id<'a, T: crate::widget::Widget<Msg, S> + 'static, Msg: crate::EmptyMessage, S>(view: &'a mut dyn View<Msg, S>, id: &str) -> Result<&'a mut T, WidgetLookupError> {
+    let not_found = || WidgetLookupError::NotFound { id: id.to_string() };
+
+    let path = widget_path(view, id).ok_or_else(not_found)?;
+    let widget = match element_at(view, &path) {
+        Some(crate::ViewElement::Widget(widget)) => widget,
+        _ => return Err(not_found()),
+    };
+
+    // `as_any` must be called on the widget rather than the box
+    let found = (**widget).type_name();
+    (**widget).as_any().downcast_mut::<T>().ok_or_else(|| WidgetLookupError::WrongType {
+        id: id.to_string(),
+        expected: std::any::type_name::<T>(),
+        found,
+    })
+}
+
+/// Child indices leading to the widget, using the view's `ViewMap` if it has one.
+/// The map is rebuilt if it was invalidated, or if it misses the widget (such as one appended to a nested view).
+fn widget_path<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, id: &str) -> Option<Vec<usize>> {
+    let is_valid = match view.map() {
+        Some(map) => map.is_valid(),
+        None => return ViewMap::build(view).path(id).map(|path| path.to_vec()),
+    };
+
+    if is_valid {
+        if let Some(path) = view.map().unwrap().path(id).map(|path| path.to_vec()) {
+            if let Some(crate::ViewElement::Widget(widget)) = element_at(view, &path) {
                 if widget.id() == id {
-                    unsafe {
-                        // TODO: Add a type-check to ensure safety such as `Widget::type`
-                        return Some(&mut *(widget as *mut Box<dyn crate::widget::Widget<Msg>> as *mut Box<T>));
-                    }
+                    return Some(path);
                 }
             }
+        }
 
-            crate::ViewElement::View(view) => {
-                let result = get_widget_by_id::<T, Msg>(&mut (**view), id);
-                if result.is_some() {
-                    return result;
-                }
+        // The tree was changed without invalidating the map
+    }
+
+    let built = ViewMap::build(view);
+    let path = built.path(id).map(|path| path.to_vec());
+
+    let map = view.map().unwrap();
+    map.paths = built.paths;
+    map.is_valid = true;
+
+    path
+}
+
+/// The nested view at `path` (`None` for an empty path, which is the view itself)
+fn child_view<'a, Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &'a mut V, path: &[usize]) -> Option<&'a mut dyn View<Msg, S>> {
+    let (index, rest) = path.split_first()?;
+
+    match view.children().get_mut(*index)? {
+        crate::ViewElement::View(child) => {
+            if rest.is_empty() {
+                Some(&mut **child)
+            } else {
+                child_view(&mut **child, rest)
             }
         }
+        crate::ViewElement::Widget(_) => None,
+    }
+}
+
+fn element_at<'a, Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &'a mut V, path: &[usize]) -> Option<&'a mut crate::ViewElement<Msg, S>> {
+    let (index, parent) = path.split_last()?;
+
+    if parent.is_empty() {
+        return view.children().get_mut(*index);
+    }
+
+    child_view(view, parent)?.children().get_mut(*index)
+}
+
+/// Path of the widget's parent view and the widget's index within it
+fn locate<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, id: &str) -> Result<(Vec<usize>, usize), MutationError> {
+    let mut path = widget_path(view, id).ok_or_else(|| MutationError::NotFound { id: id.to_string() })?;
+    let index = path.pop().unwrap();
+
+    Ok((path, index))
+}
+
+fn remove_at<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, parent: &[usize], index: usize) -> Result<crate::ViewElement<Msg, S>, MutationError> {
+    match child_view(view, parent) {
+        Some(parent) => parent.remove_child(index),
+        None => view.remove_child(index),
+    }
+}
+
+fn insert_at<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, parent: &[usize], index: usize, item: crate::ViewElement<Msg, S>) -> Result<(), MutationError> {
+    match child_view(view, parent) {
+        Some(parent) => parent.insert_child(index, item),
+        None => view.insert_child(index, item),
+    }
+}
+
+/// Inserts the item before (`offset` 0) or after (`offset` 1) the widget
+fn insert_relative<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, id: &str, offset: usize, mut item: crate::ViewElement<Msg, S>) -> Result<(), MutationError> {
+    let (parent, index) = locate(view, id)?;
+    check_unique_ids(view, &mut item, None)?;
+
+    insert_at(view, &parent, index + offset, item)?;
+
+    invalidate(view, &parent);
+    Ok(())
+}
+
+/// Moves the widget before (`offset` 0) or after (`offset` 1) the target widget
+fn move_relative<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, id: &str, target_id: &str, offset: usize) -> Result<(), MutationError> {
+    if id == target_id {
+        return Err(MutationError::MoveToItself { id: id.to_string() });
+    }
+
+    // Both must exist, and the target's view must accept the item, before anything is removed
+    let (parent, index) = locate(view, id)?;
+    let (target_parent, _) = locate(view, target_id)?;
+    match child_view(view, &target_parent) {
+        Some(target_view) => target_view.can_insert_child()?,
+        None => view.can_insert_child()?,
+    }
+
+    let item = remove_at(view, &parent, index)?;
+    invalidate(view, &parent);
+
+    // Indices after the removed widget have changed
+    let (target_parent, target_index) = locate(view, target_id)?;
+    insert_at(view, &target_parent, target_index + offset, item)?;
+
+    invalidate(view, &target_parent);
+    Ok(())
+}
+
+/// Returns an error if any widget id in `item` is already used in the view (or used twice within `item`).
+/// `replaced` is an id which is about to be removed (and may be reused).
+///
+/// The ids are looked up in the view's map, which is only rebuilt if it was invalidated.
+/// Hidden elements (see `View::hidden_children`) aren't in the map, so their ids are collected from the tree.
+fn check_unique_ids<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, item: &mut crate::ViewElement<Msg, S>, replaced: Option<&str>) -> Result<(), MutationError> {
+    let ids = match item {
+        crate::ViewElement::Widget(widget) => vec![widget.id()],
+        crate::ViewElement::View(view) => ViewMap::ids(&mut **view),
+    };
+
+    let hidden: std::collections::HashSet<&str> = hidden_ids(view).into_iter().collect();
+
+    let mut checked = std::collections::HashSet::with_capacity(ids.len());
+    for id in ids {
+        if crate::id::is_anonymous(id) {
+            continue;
+        }
+
+        if !checked.insert(id) || hidden.contains(id) || (Some(id) != replaced && widget_path(view, id).is_some()) {
+            return Err(MutationError::DuplicateId { id: id.to_string() });
+        }
+    }
+
+    Ok(())
+}
+
+/// Ids of the widgets within the hidden elements of `view` and of its nested views
+fn hidden_ids<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V) -> Vec<&'static str> {
+    let mut ids = Vec::new();
+
+    for element in view.hidden_children() {
+        match element {
+            crate::ViewElement::Widget(widget) => ids.push(widget.id()),
+            crate::ViewElement::View(view) => ids.append(&mut ViewMap::ids(&mut **view)),
+        }
+    }
+
+    for child in view.children() {
+        if let crate::ViewElement::View(view) = child {
+            ids.append(&mut hidden_ids(&mut **view));
+        }
+    }
+
+    ids
+}
+
+/// Returns an error listing every id used by more than one widget in the view.
+/// Anonymous ids (see `id::is_anonymous`) may be shared.
+pub fn validate_ids<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V) -> Result<(), String> {
+    let mut counts: std::collections::HashMap<&'static str, usize> = std::collections::HashMap::new();
+    // Duplicates are listed in tree order
+    let mut order = Vec::new();
+
+    for id in ViewMap::ids(view) {
+        if crate::id::is_anonymous(id) {
+            continue;
+        }
+
+        let count = counts.entry(id).or_insert(0);
+        if *count == 0 {
+            order.push(id);
+        }
+        *count += 1;
     }
 
-    None
+    let duplicates: Vec<String> = order.into_iter()
+        .filter(|id| counts[id] > 1)
+        .map(|id| format!("`{}` ({} widgets)", id, counts[id]))
+        .collect();
+
+    if duplicates.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Widget ids must be unique, but these are used more than once: {}. \
+            Use `id::intern` to create ids at runtime, or `\"\"` for widgets which don't need an id.", duplicates.join(", ")))
+    }
+}
+
+/// Marks the view's tree as changed so it is re-initialized and re-laid out.
+/// The maps of the nested views along `path` (leading to the changed view) are invalidated as well.
+fn invalidate<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, path: &[usize]) {
+    if let Some(map) = view.map() {
+        map.invalidate();
+    }
+
+    if let Some((index, rest)) = path.split_first() {
+        if let Some(crate::ViewElement::View(child)) = view.children().get_mut(*index) {
+            invalidate(&mut **child, rest);
+        }
+    }
 }
 
 #[macro_export]
 macro_rules! GetWidget {
     ($ty:ident($id:ident) from $view:expr) => {
-        get_widget_by_id::<$ty<_>, _>($view, stringify!($id))
-            .expect(&format!("No such widget `{}`", stringify!($id)))
+        get_widget_by_id::<$ty<_, _>, _, _>($view, stringify!($id))
+            .unwrap_or_else(|error| panic!("{}", error))
     };
 
     ($view:ident.$id:ident as $ty:ident) => {
-        get_widget_by_id::<$ty<_>, _>($view, stringify!($id))
-            .expect(&format!("No such widget `{}`", stringify!($id)))
+        get_widget_by_id::<$ty<_, _>, _, _>($view, stringify!($id))
+            .unwrap_or_else(|error| panic!("{}", error))
     };
-}
\ No newline at end of file
+}
+
+/// Resizable widget with a fixed natural size, used to test layouts
+#[cfg(test)]
+pub(crate) struct Block {
+    id: &'static str,
+    natural_size: (u32, u32),
+    pub(crate) bounds: crate::bounding_rect::BoundingRect,
+    should_resize: bool,
+}
+
+#[cfg(test)]
+impl Block {
+    pub(crate) fn new(id: &'static str, width: u32, height: u32) -> Self {
+        let mut bounds = crate::bounding_rect::BoundingRect::new();
+        bounds.width = width;
+        bounds.height = height;
+
+        Self { id, natural_size: (width, height), bounds, should_resize: false }
+    }
+}
+
+#[cfg(test)]
+impl<Msg: crate::EmptyMessage, S> crate::widget::Widget<Msg, S> for Block {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn should_resize(&mut self) -> &mut bool {
+        &mut self.should_resize
+    }
+
+    fn init(&mut self, _renderer: &mut crate::render::Renderer, _theme: &crate::style::Theme) {
+
+    }
+
+    fn place(&mut self, x: i32, y: i32) {
+        self.bounds.x = x;
+        self.bounds.y = y;
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+    }
+
+    fn render_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        self.bounds.dimensions()
+    }
+
+    fn natural_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        self.natural_size
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.bounds.width = width;
+        self.bounds.height = height;
+    }
+
+    fn render(&self, _renderer: &mut dyn crate::render::RenderTarget, _theme: &crate::style::Theme) {
+
+    }
+}
+
+/// Measures the view within `constraints` (as a nested view) and arranges it at the origin.
+/// Returns the (x, y, width, height) of every `Block` in the view (in tree order).
+#[cfg(test)]
+pub(crate) fn layout_blocks<Msg: crate::EmptyMessage, S, V: View<Msg, S> + ?Sized>(view: &mut V, constraints: BoxConstraints) -> Vec<(i32, i32, u32, u32)> {
+    let mut renderer = crate::render::headless::test_renderer();
+    view._init(&mut renderer, &crate::style::DEFAULT_THEME, true);
+    view.measure(&mut renderer, &crate::style::DEFAULT_THEME, constraints, false);
+    view.arrange(0, 0);
+
+    fn collect<Msg: crate::EmptyMessage, S>(children: &mut Vec<crate::ViewElement<Msg, S>>, blocks: &mut Vec<(i32, i32, u32, u32)>) {
+        for child in children {
+            match child {
+                crate::ViewElement::View(view) => collect(view.children(), blocks),
+                crate::ViewElement::Widget(widget) => {
+                    if let Some(block) = (**widget).as_any().downcast_ref::<Block>() {
+                        let bounds = &block.bounds;
+                        blocks.push((bounds.x, bounds.y, bounds.width, bounds.height));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut blocks = Vec::new();
+    collect(view.children(), &mut blocks);
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn widgets_are_found_by_id_and_type() {
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("title").text("Title"),
+            HStack! {
+                Button::new("ok"),
+            },
+        };
+
+        assert!(get_widget_by_id::<Text<(), ()>, (), ()>(&mut view, "title").is_ok());
+        assert!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut view, "ok").is_ok());
+
+        assert_eq!(get_widget_by_id::<Text<(), ()>, (), ()>(&mut view, "missing").err(), Some(WidgetLookupError::NotFound { id: "missing".to_string() }));
+
+        match get_widget_by_id::<Text<(), ()>, (), ()>(&mut view, "ok") {
+            Err(WidgetLookupError::WrongType { id, expected, found }) => {
+                assert_eq!(id, "ok");
+                assert!(expected.contains("Text"));
+                assert!(found.contains("Button"));
+            }
+            _ => panic!("Expected a type error"),
+        }
+    }
+
+    #[test]
+    fn the_map_is_only_rebuilt_after_it_was_invalidated() {
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("title"),
+        };
+
+        assert!(!view.map().unwrap().is_valid());
+        assert!(get_widget_by_id::<Text<(), ()>, (), ()>(&mut view, "title").is_ok());
+        assert!(view.map().unwrap().is_valid());
+
+        // Changing the children directly does not invalidate the map, so a missing id rebuilds it
+        view.children().push(Text::new("late").into_element());
+        assert!(get_widget_by_id::<Text<(), ()>, (), ()>(&mut view, "late").is_ok());
+        assert!(view.map().unwrap().path("late").is_some());
+
+        // Changes made through the view update the map, or invalidate it if other paths changed
+        view.append(Text::new("appended").into_element()).unwrap();
+        assert!(view.map().unwrap().is_valid());
+        assert!(get_widget_by_id::<Text<(), ()>, (), ()>(&mut view, "appended").is_ok());
+
+        view.insert_before("title", Text::new("inserted").into_element()).unwrap();
+        assert!(!view.map().unwrap().is_valid());
+        assert!(get_widget_by_id::<Text<(), ()>, (), ()>(&mut view, "inserted").is_ok());
+    }
+
+    fn texts_found(view: &mut Stack<(), ()>, ids: &[&str]) -> bool {
+        ids.iter().all(|id| get_widget_by_id::<Text<(), ()>, (), ()>(view, id).is_ok())
+    }
+
+    #[test]
+    fn widgets_are_moved_within_and_between_views() {
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("a"),
+            Text::new("b"),
+            HStack! {
+                Text::new("c"),
+                Text::new("d"),
+            },
+        };
+        assert!(texts_found(&mut view, &["a", "b", "c", "d"]));
+
+        // Removing "a" moves "b" (the target) to another index
+        view.move_after("a", "b").unwrap();
+        assert_eq!(ViewMap::ids(&mut view), vec!["b", "a", "c", "d"]);
+        assert!(texts_found(&mut view, &["a", "b", "c", "d"]));
+
+        // Into the nested view and back out
+        view.move_before("a", "d").unwrap();
+        assert_eq!(ViewMap::ids(&mut view), vec!["b", "c", "a", "d"]);
+        assert!(texts_found(&mut view, &["a", "b", "c", "d"]));
+
+        view.move_before("d", "b").unwrap();
+        assert_eq!(ViewMap::ids(&mut view), vec!["d", "b", "c", "a"]);
+        assert!(texts_found(&mut view, &["a", "b", "c", "d"]));
+
+        assert_eq!(view.move_before("a", "a"), Err(MutationError::MoveToItself { id: "a".to_string() }));
+        assert_eq!(view.move_before("a", "missing"), Err(MutationError::NotFound { id: "missing".to_string() }));
+        assert_eq!(ViewMap::ids(&mut view), vec!["d", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn widgets_are_not_moved_into_views_which_refuse_them() {
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("a"),
+            TabView! {
+                "Page" => Text::new("page"),
+            },
+        };
+
+        match view.move_before("a", "page") {
+            Err(MutationError::Unsupported { view, .. }) => assert_eq!(view, "TabView"),
+            result => panic!("Expected the move to be refused, got {:?}", result.map(|_| ())),
+        }
+
+        // Nothing was removed
+        assert_eq!(ViewMap::ids(&mut view), vec!["a", "page"]);
+        assert!(texts_found(&mut view, &["a", "page"]));
+    }
+
+    #[test]
+    fn replaced_widgets_are_returned() {
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("a"),
+            HStack! {
+                Text::new("b"),
+            },
+        };
+        assert!(texts_found(&mut view, &["a", "b"]));
+
+        // The replacement may keep the id or use a new one
+        let old = view.replace("b", Button::new("b").into_element()).unwrap();
+        assert!(matches!(old, ViewElement::Widget(widget) if widget.id() == "b"));
+        assert!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut view, "b").is_ok());
+
+        view.replace("a", Text::new("c").into_element()).unwrap();
+        assert_eq!(ViewMap::ids(&mut view), vec!["c", "b"]);
+        assert!(texts_found(&mut view, &["c"]));
+        assert!(get_widget_by_id::<Text<(), ()>, (), ()>(&mut view, "a").is_err());
+
+        assert_eq!(view.replace("c", Text::new("b").into_element()).err().map(|error| error.to_string()), Some("Id `b` is already in use".to_string()));
+        assert_eq!(view.replace("a", Text::new("a").into_element()).err(), Some(MutationError::NotFound { id: "a".to_string() }));
+    }
+
+    #[test]
+    fn duplicate_ids_are_rejected_on_insert() {
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("title"),
+            HStack! {
+                Button::new("ok"),
+            },
+        };
+
+        assert!(view.append(Button::new("ok").into_element()).is_err());
+        assert!(view.insert_before("title", VStack! { Text::new("new"), Text::new("new") }.into_element()).is_err());
+        assert_eq!(ViewMap::ids(&mut view), vec!["title", "ok"]);
+
+        // Appending keeps the map valid
+        view.append(Button::new("cancel").into_element()).unwrap();
+        assert!(view.map().unwrap().is_valid());
+        assert!(view.append(Button::new("cancel").into_element()).is_err());
+
+        // A replacement may reuse the replaced id
+        view.replace("ok", Button::new("ok").into_element()).unwrap();
+        assert!(validate_ids(&mut view).is_ok());
+
+        // Ids added without checks are reported
+        view.children().push(Text::new("title").into_element());
+        let error = validate_ids(&mut view).unwrap_err();
+        assert!(error.contains("`title` (2 widgets)"), "{}", error);
+        assert!(!error.contains("`ok`"));
+    }
+
+    #[test]
+    fn changes_to_nested_views_are_found_from_the_root() {
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("title"),
+            HStack! {
+                Button::new("ok"),
+            },
+        };
+
+        assert!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut view, "ok").is_ok());
+
+        match &mut view.children()[1] {
+            ViewElement::View(nested) => nested.append(Button::new("cancel").into_element()).unwrap(),
+            ViewElement::Widget(_) => unreachable!(),
+        }
+
+        // Found before the change reaches the root's map with the next pass over the tree (after events and messages)
+        assert!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut view, "cancel").is_ok());
+        assert!(view.check_if_should_resize());
+        assert!(view.map().unwrap().is_valid());
+
+        match &mut view.children()[1] {
+            ViewElement::View(nested) => nested.append(Button::new("retry").into_element()).unwrap(),
+            ViewElement::Widget(_) => unreachable!(),
+        }
+
+        // The pass marks the root's map as stale
+        assert!(view.check_if_should_resize());
+        assert!(!view.map().unwrap().is_valid());
+
+        assert!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut view, "retry").is_ok());
+        assert_eq!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut view, "missing").err(), Some(WidgetLookupError::NotFound { id: "missing".to_string() }));
+        assert!(view.map().unwrap().is_valid());
+
+        // Only changes since the map was rebuilt are passed on
+        assert!(!view.check_if_should_resize());
+        assert!(view.map().unwrap().is_valid());
+    }
+
+    #[test]
+    fn changes_from_the_root_invalidate_the_nested_views() {
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("title"),
+            HStack! {
+                Button::new("ok"),
+            },
+        };
+
+        assert!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut view, "ok").is_ok());
+        view.insert_after("ok", Button::new("cancel").into_element()).unwrap();
+
+        // Found from the nested view as well as from the root
+        match &mut view.children()[1] {
+            ViewElement::View(nested) => assert!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut **nested, "cancel").is_ok()),
+            ViewElement::Widget(_) => unreachable!(),
+        }
+        assert!(get_widget_by_id::<Button<(), ()>, (), ()>(&mut view, "cancel").is_ok());
+    }
+
+    /// A view with a nested view, whose hooks send their names
+    fn hooked_view() -> (Stack<(), ()>, std::sync::mpsc::Receiver<&'static str>) {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let mut nested: Stack<(), ()> = HStack! {
+            Text::new("nested_text"),
+        };
+        let nested_sender = sender.clone();
+        nested.add_hook(Box::new(move |_, _| nested_sender.send("nested").unwrap()));
+
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("title"),
+            nested,
+        };
+        let first_sender = sender.clone();
+        view.add_hook(Box::new(move |_, _| first_sender.send("first").unwrap()));
+        view.add_hook(Box::new(move |_, _| sender.send("second").unwrap()));
+
+        (view, receiver)
+    }
+
+    #[test]
+    fn hooks_are_called_in_order_then_those_of_nested_views() {
+        let (mut view, receiver) = hooked_view();
+
+        crate::view::call_hooks(&mut view, &());
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["first", "second", "nested"]);
+
+        crate::view::call_hooks(&mut view, &());
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["first", "second", "nested"]);
+    }
+
+    #[test]
+    fn hooks_keep_their_captured_state() {
+        let count = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut calls = 0;
+
+        let mut view: Stack<(), ()> = VStack! {
+            Text::new("title"),
+        };
+        let shared_count = count.clone();
+        view.set_hook(Box::new(move |_, _| {
+            calls += 1;
+            shared_count.set(calls);
+        }));
+
+        for _ in 0..3 {
+            crate::view::call_hooks(&mut view, &());
+        }
+        assert_eq!(count.get(), 3);
+    }
+
+    #[test]
+    fn hooks_changed_by_hooks_apply_after_they_ran() {
+        let (mut view, receiver) = hooked_view();
+
+        // Added hooks follow the running ones
+        let mut added = false;
+        view.add_hook(Box::new(move |view, _| {
+            if !added {
+                added = true;
+                view.add_hook(Box::new(|_, _| {}));
+            }
+        }));
+        crate::view::call_hooks(&mut view, &());
+        assert_eq!(view.hooks().len(), 4);
+        receiver.try_iter().count();
+
+        // Replacing the hooks replaces the running ones as well (after the rest of them ran)
+        let (sender, replaced) = std::sync::mpsc::channel();
+        view.hooks().insert(0, Box::new(move |view, _| {
+            let sender = sender.clone();
+            view.set_hook(Box::new(move |_, _| sender.send("replacement").unwrap()));
+        }));
+        crate::view::call_hooks(&mut view, &());
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["first", "second", "nested"]);
+        assert_eq!(view.hooks().len(), 1);
+
+        crate::view::call_hooks(&mut view, &());
+        assert_eq!(replaced.try_iter().collect::<Vec<_>>(), vec!["replacement"]);
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec!["nested"]);
+    }
+}
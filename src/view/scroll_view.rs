@@ -0,0 +1,462 @@
+use crate::state::{Shared, State};
+use crate::bounding_rect::BoundingRect;
+use crate::widget::{ScrollBar, Widget};
+use crate::view_element::*;
+
+use super::{View, MutationError};
+
+/// Scrolls a view which is larger than the space available to it.
+///
+/// The content is layed out at its natural size and clipped to the viewport.
+/// Scroll bars are added automatically when the content overflows.
+///
+/// Usage:
+/// ```ignore
+/// ScrollView::new(VStack! {
+///     ...
+/// })
+/// .height(300)
+/// ```
+pub struct ScrollView<Msg: EmptyMessage, S> {
+    state: Option<Shared<State<S>>>,
+    /// Contains only the scrolled view
+    children: Vec<ViewElement<Msg, S>>,
+
+    hooks: Vec<super::ViewHook<Msg, S>>,
+    map: super::ViewMap,
+
+    /// The viewport
+    bounds: BoundingRect,
+    // Viewport size requested by the user. Defaults to the content size (limited by the constraints).
+    width: Option<u32>,
+    height: Option<u32>,
+
+    content_size: (u32, u32),
+    /// How far the content is scrolled (in pixels)
+    scroll: (f32, f32),
+    /// How far the content is currently translated (rounded `scroll`)
+    content_offset: (i32, i32),
+
+    vertical_bar: Option<ScrollBar<Msg, S>>,
+    horizontal_bar: Option<ScrollBar<Msg, S>>,
+    bar_width: u32,
+}
+
+impl<Msg: EmptyMessage + 'static, S: 'static> ScrollView<Msg, S> {
+    /// Smallest length of a scroll bar's slider (in pixels)
+    const MIN_SLIDER_SIZE: u32 = 20;
+
+    pub fn new<V: View<Msg, S> + 'static>(content: V) -> Self {
+        Self {
+            state: None,
+            children: vec![ViewElement::View(Box::new(content))],
+            hooks: Vec::new(),
+            map: super::ViewMap::new(),
+            bounds: BoundingRect::new(),
+            width: None,
+            height: None,
+            content_size: (0, 0),
+            scroll: (0.0, 0.0),
+            content_offset: (0, 0),
+            vertical_bar: None,
+            horizontal_bar: None,
+            bar_width: 12,
+        }
+    }
+
+    /// Width of the viewport
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Height of the viewport
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Width of the scroll bars (in the unscrollable direction)
+    pub fn scroll_bar_width(mut self, width: u32) -> Self {
+        self.bar_width = width;
+        self
+    }
+
+    /// How far the content is scrolled (in pixels)
+    pub fn scroll_offset(&self) -> (u32, u32) {
+        (self.content_offset.0 as u32, self.content_offset.1 as u32)
+    }
+
+    /// Scrolls the content so that (x, y) is at the top-left of the viewport.
+    /// Offsets are clamped to the scrollable area.
+    pub fn scroll_to(&mut self, x: f32, y: f32) {
+        let (max_x, max_y) = self.max_scroll();
+        self.scroll = (x.max(0.0).min(max_x as f32), y.max(0.0).min(max_y as f32));
+
+        let offset = (self.scroll.0.round() as i32, self.scroll.1.round() as i32);
+        let (dx, dy) = (self.content_offset.0 - offset.0, self.content_offset.1 - offset.1);
+        self.content_offset = offset;
+        self.content().translate(dx, dy);
+
+        // Keep the scroll bars in sync
+        if let Some(bar) = &mut self.vertical_bar {
+            bar.set_percentage(if max_y == 0 { 0.0 } else { self.scroll.1 / max_y as f32 });
+        }
+        if let Some(bar) = &mut self.horizontal_bar {
+            bar.set_percentage(if max_x == 0 { 0.0 } else { self.scroll.0 / max_x as f32 });
+        }
+    }
+
+    /// Scrolls the least amount needed to show the rect (in window coordinates) within the viewport.
+    /// Rects larger than the viewport are aligned to its top-left.
+    pub fn scroll_into_view(&mut self, rect: &BoundingRect) {
+        // The scroll bars cover the content at the edges of the viewport
+        let visible_width = if self.vertical_bar.is_some() { self.bounds.width.saturating_sub(self.bar_width) } else { self.bounds.width };
+        let visible_height = if self.horizontal_bar.is_some() { self.bounds.height.saturating_sub(self.bar_width) } else { self.bounds.height };
+
+        // Position within the content
+        let x = rect.x - self.bounds.x + self.content_offset.0;
+        let y = rect.y - self.bounds.y + self.content_offset.1;
+
+        let scroll_x = Self::scroll_to_show(self.content_offset.0, visible_width, x, rect.width);
+        let scroll_y = Self::scroll_to_show(self.content_offset.1, visible_height, y, rect.height);
+
+        self.scroll_to(scroll_x as f32, scroll_y as f32);
+    }
+
+    /// Scroll position along one axis which shows `length` pixels starting at `start`
+    fn scroll_to_show(scroll: i32, visible_length: u32, start: i32, length: u32) -> i32 {
+        let end = start + length as i32;
+
+        if start < scroll {
+            start
+        } else if end > scroll + visible_length as i32 {
+            std::cmp::min(start, end - visible_length as i32)
+        } else {
+            scroll
+        }
+    }
+
+    fn content(&mut self) -> &mut Box<dyn View<Msg, S>> {
+        match &mut self.children[0] {
+            ViewElement::View(view) => view,
+            ViewElement::Widget(_) => unreachable!("The content of a ScrollView is always a view"),
+        }
+    }
+
+    fn max_scroll(&self) -> (u32, u32) {
+        (
+            self.content_size.0.saturating_sub(self.bounds.width),
+            self.content_size.1.saturating_sub(self.bounds.height),
+        )
+    }
+
+    /// Creates, resizes, or removes a scroll bar depending on whether the content overflows
+    fn update_scroll_bar(bar: &mut Option<ScrollBar<Msg, S>>, orientation: crate::Orientation, bar_width: u32, bar_length: u32, viewport_size: u32, content_size: u32, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme) {
+        if content_size <= viewport_size {
+            *bar = None;
+            return;
+        }
+
+        // The slider represents the visible part of the content
+        let slider_size = std::cmp::max(Self::MIN_SLIDER_SIZE, (bar_length as u64 * viewport_size as u64 / content_size as u64) as u32);
+        let slider_size = std::cmp::min(slider_size, bar_length.saturating_sub(1));
+
+        match bar {
+            Some(bar) => bar.set_sizes(bar_length, slider_size),
+            None => {
+                let mut new_bar = ScrollBar::new("", bar_length, slider_size)
+                    .orientation(orientation)
+                    .width(bar_width);
+                new_bar.init(renderer, theme);
+
+                *bar = Some(new_bar);
+            }
+        }
+    }
+
+    /// Scrolls the content to match the scroll bars (after they were dragged)
+    fn scroll_to_scroll_bars(&mut self) {
+        let (max_x, max_y) = self.max_scroll();
+
+        let x = self.horizontal_bar.as_ref().map_or(self.scroll.0, |bar| bar.percentage() * max_x as f32);
+        let y = self.vertical_bar.as_ref().map_or(self.scroll.1, |bar| bar.percentage() * max_y as f32);
+
+        self.scroll_to(x, y);
+    }
+}
+
+impl<Msg: EmptyMessage, S> View<Msg, S> for ScrollView<Msg, S> where Msg: 'static, S: 'static {
+    fn state(&self) -> Shared<State<S>> {
+        self.state.as_ref().unwrap().clone()
+    }
+
+    fn assign_state(&mut self, state: Shared<State<S>>) {
+        self.content().assign_state(state.clone());
+        self.state = Some(state);
+    }
+
+    fn hooks(&mut self) -> &mut Vec<super::ViewHook<Msg, S>> {
+        &mut self.hooks
+    }
+
+    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg, S>> {
+        &mut self.children
+    }
+
+    fn map(&mut self) -> Option<&mut super::ViewMap> {
+        Some(&mut self.map)
+    }
+
+    /// The content view is the only child
+    fn can_insert_child(&mut self) -> Result<(), MutationError> {
+        Err(MutationError::Unsupported { view: "ScrollView", reason: "it holds a single view, so insert elements into its content instead" })
+    }
+
+    fn remove_child(&mut self, _index: usize) -> Result<ViewElement<Msg, S>, MutationError> {
+        Err(MutationError::Unsupported { view: "ScrollView", reason: "it holds a single view, so remove elements from its content instead" })
+    }
+
+    /// The content can be replaced by another view
+    fn replace_child(&mut self, index: usize, item: ViewElement<Msg, S>) -> Result<ViewElement<Msg, S>, MutationError> {
+        match item {
+            ViewElement::View(_) => Ok(std::mem::replace(&mut self.children[index], item)),
+            ViewElement::Widget(_) => Err(MutationError::Unsupported { view: "ScrollView", reason: "its content must be a view" }),
+        }
+    }
+
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: super::BoxConstraints, _is_root: bool) -> (u32, u32) {
+        use super::BoxConstraints;
+
+        // Parent views arrange this view
+        self.bounds.x = 0;
+        self.bounds.y = 0;
+        self.content_offset = (0, 0);
+
+        // The content scrolls rather than being limited to the viewport
+        let content = self.content();
+        let content_size = content.measure(renderer, theme, BoxConstraints::unbounded(), false);
+
+        let viewport = constraints.constrain(content_size);
+        let viewport = (self.width.unwrap_or(viewport.0), self.height.unwrap_or(viewport.1));
+
+        // Measure again to fill the viewport (centered content would otherwise be shifted out of view)
+        let content = self.content();
+        if content_size.0 < viewport.0 || content_size.1 < viewport.1 {
+            content.measure(renderer, theme, BoxConstraints::new(viewport, (BoxConstraints::UNBOUNDED, BoxConstraints::UNBOUNDED)), false);
+        }
+
+        self.content_size = content.render_size();
+
+        self.bounds.width = viewport.0;
+        self.bounds.height = viewport.1;
+
+        let (width, height) = self.bounds.dimensions();
+        let has_vertical_bar = self.content_size.1 > height;
+        let has_horizontal_bar = self.content_size.0 > width;
+
+        // Bars don't overlap each other in the corner
+        let vertical_bar_length = if has_horizontal_bar { height.saturating_sub(self.bar_width) } else { height };
+        let horizontal_bar_length = if has_vertical_bar { width.saturating_sub(self.bar_width) } else { width };
+
+        Self::update_scroll_bar(&mut self.vertical_bar, crate::Orientation::Vertical, self.bar_width, vertical_bar_length, height, self.content_size.1, renderer, theme);
+        Self::update_scroll_bar(&mut self.horizontal_bar, crate::Orientation::Horizontal, self.bar_width, horizontal_bar_length, width, self.content_size.0, renderer, theme);
+
+        // Bars are placed on top of the content at the edges of the viewport
+        if let Some(bar) = &mut self.vertical_bar {
+            bar.place(width.saturating_sub(self.bar_width) as i32, 0);
+        }
+        if let Some(bar) = &mut self.horizontal_bar {
+            bar.place(0, height.saturating_sub(self.bar_width) as i32);
+        }
+
+        // Restore the scroll position (clamped to the new content size)
+        let (x, y) = self.scroll;
+        self.scroll_to(x, y);
+
+        viewport
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+
+        self.content().translate(dx, dy);
+
+        if let Some(bar) = &mut self.vertical_bar {
+            bar.translate(dx, dy);
+        }
+        if let Some(bar) = &mut self.horizontal_bar {
+            bar.translate(dx, dy);
+        }
+    }
+
+    fn render_width(&self) -> u32 {
+        self.bounds.width
+    }
+
+    fn render_height(&self) -> u32 {
+        self.bounds.height
+    }
+
+    fn render(&mut self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme) {
+        renderer.push_clip(crate::render::ClipRect::new(self.bounds.top_left(), self.bounds.width, self.bounds.height));
+        self.content().render(renderer, theme);
+        renderer.pop_clip();
+
+        if let Some(bar) = &self.vertical_bar {
+            bar.render(renderer, theme);
+        }
+        if let Some(bar) = &self.horizontal_bar {
+            bar.render(renderer, theme);
+        }
+    }
+
+    fn propogate_event(&mut self, event: &crate::event::ApplicationEvent, message_queue: &mut crate::MessageQueue<Msg>) -> bool {
+        use crate::event::*;
+
+        match event {
+            // Scrolling within the viewport scrolls the content
+            ApplicationEvent::MouseWheel { delta, position: (x, y) } => {
+                if !self.bounds.contains(*x, *y) {
+                    return false;
+                }
+
+                // Scrollable elements within the content (such as nested scroll views) scroll first.
+                // They leave the event to this view once they cannot scroll any further.
+                if self.content().propogate_event(event, message_queue) {
+                    return true;
+                }
+
+                let (mut dx, mut dy) = delta.to_pixels();
+                // Vertical wheels scroll horizontally when there is nothing to scroll vertically
+                if self.vertical_bar.is_none() && dx == 0.0 {
+                    dx = -dy;
+                    dy = 0.0;
+                }
+
+                let previous_offset = self.content_offset;
+                // Scrolling up moves towards the start of the content
+                let (scroll_x, scroll_y) = self.scroll;
+                self.scroll_to(scroll_x + dx, scroll_y - dy);
+
+                return self.content_offset != previous_offset;
+            }
+
+            // Content outside of the viewport cannot be clicked
+            ApplicationEvent::MouseButton { state: ButtonState::Pressed, position: (x, y), .. } => {
+                if !self.bounds.contains(*x, *y) {
+                    return false;
+                }
+            }
+
+            _ => {}
+        }
+
+        let mut redraw = false;
+        let mut is_consumed = false;
+
+        // Scroll bars are drawn on top of the content -> they receive events first
+        let state = self.state();
+        for bar in self.vertical_bar.iter_mut().chain(self.horizontal_bar.iter_mut()) {
+            match bar.handle_event(event, state.borrow_mut(), message_queue) {
+                crate::EventResponse::Consume => {
+                    is_consumed = true;
+                    break;
+                }
+                crate::EventResponse::Redraw => redraw = true,
+                crate::EventResponse::None => {}
+            }
+        }
+
+        // A scroll bar was dragged
+        if redraw {
+            self.scroll_to_scroll_bars();
+        }
+
+        if !is_consumed {
+            redraw |= self.content().propogate_event(event, message_queue);
+        }
+
+        redraw
+    }
+
+    fn focusable_widget_at(&mut self, x: i32, y: i32) -> Option<&'static str> {
+        if self.bounds.contains(x, y) {
+            self.content().focusable_widget_at(x, y)
+        } else {
+            None
+        }
+    }
+
+    /// Focused widgets are scrolled into view (such as when tabbing to a widget outside of the viewport)
+    fn set_widget_focus(&mut self, id: &str, has_focus: bool) -> bool {
+        if !self.content().set_widget_focus(id, has_focus) {
+            return false;
+        }
+
+        if has_focus {
+            let rect = super::widget_path(self, id)
+                .and_then(|path| match super::element_at(self, &path) {
+                    Some(ViewElement::Widget(widget)) => widget.focus_bounds().copied(),
+                    _ => None,
+                });
+
+            if let Some(rect) = rect {
+                self.scroll_into_view(&rect);
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn focus_bounds_of(view: &mut ScrollView<(), ()>, index: usize) -> BoundingRect {
+        match view.content().children().get(index) {
+            Some(ViewElement::Widget(widget)) => *widget.focus_bounds().unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn focused_widgets_are_scrolled_into_view() {
+        let mut view: ScrollView<(), ()> = ScrollView::new(VStack! {
+            Button::new("first").text(Text::new("").text("First")),
+            Button::new("second").text(Text::new("").text("Second")),
+            Button::new("third").text(Text::new("").text("Third")),
+            Button::new("fourth").text(Text::new("").text("Fourth")),
+        })
+        .height(100);
+
+        let mut renderer = crate::render::headless::test_renderer();
+        let mut recorder = crate::render::headless::CommandRecorder::new();
+        recorder.record_view(&mut view, &mut renderer, &DEFAULT_THEME, (200, 200));
+        assert_eq!(view.scroll_offset(), (0, 0));
+
+        // Focusing (such as by tabbing to) a widget below the viewport scrolls down to it
+        assert!(view.set_widget_focus("fourth", true));
+        let offset = view.scroll_offset().1;
+        assert!(offset > 0);
+
+        let rect = focus_bounds_of(&mut view, 3);
+        assert!(view.bounds.contains(rect.x, rect.y));
+        assert!(view.bounds.contains(rect.x, rect.y + rect.height as i32 - 1));
+
+        // Widgets which are already visible don't scroll
+        assert!(view.set_widget_focus("fourth", false));
+        assert!(view.set_widget_focus("fourth", true));
+        assert_eq!(view.scroll_offset().1, offset);
+
+        // Scrolls back up to the first widget
+        assert!(view.set_widget_focus("first", true));
+        let rect = focus_bounds_of(&mut view, 0);
+        assert_eq!(rect.y, view.bounds.y);
+
+        assert!(!view.set_widget_focus("missing", true));
+    }
+}
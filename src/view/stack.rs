@@ -2,37 +2,191 @@ use crate::state::{Shared, State};
 use crate::{Orientation, Alignment};
 use crate::view_element::*;
 
-pub struct Stack<Msg: EmptyMessage> {
+use super::{Flex, BoxConstraints};
+
+/// Placement of children along a stack (vertically in a `VStack`, horizontally in an `HStack`)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MainAxisAlignment {
+    Start,
+    Center,
+    End,
+    /// First and last children at the edges, with the free space split evenly between children
+    SpaceBetween,
+    /// Free space split evenly around every child (half-size spaces at the edges)
+    SpaceAround,
+}
+
+/// Placement of children across a stack (horizontally in a `VStack`, vertically in an `HStack`)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CrossAxisAlignment {
+    Start,
+    Center,
+    End,
+    /// Resizes children to the stack's cross axis size (see `Flexible`)
+    Stretch,
+}
+
+pub struct Stack<Msg: EmptyMessage, S> {
     orientation: Orientation,
     alignment: Option<Alignment>,
-    state: Option<Shared<State>>,
-    children: Vec<ViewElement<Msg>>,
+    main_alignment: Option<MainAxisAlignment>,
+    cross_alignment: Option<CrossAxisAlignment>,
+    state: Option<Shared<State<S>>>,
+    children: Vec<ViewElement<Msg, S>>,
 
-    hook: Option<super::ViewHook<Msg>>,
+    hooks: Vec<super::ViewHook<Msg, S>>,
+    map: super::ViewMap,
 
     bounds: crate::bounding_rect::BoundingRect,
 }
 
-impl<Msg: EmptyMessage> Stack<Msg> {
-    pub fn new(orientation: Orientation, children: Vec<ViewElement<Msg>>) -> Self {
+impl<Msg: EmptyMessage, S> Stack<Msg, S> {
+    pub fn new(orientation: Orientation, children: Vec<ViewElement<Msg, S>>) -> Self {
         Stack {
             orientation,
             alignment: None,
+            main_alignment: None,
+            cross_alignment: None,
             state: None,
             children,
-            hook: None,
+            hooks: Vec::new(),
+            map: super::ViewMap::new(),
             bounds: crate::bounding_rect::BoundingRect::new(),
         }
     }
 
+    /// Aligns the children horizontally (`Center` also centers them vertically).
+    /// Overridden by `main_alignment` and `cross_alignment`.
     pub fn alignment(mut self, alignment: Alignment) -> Self {
         self.alignment = Some(alignment);
         self
     }
+
+    /// How children are placed along the stack (vertically in a `VStack`, horizontally in an `HStack`)
+    pub fn main_alignment(mut self, alignment: MainAxisAlignment) -> Self {
+        self.main_alignment = Some(alignment);
+        self
+    }
+
+    /// How children are placed across the stack (horizontally in a `VStack`, vertically in an `HStack`)
+    pub fn cross_alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.cross_alignment = Some(alignment);
+        self
+    }
+
+    /// Stretches every child to the stack's width (`VStack`) or height (`HStack`).
+    /// Only resizable children are stretched (see `Flexible`).
+    pub fn stretch(self) -> Self {
+        self.cross_alignment(CrossAxisAlignment::Stretch)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Main and cross axis alignment, falling back to `alignment` (or the theme's default alignment)
+    fn resolved_alignment(&self, theme: &crate::style::Theme) -> (MainAxisAlignment, CrossAxisAlignment) {
+        let alignment = self.alignment.unwrap_or(theme.default_alignment);
+
+        // `Alignment` is horizontal, so it applies to the main axis of an `HStack` and the cross axis of a `VStack`
+        let (main, cross) = match (self.orientation.is_vertical(), alignment) {
+            (_, Alignment::Center) => (MainAxisAlignment::Center, CrossAxisAlignment::Center),
+            (true, Alignment::Left) => (MainAxisAlignment::Start, CrossAxisAlignment::Start),
+            (true, Alignment::Right) => (MainAxisAlignment::Start, CrossAxisAlignment::End),
+            (false, Alignment::Left) => (MainAxisAlignment::Start, CrossAxisAlignment::Start),
+            (false, Alignment::Right) => (MainAxisAlignment::End, CrossAxisAlignment::Start),
+        };
+
+        (self.main_alignment.unwrap_or(main), self.cross_alignment.unwrap_or(cross))
+    }
+
+    /// Converts between (width, height) and (main axis, cross axis)
+    fn axes(&self) -> impl Fn((u32, u32)) -> (u32, u32) {
+        let vertical = self.orientation.is_vertical();
+        move |size| if vertical { (size.1, size.0) } else { size }
+    }
 }
 
-impl<Msg: EmptyMessage> super::View<Msg> for Stack<Msg> where Msg: 'static{
-    fn assign_state(&mut self, state: Shared<State>) {
+/// Adds the stack's children to `elements`, replacing inline views (see `View::is_inline`) by their own children
+fn inline_elements<'a, Msg: EmptyMessage, S>(children: &'a mut Vec<ViewElement<Msg, S>>, elements: &mut Vec<&'a mut ViewElement<Msg, S>>) {
+    for child in children.iter_mut() {
+        let is_inline = matches!(child, ViewElement::View(view) if view.is_inline());
+
+        if !is_inline {
+            elements.push(child);
+        } else if let ViewElement::View(view) = child {
+            inline_elements(view.children(), elements);
+        }
+    }
+}
+
+/// Main axis size of each child after growing (or shrinking) the flexible children to fill `available`.
+/// `sizes` are (main axis, cross axis). Children that reach their min/max size stop changing,
+/// and the rest of the space is shared by the others.
+fn distribute_free_space<Min, Max>(sizes: &[(u32, u32)], flexes: &[Option<Flex>], min: Min, max: Max, available: u32) -> Vec<u32>
+where
+    Min: Fn(&Flex) -> u32,
+    Max: Fn(&Flex) -> u32,
+{
+    let used: u32 = sizes.iter().map(|size| size.0).sum();
+    let growing = available > used;
+    let mut free_space = available as f32 - used as f32;
+
+    let mut main_sizes: Vec<f32> = sizes.iter().map(|size| size.0 as f32).collect();
+    // Children which are done changing size
+    let mut frozen: Vec<bool> = flexes.iter().map(|flex| flex.is_none()).collect();
+    let weights: Vec<f32> = flexes.iter().zip(sizes)
+        .map(|(flex, size)| match flex {
+            Some(flex) if growing => flex.grow,
+            // Larger children shrink more
+            Some(flex) => flex.shrink * size.0 as f32,
+            None => 0.0,
+        })
+        .collect();
+
+    while free_space.abs() >= 1.0 {
+        let total_weight: f32 = weights.iter().zip(&frozen)
+            .filter(|(_, frozen)| !**frozen)
+            .map(|(weight, _)| weight)
+            .sum();
+
+        if total_weight <= 0.0 {
+            break;
+        }
+
+        let mut any_clamped = false;
+        let mut distributed = 0.0;
+
+        for index in 0..main_sizes.len() {
+            if frozen[index] || weights[index] <= 0.0 {
+                continue;
+            }
+
+            let flex = flexes[index].as_ref().unwrap();
+            let target = main_sizes[index] + free_space * weights[index] / total_weight;
+            let clamped = target.min(max(flex) as f32).max(min(flex) as f32);
+
+            if clamped != target {
+                frozen[index] = true;
+                any_clamped = true;
+            }
+
+            distributed += clamped - main_sizes[index];
+            main_sizes[index] = clamped;
+        }
+
+        free_space -= distributed;
+
+        if !any_clamped {
+            break;
+        }
+    }
+
+    main_sizes.into_iter().map(|size| size.round() as u32).collect()
+}
+
+impl<Msg: EmptyMessage, S> super::View<Msg, S> for Stack<Msg, S> where Msg: 'static, S: 'static {
+    fn assign_state(&mut self, state: Shared<State<S>>) {
         for child in &mut self.children {
             if let ViewElement::View(view) = child {
                 view.assign_state(state.clone());
@@ -48,121 +202,145 @@ impl<Msg: EmptyMessage> super::View<Msg> for Stack<Msg> where Msg: 'static{
         }
     }
 
-    fn state(&self) -> Shared<State> {
+    fn state(&self) -> Shared<State<S>> {
         self.state.as_ref().unwrap().clone()
     }
 
-    fn set_hook(&mut self, hook: super::ViewHook<Msg>) {
-        self.hook = Some(hook);
+    fn hooks(&mut self) -> &mut Vec<super::ViewHook<Msg, S>> {
+        &mut self.hooks
     }
 
-    fn get_hook(&self) -> Option<&super::ViewHook<Msg>> {
-        self.hook.as_ref()
-    }
+    /// Children are measured at their own size, then flexible children are grown (or shrunk)
+    /// along the main axis and stretched children are measured again at their final size.
+    ///
+    /// Children are aligned within the minimum size of the constraints, or the space they take up if that is larger.
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: BoxConstraints, is_root: bool) -> (u32, u32) {
+        let (main_alignment, cross_alignment) = self.resolved_alignment(theme);
+        let axes = self.axes();
+
+        let mut elements = Vec::with_capacity(self.children.len());
+        inline_elements(&mut self.children, &mut elements);
 
-    // TODO: Right alignment
-    // TODO: Finish Center alignment
-    // TODO: Test with VStack root and HStack root (ensure same behavior)
-    fn layout(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: (u32, u32), is_root: bool) {
-        let alignment = if let Some(alignment) = self.alignment {
-            alignment
+        // Initial padding within window
+        let padding = if is_root {
+            (theme.view_padding.horizontal, theme.view_padding.vertical)
         } else {
-            theme.default_alignment
+            (0, 0)
         };
-        
-        let mut current_x = 0;
-        let mut current_y = 0;
-        let mut view_width = 0;
-        let mut view_height = 0;
+        // Hidden children are skipped (they get no size and no gap)
+        let hidden: Vec<bool> = elements.iter()
+            .map(|child| if let ViewElement::View(view) = &**child { view.is_hidden() } else { false })
+            .collect();
+        let count = hidden.iter().filter(|hidden| !**hidden).count() as u32;
 
-        // Initial padding within window
-        if is_root {
-            if !alignment.is_centered() {
-                current_x += theme.view_padding.horizontal;
-            }
-            // FIXME: Is this correct?
-            current_y += theme.view_padding.vertical;
-        }
-        
-        for child in &mut self.children {
-            let child_width;
-            let child_height;
+        let gap = axes((theme.widget_padding.horizontal, theme.widget_padding.vertical)).0;
+        let gaps = count.saturating_sub(1) * gap;
 
-            match child {
-                ViewElement::View(view) => {
-                    view.layout(renderer, theme, constraints, false);
-                    let size = view.render_size();
-                    child_width = size.0; 
-                    child_height = size.1;
-                    
-                    if alignment.is_centered() {
-                        if self.orientation.is_vertical() {
-                            view.translate((constraints.0 / 2) as i32 - (child_width / 2) as i32, current_y as i32);
-                        }
-                    } else {   
-                        view.translate(current_x as i32, current_y as i32);
-                    }
-                }
-                
+        let inner = constraints.deflate(2 * padding.0, 2 * padding.1);
+        let (min_main, min_cross) = axes(inner.min);
+        let (max_main, max_cross) = axes(inner.max);
+        let unbounded_main = max_main == BoxConstraints::UNBOUNDED;
+
+        // Children are first measured at their own size (within the space between the gaps)
+        let child_max_main = if unbounded_main { max_main } else { max_main.saturating_sub(gaps) };
+        let loose = BoxConstraints::loose(axes((child_max_main, max_cross)));
+
+        let mut sizes = Vec::with_capacity(elements.len());
+        let mut flexes = Vec::with_capacity(elements.len());
+        for (child, hidden) in elements.iter_mut().zip(&hidden) {
+            let (size, flex) = match child {
+                _ if *hidden => ((0, 0), None),
+                ViewElement::View(view) => (view.measure(renderer, theme, loose, false), view.flex().copied()),
                 ViewElement::Widget(widget) => {
-                    let size = widget.render_size(theme);
-                    child_width = size.0; 
-                    child_height = size.1;
-
-                    if alignment.is_centered() {
-                        if self.orientation.is_vertical() {
-                            widget.place((constraints.0 / 2) as i32 - (child_width / 2) as i32, current_y as i32);
-                        }
-                    } else {   
-                        widget.place(current_x as i32, current_y as i32);
-                    }
+                    widget.orient(self.orientation);
+                    (widget.measure(renderer, theme, loose), widget.flex().copied())
                 }
-            }
+            };
 
-            // Determines size of view thus far & where to place next element
-            match self.orientation {
-                Orientation::Vertical => {
-                    current_y += child_height + theme.widget_padding.vertical;
-                    view_width = std::cmp::max(child_width, view_width);
-                }
-                Orientation::Horizontal => {
-                    current_x += child_width + theme.widget_padding.horizontal;
-                    view_height = std::cmp::max(child_height, view_height);
-                }
+            sizes.push(axes(size));
+            flexes.push(flex);
+        }
+
+        // Flexible children fill the maximum size. Without a maximum they only grow to the minimum size.
+        let used: u32 = sizes.iter().map(|size| size.0).sum();
+        let target_main = if unbounded_main {
+            std::cmp::max(min_main.saturating_sub(gaps), used)
+        } else {
+            child_max_main
+        };
+        let main_sizes = distribute_free_space(&sizes, &flexes, |flex| axes(flex.min_size).0, |flex| axes(flex.max_size).0, target_main);
+
+        // Stretched children fill the cross axis of the stack (the largest child or the minimum size)
+        let content_cross = sizes.iter().map(|size| size.1).max().unwrap_or(0);
+        let stretched_cross = std::cmp::max(content_cross, min_cross);
+
+        // Children which were resized are measured again at their final size
+        for (index, child) in elements.iter_mut().enumerate() {
+            let stretch = cross_alignment == CrossAxisAlignment::Stretch || flexes[index].map_or(false, |flex| flex.stretch);
+            if hidden[index] || (main_sizes[index] == sizes[index].0 && !stretch) {
+                continue;
             }
+
+            let cross = if stretch { stretched_cross } else { sizes[index].1 };
+            let tight = BoxConstraints::tight(axes((main_sizes[index], cross)));
+
+            let size = match child {
+                ViewElement::View(view) => view.measure(renderer, theme, tight, false),
+                ViewElement::Widget(widget) => widget.measure(renderer, theme, tight),
+            };
+            sizes[index] = axes(size);
         }
 
-        // NOTE: Subtraction is to remove the extra padding added in final iteration of the above loop
-        if is_root { // Add extra padding at the end of the root view (to match the initial padding)
-            match self.orientation {
-                Orientation::Vertical => {
-                    self.bounds.width = current_x + view_width + theme.view_padding.horizontal;
-                    self.bounds.height = current_y + theme.view_padding.vertical - theme.widget_padding.vertical;
-                }
-                Orientation::Horizontal => {
-                    self.bounds.width = current_x + theme.view_padding.horizontal - theme.widget_padding.horizontal;
-                    self.bounds.height = current_y + view_height + theme.view_padding.vertical;
-                }
+        let content_main = sizes.iter().map(|size| size.0).sum::<u32>() + gaps;
+        let content_cross = sizes.iter().map(|size| size.1).max().unwrap_or(0);
+
+        // Space the children are aligned within
+        let region_main = std::cmp::max(content_main, min_main);
+        let region_cross = std::cmp::max(content_cross, min_cross);
+
+        // Where the first child goes and the space between children
+        let free_space = region_main - content_main;
+        let (mut main, spacing) = match main_alignment {
+            MainAxisAlignment::Start => (0, gap),
+            MainAxisAlignment::Center => (free_space / 2, gap),
+            MainAxisAlignment::End => (free_space, gap),
+            MainAxisAlignment::SpaceBetween if count > 1 => (0, gap + free_space / (count - 1)),
+            MainAxisAlignment::SpaceBetween => (0, gap),
+            MainAxisAlignment::SpaceAround if count > 0 => (free_space / count / 2, gap + free_space / count),
+            MainAxisAlignment::SpaceAround => (0, gap),
+        };
+
+        let (padding_main, padding_cross) = axes(padding);
+        main += padding_main;
+
+        for ((child, (child_main, child_cross)), hidden) in elements.iter_mut().zip(sizes).zip(hidden) {
+            if hidden {
+                continue;
             }
-        } else { // Do not add any more padding --> treat child view just like a widget
-            match self.orientation {
-                Orientation::Vertical => {
-                    self.bounds.width = view_width;
-                    self.bounds.height = current_y - theme.widget_padding.vertical;
-                }
-                Orientation::Horizontal => {
-                    self.bounds.width = current_x - theme.widget_padding.horizontal;
-                    self.bounds.height = view_height;
-                }
+
+            let cross = padding_cross + match cross_alignment {
+                CrossAxisAlignment::Start | CrossAxisAlignment::Stretch => 0,
+                CrossAxisAlignment::Center => region_cross.saturating_sub(child_cross) / 2,
+                CrossAxisAlignment::End => region_cross.saturating_sub(child_cross),
+            };
+
+            let (x, y) = axes((main, cross));
+            match child {
+                ViewElement::View(view) => view.arrange(x as i32, y as i32),
+                ViewElement::Widget(widget) => widget.place(x as i32, y as i32),
             }
-        }
 
-        // Translate the view to be centered vertically within its bounds
-        if alignment.is_centered() {
-            let view_center_y = self.bounds.height / 2;
-            let bounds_center_y = constraints.1 / 2;
-            self.translate(0, bounds_center_y as i32 - view_center_y as i32);
+            main += child_main + spacing;
         }
+
+        let (width, height) = constraints.constrain(axes((region_main + 2 * padding_main, region_cross + 2 * padding_cross)));
+
+        self.bounds.x = 0;
+        self.bounds.y = 0;
+        self.bounds.width = width;
+        self.bounds.height = height;
+
+        (width, height)
     }
 
     fn translate(&mut self, dx: i32, dy: i32) {
@@ -189,42 +367,406 @@ impl<Msg: EmptyMessage> super::View<Msg> for Stack<Msg> where Msg: 'static{
         self.bounds.height
     }
 
-    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg>> {
+    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg, S>> {
         &mut self.children
     }
+
+    fn map(&mut self) -> Option<&mut super::ViewMap> {
+        Some(&mut self.map)
+    }
 }
 
-// TODO: Create a third "Stack" macro that is called by V/HStack
-// with the proper orientation.
-// This prevents the two macros from having duplicated bodies.
+/// Adds the elements of a `VStack!` or `HStack!` body to `$children`.
+///
+/// Each element may be a widget or view, an `Option` or `Vec` of them, or `()`.
+/// `if condition { ... } else { ... }` picks a group of elements when the view is built,
+/// while `if |state| condition { ... } else { ... }` creates a `Conditional` which switches
+/// between the groups whenever the condition changes. Conditions cannot contain `{}` blocks.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __StackChildren {
+    ( $orientation:expr; $children:ident; ) => {};
+
+    // Checked whenever the state changes
+    ( $orientation:expr; $children:ident; if | $param:ident | $($rest:tt)+ ) => {
+        $crate::__StackChildren!(@dynamic $orientation; $children; $param; []; $($rest)+)
+    };
 
+    // Checked when the view is built
+    ( $orientation:expr; $children:ident; if $($rest:tt)+ ) => {
+        $crate::__StackChildren!(@static $orientation; $children; []; $($rest)+)
+    };
 
-/// Builds a view with `Orientation::Vertical` 
+    ( $orientation:expr; $children:ident; $component:expr $(, $($rest:tt)*)? ) => {
+        $crate::InsertViewElement::insert_elements($component, &mut $children);
+        $crate::__StackChildren!($orientation; $children; $($($rest)*)?);
+    };
+
+    // Collects the tokens of the condition up to the first block
+    ( @dynamic $orientation:expr; $children:ident; $param:ident; [$($condition:tt)*]; { $($then:tt)* } else { $($otherwise:tt)* } $(, $($rest:tt)*)? ) => {
+        $children.push($crate::ViewElement::View(Box::new($crate::view::Conditional::new(
+            $orientation,
+            move |mut $param: std::cell::RefMut<$crate::state::State<_>>| -> bool { $($condition)* },
+            $crate::__StackChildren!(@group $orientation; $($then)*),
+            $crate::__StackChildren!(@group $orientation; $($otherwise)*),
+        ))));
+        $crate::__StackChildren!($orientation; $children; $($($rest)*)?);
+    };
+    ( @dynamic $orientation:expr; $children:ident; $param:ident; [$($condition:tt)*]; { $($then:tt)* } $(, $($rest:tt)*)? ) => {
+        $crate::__StackChildren!(@dynamic $orientation; $children; $param; [$($condition)*]; { $($then)* } else {} $(, $($rest)*)?);
+    };
+    ( @dynamic $orientation:expr; $children:ident; $param:ident; [$($condition:tt)*]; $next:tt $($rest:tt)* ) => {
+        $crate::__StackChildren!(@dynamic $orientation; $children; $param; [$($condition)* $next]; $($rest)*);
+    };
+
+    ( @static $orientation:expr; $children:ident; [$($condition:tt)*]; { $($then:tt)* } else { $($otherwise:tt)* } $(, $($rest:tt)*)? ) => {
+        if $($condition)* {
+            $crate::__StackChildren!($orientation; $children; $($then)*);
+        } else {
+            $crate::__StackChildren!($orientation; $children; $($otherwise)*);
+        }
+        $crate::__StackChildren!($orientation; $children; $($($rest)*)?);
+    };
+    ( @static $orientation:expr; $children:ident; [$($condition:tt)*]; { $($then:tt)* } $(, $($rest:tt)*)? ) => {
+        $crate::__StackChildren!(@static $orientation; $children; [$($condition)*]; { $($then)* } else {} $(, $($rest)*)?);
+    };
+    ( @static $orientation:expr; $children:ident; [$($condition:tt)*]; $next:tt $($rest:tt)* ) => {
+        $crate::__StackChildren!(@static $orientation; $children; [$($condition)* $next]; $($rest)*);
+    };
+
+    // Elements of one branch of a `Conditional`
+    ( @group $orientation:expr; $($elements:tt)* ) => {{
+        let mut group = Vec::new();
+        $crate::__StackChildren!($orientation; group; $($elements)*);
+        group
+    }};
+}
+
+/// Builds a view with `Orientation::Vertical`.
+///
+/// Elements may also be optional (`Option<T>`), lists (`Vec<T>`), or `if`/`else` groups:
+/// ```ignore
+/// VStack! {
+///     Text::new("title"),
+///     if show_details {
+///         Text::new("details"),
+///     },
+///     if |state| @logged_in {
+///         Button::new("logout"),
+///     } else {
+///         Button::new("login"),
+///     },
+/// }
+/// ```
 #[macro_export]
 macro_rules! VStack {
-    ( $($component:expr),+ $(,)? ) => {{
+    ( $($elements:tt)* ) => {{
         let mut children = Vec::new();
-
-        $(
-            let child = $component.into_element();
-            children.push(child);
-        )+
+        $crate::__StackChildren!(Orientation::Vertical; children; $($elements)*);
 
         Stack::new(Orientation::Vertical, children)
     }};
 }
 
-/// Builds a view with `Orientation::Horizontal` 
+/// Builds a view with `Orientation::Horizontal`. Accepts the same elements as `VStack!`.
 #[macro_export]
 macro_rules! HStack {
-    ( $($component:expr),+ $(,)? ) => {{
+    ( $($elements:tt)* ) => {{
         let mut children = Vec::new();
-
-        $(
-            let child = $component.into_element();
-            children.push(child);
-        )+
+        $crate::__StackChildren!(Orientation::Horizontal; children; $($elements)*);
 
         Stack::new(Orientation::Horizontal, children)
     }};
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::view::{Block, layout_blocks};
+
+    fn loose(width: u32, height: u32) -> BoxConstraints {
+        BoxConstraints::loose((width, height))
+    }
+
+    #[test]
+    fn flexible_children_share_the_free_space() {
+        let mut view: Stack<(), ()> = HStack! {
+            Block::new("", 50, 20),
+            Flexible::new(Block::new("", 10, 20)).grow(1.0),
+            Flexible::new(Block::new("", 10, 20)).grow(3.0),
+        };
+
+        // 400 - 2 gaps of 15 -> 300 free pixels split 1:3
+        assert_eq!(layout_blocks(&mut view, loose(400, 100)), vec![
+            (0, 0, 50, 20),
+            (65, 0, 85, 20),
+            (165, 0, 235, 20),
+        ]);
+        assert_eq!(view.render_size(), (400, 20));
+    }
+
+    #[test]
+    fn growing_stops_at_the_maximum_size() {
+        let mut view: Stack<(), ()> = HStack! {
+            Flexible::new(Block::new("", 10, 20)).fill().max_size(50, 100),
+            Flexible::new(Block::new("", 10, 20)).fill(),
+        };
+
+        // The space the first child could not take goes to the second
+        assert_eq!(layout_blocks(&mut view, loose(400, 100)), vec![
+            (0, 0, 50, 20),
+            (65, 0, 335, 20),
+        ]);
+    }
+
+    #[test]
+    fn children_shrink_when_the_stack_overflows() {
+        let mut view: Stack<(), ()> = HStack! {
+            Flexible::new(Block::new("", 200, 20)),
+            Flexible::new(Block::new("", 100, 20)),
+        };
+
+        // 30 pixels too many, taken from each child by its size
+        assert_eq!(layout_blocks(&mut view, loose(285, 100)), vec![
+            (0, 0, 180, 20),
+            (195, 0, 90, 20),
+        ]);
+
+        let mut view: Stack<(), ()> = HStack! {
+            Flexible::new(Block::new("", 200, 20)).shrink(0.0),
+            Flexible::new(Block::new("", 100, 20)).min_size(80, 0),
+            Flexible::new(Block::new("", 100, 20)),
+        };
+
+        // The second child stops at its minimum size, so the third shrinks further
+        assert_eq!(layout_blocks(&mut view, loose(380, 100)), vec![
+            (0, 0, 200, 20),
+            (215, 0, 80, 20),
+            (310, 0, 70, 20),
+        ]);
+    }
+
+    #[test]
+    fn stretched_children_fill_the_cross_axis() {
+        let mut view: Stack<(), ()> = VStack! {
+            Block::new("", 100, 20),
+            Flexible::new(Block::new("", 30, 20)).stretch(),
+            Block::new("", 40, 20),
+        };
+
+        assert_eq!(layout_blocks(&mut view, loose(400, 400)), vec![
+            (0, 0, 100, 20),
+            (0, 30, 100, 20),
+            (0, 60, 40, 20),
+        ]);
+
+        // Stretching the stack stretches every child
+        let mut view: Stack<(), ()> = VStack! {
+            Block::new("", 100, 20),
+            Block::new("", 40, 20),
+        }
+        .stretch();
+
+        assert_eq!(layout_blocks(&mut view, BoxConstraints::new((150, 0), (400, 400))), vec![
+            (0, 0, 150, 20),
+            (0, 30, 150, 20),
+        ]);
+    }
+
+    /// Lays out blocks of (main, cross) sizes 20x10, 30x20 and 40x30 in a 200x50 (main x cross) stack.
+    /// Returns the (main, cross) position of each block and the space between children.
+    fn align(orientation: Orientation, main: MainAxisAlignment, cross: CrossAxisAlignment) -> (Vec<(i32, i32)>, i32) {
+        let vertical = orientation.is_vertical();
+        let axes = move |(main, cross): (u32, u32)| if vertical { (cross, main) } else { (main, cross) };
+
+        let children = vec![(20, 10), (30, 20), (40, 30)].into_iter()
+            .map(|size| {
+                let (width, height) = axes(size);
+                Block::new("", width, height).into_element()
+            })
+            .collect();
+
+        let mut view: Stack<(), ()> = Stack::new(orientation, children)
+            .main_alignment(main)
+            .cross_alignment(cross);
+
+        let positions = layout_blocks(&mut view, BoxConstraints::tight(axes((200, 50))))
+            .into_iter()
+            .map(|(x, y, _, _)| if vertical { (y, x) } else { (x, y) })
+            .collect();
+
+        let padding = &DEFAULT_THEME.widget_padding;
+        let gap = if vertical { padding.vertical } else { padding.horizontal };
+
+        (positions, gap as i32)
+    }
+
+    #[test]
+    fn main_axis_alignment_is_the_same_for_both_orientations() {
+        for orientation in vec![Orientation::Horizontal, Orientation::Vertical] {
+            let (start, gap) = align(orientation, MainAxisAlignment::Start, CrossAxisAlignment::Start);
+            let starts: Vec<i32> = start.iter().map(|position| position.0).collect();
+            assert_eq!(starts, vec![0, 20 + gap, 50 + 2 * gap]);
+
+            let free = 200 - (90 + 2 * gap);
+            let mains = |alignment| -> Vec<i32> {
+                align(orientation, alignment, CrossAxisAlignment::Start).0.iter().map(|position| position.0).collect()
+            };
+
+            assert_eq!(mains(MainAxisAlignment::Center), starts.iter().map(|main| main + free / 2).collect::<Vec<_>>());
+            assert_eq!(mains(MainAxisAlignment::End), starts.iter().map(|main| main + free).collect::<Vec<_>>());
+            assert_eq!(mains(MainAxisAlignment::SpaceBetween), vec![0, 20 + gap + free / 2, 50 + 2 * (gap + free / 2)]);
+
+            let around = free / 3;
+            assert_eq!(mains(MainAxisAlignment::SpaceAround), vec![around / 2, around / 2 + 20 + gap + around, around / 2 + 50 + 2 * (gap + around)]);
+        }
+    }
+
+    #[test]
+    fn cross_axis_alignment_is_the_same_for_both_orientations() {
+        for orientation in vec![Orientation::Horizontal, Orientation::Vertical] {
+            let crosses = |alignment| -> Vec<i32> {
+                align(orientation, MainAxisAlignment::Start, alignment).0.iter().map(|position| position.1).collect()
+            };
+
+            assert_eq!(crosses(CrossAxisAlignment::Start), vec![0, 0, 0]);
+            assert_eq!(crosses(CrossAxisAlignment::Center), vec![20, 15, 10]);
+            assert_eq!(crosses(CrossAxisAlignment::End), vec![40, 30, 20]);
+        }
+    }
+
+    #[test]
+    fn alignment_applies_horizontally() {
+        let positions = |orientation, alignment| -> Vec<(i32, i32)> {
+            let mut view: Stack<(), ()> = Stack::new(orientation, vec![Block::new("", 20, 10).into_element()]).alignment(alignment);
+            layout_blocks(&mut view, BoxConstraints::tight((200, 50))).into_iter().map(|(x, y, _, _)| (x, y)).collect()
+        };
+
+        assert_eq!(positions(Orientation::Vertical, Alignment::Right), vec![(180, 0)]);
+        assert_eq!(positions(Orientation::Horizontal, Alignment::Right), vec![(180, 0)]);
+        assert_eq!(positions(Orientation::Vertical, Alignment::Center), vec![(90, 20)]);
+        assert_eq!(positions(Orientation::Horizontal, Alignment::Center), vec![(90, 20)]);
+    }
+
+    #[test]
+    fn nested_stacks_are_aligned_within_their_parent() {
+        let mut view: Stack<(), ()> = VStack! {
+            HStack! {
+                Block::new("", 20, 10),
+                Block::new("", 20, 10),
+            },
+        }
+        .alignment(Alignment::Center);
+
+        let gap = DEFAULT_THEME.widget_padding.horizontal as i32;
+        let width = 40 + gap;
+        assert_eq!(layout_blocks(&mut view, BoxConstraints::tight((200, 50))), vec![
+            ((200 - width) / 2, 20, 20, 10),
+            ((200 - width) / 2 + 20 + gap, 20, 20, 10),
+        ]);
+    }
+
+    #[test]
+    fn root_stacks_are_padded_and_arranged_after_measuring() {
+        let mut renderer = crate::render::headless::test_renderer();
+        let mut view: Stack<(), ()> = VStack! {
+            Block::new("", 20, 10),
+        };
+        view._init(&mut renderer, &DEFAULT_THEME, true);
+
+        // Measuring lays out the contents at the origin (within the window padding of a root view)
+        let size = view.measure(&mut renderer, &DEFAULT_THEME, BoxConstraints::loose((200, 100)), true);
+        let padding = &DEFAULT_THEME.view_padding;
+        assert_eq!(size, (20 + 2 * padding.horizontal, 10 + 2 * padding.vertical));
+
+        // Measuring again gives the same layout
+        assert_eq!(view.measure(&mut renderer, &DEFAULT_THEME, BoxConstraints::loose((200, 100)), true), size);
+
+        // Arranging moves the contents without measuring them again
+        view.arrange(5, 7);
+        let block = match &mut view.children()[0] {
+            ViewElement::Widget(widget) => (**widget).as_any().downcast_ref::<Block>().unwrap().bounds,
+            _ => unreachable!(),
+        };
+        assert_eq!((block.x, block.y), (5 + padding.horizontal as i32, 7 + padding.vertical as i32));
+    }
+
+    /// Lays out the view with `theme` and returns the size of each child
+    fn child_sizes(view: &mut Stack<(), ()>, theme: &Theme, constraints: BoxConstraints) -> Vec<(u32, u32)> {
+        let mut renderer = crate::render::headless::test_renderer();
+        view._init(&mut renderer, theme, true);
+        view.measure(&mut renderer, theme, constraints, false);
+        view.arrange(0, 0);
+
+        view.children().iter().map(|child| match child {
+            ViewElement::Widget(widget) => widget.render_size(theme),
+            ViewElement::View(view) => view.render_size(),
+        }).collect()
+    }
+
+    #[test]
+    fn spacers_add_space_along_the_stack() {
+        let mut view: Stack<(), ()> = VStack! {
+            Block::new("", 20, 20),
+            Spacer::new(40),
+            Block::new("", 20, 20),
+        };
+
+        // The spacer only takes space along the stack
+        assert_eq!(layout_blocks(&mut view, loose(400, 400)), vec![
+            (0, 0, 20, 20),
+            (0, 80, 20, 20),
+        ]);
+        assert_eq!(view.render_size(), (20, 100));
+    }
+
+    #[test]
+    fn flexible_spacers_share_the_free_space() {
+        let mut view: Stack<(), ()> = HStack! {
+            Block::new("", 50, 20),
+            Spacer::flexible(),
+            Block::new("", 50, 20),
+            Spacer::flexible(),
+            Block::new("", 50, 20),
+        };
+
+        // 400 - 150 - 4 gaps of 15 -> 95 pixels for each spacer
+        assert_eq!(layout_blocks(&mut view, loose(400, 100)), vec![
+            (0, 0, 50, 20),
+            (175, 0, 50, 20),
+            (350, 0, 50, 20),
+        ]);
+        assert_eq!(child_sizes(&mut view, &DEFAULT_THEME, loose(400, 100))[1], (95, 0));
+    }
+
+    #[test]
+    fn dividers_span_the_stack_with_the_theme_thickness() {
+        let thickness = DEFAULT_THEME.widget_styles.dividers.thickness;
+
+        let mut view: Stack<(), ()> = VStack! {
+            Block::new("", 100, 20),
+            Divider::new(),
+            Block::new("", 40, 20),
+        };
+        assert_eq!(child_sizes(&mut view, &DEFAULT_THEME, loose(400, 400))[1], (100, thickness));
+        assert_eq!(layout_blocks(&mut view, loose(400, 400))[1], (0, 20 + 10 + thickness as i32 + 10, 40, 20));
+
+        let mut view: Stack<(), ()> = HStack! {
+            Block::new("", 20, 30),
+            Divider::new(),
+            Block::new("", 20, 10),
+        };
+        assert_eq!(child_sizes(&mut view, &DEFAULT_THEME, loose(400, 400))[1], (thickness, 30));
+
+        // The theme's thickness is used unless one is given
+        let mut theme = DEFAULT_THEME;
+        theme.widget_styles.dividers.thickness = 6;
+
+        let mut view: Stack<(), ()> = VStack! {
+            Block::new("", 100, 20),
+            Divider::new(),
+            Divider::new().thickness(1),
+        };
+        assert_eq!(child_sizes(&mut view, &theme, loose(400, 400)), vec![(100, 20), (100, 6), (100, 1)]);
+    }
+}
@@ -0,0 +1,608 @@
+use crate::state::{Shared, State};
+use crate::bounding_rect::BoundingRect;
+use crate::widget::{Text, Widget};
+use crate::view_element::*;
+
+use std::cell::RefMut;
+
+use super::{View, MutationError};
+
+/// Clickable title of a page
+struct TabHeader<Msg, S> {
+    title: String,
+    text: Option<Text<Msg, S>>,
+    bounds: BoundingRect,
+}
+
+/// Shows a row of tab headers and one page at a time.
+///
+/// Only the visible page receives events and messages.
+///
+/// Usage:
+/// ```ignore
+/// TabView! {
+///     "Home" => VStack! { ... },
+///     "Settings" => VStack! { ... },
+/// }
+/// .on_select(|index, mut state| {
+///     Message::TabChanged
+/// })
+/// ```
+pub struct TabView<Msg: EmptyMessage, S> {
+    state: Option<Shared<State<S>>>,
+    /// One page per tab
+    children: Vec<ViewElement<Msg, S>>,
+    headers: Vec<TabHeader<Msg, S>>,
+
+    hooks: Vec<super::ViewHook<Msg, S>>,
+    map: super::ViewMap,
+
+    bounds: BoundingRect,
+    header_height: u32,
+    header_scale: Option<f32>,
+
+    selected: usize,
+    on_select: Option<Box<dyn FnMut(usize, RefMut<State<S>>) -> Msg>>,
+    // Register a click only when mouse-down *and* mouse-up occur within the same tab
+    pressed_tab: Option<usize>,
+}
+
+impl<Msg: EmptyMessage + 'static, S: 'static> TabView<Msg, S> {
+    pub fn new() -> Self {
+        Self {
+            state: None,
+            children: Vec::new(),
+            headers: Vec::new(),
+            hooks: Vec::new(),
+            map: super::ViewMap::new(),
+            bounds: BoundingRect::new(),
+            header_height: 0,
+            header_scale: None,
+            selected: 0,
+            on_select: None,
+            pressed_tab: None,
+        }
+    }
+
+    /// Adds a page with a tab titled `title`
+    pub fn tab<ImplConstraint, E: IntoViewElement<Msg, S, ImplConstraint>>(mut self, title: &str, page: E) -> Self {
+        self.push(title, page.into_element());
+        self
+    }
+
+    /// Adds a page with a tab titled `title`
+    pub fn push(&mut self, title: &str, page: ViewElement<Msg, S>) {
+        self.headers.push(TabHeader {
+            title: title.to_owned(),
+            text: None,
+            bounds: BoundingRect::new(),
+        });
+        self.children.push(page);
+    }
+
+    /// The initially selected tab
+    pub fn selected(mut self, index: usize) -> Self {
+        self.select(index);
+        self
+    }
+
+    /// Text scale of the tab titles. Defaults to the theme's text scale.
+    pub fn header_scale(mut self, scale: f32) -> Self {
+        self.header_scale = Some(scale);
+        self
+    }
+
+    /// Called when the user selects a different tab (with the index of the new tab)
+    pub fn on_select<F: FnMut(usize, RefMut<State<S>>) -> Msg + 'static>(mut self, callback: F) -> Self {
+        self.on_select = Some(Box::new(callback));
+        self
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn tab_count(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Shows the page at `index`. Does not call `on_select`.
+    pub fn select(&mut self, index: usize) {
+        if index >= self.children.len() {
+            panic!("Tab index {} is out of bounds (TabView has {} tabs)", index, self.children.len());
+        }
+
+        self.selected = index;
+        self.pressed_tab = None;
+    }
+
+    fn visible_page(&mut self) -> Option<&mut ViewElement<Msg, S>> {
+        self.children.get_mut(self.selected)
+    }
+
+    fn header_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.headers.iter().position(|header| header.bounds.contains(x, y))
+    }
+}
+
+impl<Msg: EmptyMessage, S> View<Msg, S> for TabView<Msg, S> where Msg: 'static, S: 'static {
+    fn state(&self) -> Shared<State<S>> {
+        self.state.as_ref().unwrap().clone()
+    }
+
+    fn assign_state(&mut self, state: Shared<State<S>>) {
+        for child in &mut self.children {
+            if let ViewElement::View(view) = child {
+                view.assign_state(state.clone());
+            }
+        }
+
+        self.state = Some(state);
+    }
+
+    fn hooks(&mut self) -> &mut Vec<super::ViewHook<Msg, S>> {
+        &mut self.hooks
+    }
+
+    fn children(&mut self) -> &mut Vec<crate::ViewElement<Msg, S>> {
+        &mut self.children
+    }
+
+    fn map(&mut self) -> Option<&mut super::ViewMap> {
+        Some(&mut self.map)
+    }
+
+    /// Pages can only be added with a title (see `TabView::tab`)
+    fn can_insert_child(&mut self) -> Result<(), MutationError> {
+        Err(MutationError::Unsupported { view: "TabView", reason: "pages cannot be inserted without a title, so use `replace` to change a page" })
+    }
+
+    /// Removes the tab. The selected tab stays selected (or the previous tab if it was removed).
+    fn remove_child(&mut self, index: usize) -> Result<ViewElement<Msg, S>, MutationError> {
+        self.headers.remove(index);
+        let page = self.children.remove(index);
+
+        if index < self.selected || self.selected >= self.children.len() {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        self.pressed_tab = None;
+
+        Ok(page)
+    }
+
+    fn init(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme) {
+        let scale = self.header_scale.unwrap_or(theme.text.scale);
+
+        for header in &mut self.headers {
+            let mut text = Text::new("")
+                .text(&header.title)
+                .scale(scale);
+            text.init(renderer, theme);
+
+            header.text = Some(text);
+        }
+    }
+
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: super::BoxConstraints, is_root: bool) -> (u32, u32) {
+        // Initial padding within window
+        let padding = if is_root {
+            (theme.view_padding.horizontal, theme.view_padding.vertical)
+        } else {
+            (0, 0)
+        };
+
+        let header_padding = &theme.widget_padding;
+        let text_height = self.headers.iter()
+            .filter_map(|header| header.text.as_ref())
+            .map(|text| text.bounds.height)
+            .max()
+            .unwrap_or(0);
+        self.header_height = text_height + 2 * header_padding.vertical;
+
+        // Headers are placed side by side
+        let mut header_x = padding.0;
+        for header in &mut self.headers {
+            let text = header.text.as_mut().unwrap();
+            let (text_width, text_height) = text.bounds.dimensions();
+
+            header.bounds.x = header_x as i32;
+            header.bounds.y = padding.1 as i32;
+            header.bounds.width = text_width + 2 * header_padding.horizontal;
+            header.bounds.height = self.header_height;
+
+            text.place(
+                (header_x + header_padding.horizontal) as i32,
+                (padding.1 + (self.header_height - text_height) / 2) as i32,
+            );
+
+            header_x += header.bounds.width;
+        }
+        let headers_width = header_x - padding.0;
+
+        // Every page is measured so that switching tabs does not change the view's size
+        let page_y = padding.1 + self.header_height + theme.widget_padding.vertical;
+        let page_constraints = constraints
+            .deflate(2 * padding.0, page_y + padding.1)
+            .loosen();
+
+        let mut pages_width = 0;
+        let mut pages_height = 0;
+        for page in &mut self.children {
+            let (width, height) = match page {
+                ViewElement::View(view) => {
+                    let size = view.measure(renderer, theme, page_constraints, false);
+                    view.arrange(padding.0 as i32, page_y as i32);
+                    size
+                }
+                ViewElement::Widget(widget) => {
+                    let size = widget.measure(renderer, theme, page_constraints);
+                    widget.place(padding.0 as i32, page_y as i32);
+                    size
+                }
+            };
+
+            pages_width = std::cmp::max(pages_width, width);
+            pages_height = std::cmp::max(pages_height, height);
+        }
+
+        let (width, height) = constraints.constrain((
+            std::cmp::max(headers_width, pages_width) + 2 * padding.0,
+            page_y + pages_height + padding.1,
+        ));
+
+        self.bounds.x = 0;
+        self.bounds.y = 0;
+        self.bounds.width = width;
+        self.bounds.height = height;
+
+        (width, height)
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+
+        for header in &mut self.headers {
+            header.bounds.x += dx;
+            header.bounds.y += dy;
+
+            if let Some(text) = &mut header.text {
+                text.translate(dx, dy);
+            }
+        }
+
+        for child in &mut self.children {
+            match child {
+                ViewElement::Widget(widget) => {
+                    widget.translate(dx, dy);
+                }
+                ViewElement::View(view) => {
+                    view.translate(dx, dy);
+                }
+            }
+        }
+    }
+
+    fn render_width(&self) -> u32 {
+        self.bounds.width
+    }
+
+    fn render_height(&self) -> u32 {
+        self.bounds.height
+    }
+
+    fn render(&mut self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme) {
+        if let (Some(first), Some(last)) = (self.headers.first(), self.headers.last()) {
+            // Header bar
+            renderer.draw(crate::render::DrawCommand::Rect {
+                top_left: first.bounds.top_left(),
+                width: (last.bounds.x + last.bounds.width as i32 - first.bounds.x) as u32,
+                height: self.header_height,
+                color: theme.colors.secondary,
+            });
+
+            let selected = &self.headers[self.selected].bounds;
+            renderer.draw(crate::render::DrawCommand::Rect {
+                top_left: selected.top_left(),
+                width: selected.width,
+                height: selected.height,
+                color: theme.colors.accent,
+            });
+        }
+
+        for header in &self.headers {
+            if let Some(text) = &header.text {
+                text.render(renderer, theme);
+            }
+        }
+
+        match self.visible_page() {
+            Some(ViewElement::View(view)) => view.render(renderer, theme),
+            Some(ViewElement::Widget(widget)) => widget.render(renderer, theme),
+            None => {}
+        }
+    }
+
+    fn propogate_event(&mut self, event: &crate::event::ApplicationEvent, message_queue: &mut crate::MessageQueue<Msg>) -> bool {
+        use crate::event::*;
+
+        match event {
+            ApplicationEvent::MouseButton { state: ButtonState::Pressed, button: MouseButton::Left, position: (x, y) } => {
+                if let Some(index) = self.header_at(*x, *y) {
+                    self.pressed_tab = Some(index);
+                    return false;
+                }
+            }
+
+            ApplicationEvent::MouseButton { state: ButtonState::Released, button: MouseButton::Left, position: (x, y) } => {
+                let pressed_tab = self.pressed_tab.take();
+
+                if let Some(index) = self.header_at(*x, *y) {
+                    if pressed_tab == Some(index) && index != self.selected {
+                        self.select(index);
+
+                        if let Some(on_select) = &mut self.on_select {
+                            let state = self.state.as_ref().unwrap().clone();
+                            message_queue.push((on_select)(index, state.borrow_mut()));
+                        }
+
+                        return true;
+                    }
+
+                    return false;
+                }
+            }
+
+            _ => {}
+        }
+
+        let state = self.state();
+        match self.visible_page() {
+            Some(ViewElement::View(view)) => view.propogate_event(event, message_queue),
+            Some(ViewElement::Widget(widget)) => {
+                match widget.handle_event(event, state.borrow_mut(), message_queue) {
+                    crate::EventResponse::Redraw => true,
+                    _ => false,
+                }
+            }
+            None => false,
+        }
+    }
+
+    fn propogate_message(&mut self, message: &Msg) -> bool {
+        let state = self.state();
+
+        // The pages were changed by a hook (see `View::delete`)
+        let mut should_resize = self.map.take_changed();
+
+        should_resize |= match self.visible_page() {
+            Some(ViewElement::View(view)) => view.propogate_message(message),
+            Some(ViewElement::Widget(widget)) => {
+                widget.handle_message(message, state.borrow_mut());
+                widget.check_if_should_resize_then_reset_to_false()
+            }
+            None => false,
+        };
+
+        super::mark_stale_if_nested_changed(self);
+        should_resize
+    }
+
+    fn focusable_widgets(&mut self) -> Vec<&'static str> {
+        match self.visible_page() {
+            Some(ViewElement::View(view)) => view.focusable_widgets(),
+            Some(ViewElement::Widget(widget)) => {
+                if widget.is_focusable() && !crate::id::is_anonymous(widget.id()) {
+                    vec![widget.id()]
+                } else {
+                    Vec::new()
+                }
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn focusable_widget_at(&mut self, x: i32, y: i32) -> Option<&'static str> {
+        match self.visible_page() {
+            Some(ViewElement::View(view)) => view.focusable_widget_at(x, y),
+            Some(ViewElement::Widget(widget)) => {
+                if widget.is_focusable() && !crate::id::is_anonymous(widget.id()) && widget.contains_point(x, y) {
+                    Some(widget.id())
+                } else {
+                    None
+                }
+            }
+            None => None,
+        }
+    }
+}
+
+/// Builds a `TabView` from `"title" => page` pairs
+#[macro_export]
+macro_rules! TabView {
+    ( $($title:expr => $page:expr),+ $(,)? ) => {{
+        let mut tab_view = TabView::new();
+
+        $(
+            tab_view.push($title, $page.into_element());
+        )+
+
+        tab_view
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::event::{ApplicationEvent, ButtonState, MouseButton};
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Clicked {
+        First,
+        Second,
+        Tab(usize),
+    }
+
+    impl EmptyMessage for Clicked {
+        fn is_message(&self) -> bool {
+            true
+        }
+    }
+
+    /// Two tabs with a button on each page, laid out at the origin
+    fn tabs() -> TabView<Clicked, ()> {
+        let mut view = TabView! {
+            "First" => Button::new("first").on_click(|_| Clicked::First),
+            "Second" => Button::new("second").on_click(|_| Clicked::Second),
+        }
+        .on_select(|index, _| Clicked::Tab(index));
+
+        view.assign_state(crate::state::make_shared(crate::state::State::new(())));
+
+        let mut renderer = crate::render::headless::test_renderer();
+        let mut recorder = crate::render::headless::CommandRecorder::new();
+        recorder.record_view(&mut view, &mut renderer, &DEFAULT_THEME, (300, 200));
+
+        view
+    }
+
+    /// Presses and releases the left mouse button. Returns the messages.
+    fn click(view: &mut TabView<Clicked, ()>, x: i32, y: i32) -> Vec<Clicked> {
+        let mut message_queue = crate::MessageQueue::new();
+
+        view.propogate_event(&ApplicationEvent::MouseButton { state: ButtonState::Pressed, button: MouseButton::Left, position: (x, y) }, &mut message_queue);
+        view.propogate_event(&ApplicationEvent::MouseButton { state: ButtonState::Released, button: MouseButton::Left, position: (x, y) }, &mut message_queue);
+
+        message_queue.drain().collect()
+    }
+
+    fn center(bounds: &BoundingRect) -> (i32, i32) {
+        (bounds.x + bounds.width as i32 / 2, bounds.y + bounds.height as i32 / 2)
+    }
+
+    fn page_button_center(view: &mut TabView<Clicked, ()>) -> (i32, i32) {
+        match &view.children()[0] {
+            ViewElement::Widget(widget) => center(widget.focus_bounds().unwrap()),
+            ViewElement::View(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn clicking_a_header_selects_its_tab() {
+        let mut view = tabs();
+        assert_eq!(view.selected_index(), 0);
+
+        let (x, y) = center(&view.headers[1].bounds);
+        assert_eq!(click(&mut view, x, y), vec![Clicked::Tab(1)]);
+        assert_eq!(view.selected_index(), 1);
+
+        // The selected tab does not notify again
+        assert_eq!(click(&mut view, x, y), vec![]);
+
+        // Pressing on one header and releasing on another does nothing
+        let mut message_queue = crate::MessageQueue::new();
+        let (other_x, other_y) = center(&view.headers[0].bounds);
+        view.propogate_event(&ApplicationEvent::MouseButton { state: ButtonState::Pressed, button: MouseButton::Left, position: (other_x, other_y) }, &mut message_queue);
+        view.propogate_event(&ApplicationEvent::MouseButton { state: ButtonState::Released, button: MouseButton::Left, position: (x, y) }, &mut message_queue);
+        assert_eq!(message_queue.drain().count(), 0);
+        assert_eq!(view.selected_index(), 1);
+    }
+
+    #[test]
+    fn hidden_pages_get_no_events() {
+        let mut view = tabs();
+
+        // Both pages are placed at the same position
+        let (x, y) = page_button_center(&mut view);
+        assert_eq!(click(&mut view, x, y), vec![Clicked::First]);
+        assert_eq!(view.focusable_widgets(), vec!["first"]);
+        assert_eq!(view.focusable_widget_at(x, y), Some("first"));
+
+        view.select(1);
+        assert_eq!(click(&mut view, x, y), vec![Clicked::Second]);
+        assert_eq!(view.focusable_widgets(), vec!["second"]);
+        assert_eq!(view.focusable_widget_at(x, y), Some("second"));
+    }
+
+    #[test]
+    fn pages_changed_by_hooks_are_laid_out() {
+        let mut view: TabView<(), ()> = TabView! {
+            "First" => Button::new("first"),
+            "Second" => Button::new("second"),
+        };
+        view.add_hook(Box::new(|view, _| {
+            view.delete("second").ok();
+        }));
+        view.assign_state(crate::state::make_shared(crate::state::State::new(())));
+        view.check_if_should_resize();
+
+        // Removing the hidden page changes the headers
+        crate::view::call_hooks(&mut view, &());
+        assert!(view.propogate_message(&()));
+        assert_eq!(view.tab_count(), 1);
+
+        // Nothing changed since
+        crate::view::call_hooks(&mut view, &());
+        assert!(!view.propogate_message(&()));
+    }
+
+    struct Items {
+        items: Vec<u32>,
+    }
+
+    #[test]
+    fn changes_within_pages_reach_the_tab_view() {
+        let page = ForEach::new(|state: &State<Items>| &state.fields.items, |item: &u32| *item, |item| {
+            Button::new(crate::id::intern(format!("item_{}", item)))
+        });
+
+        let mut view: TabView<(), Items> = TabView! {
+            "Items" => page,
+        };
+        let state = crate::state::make_shared(State::new(Items { items: vec![1] }));
+        view.assign_state(state.clone());
+
+        let mut renderer = crate::render::headless::test_renderer();
+        crate::render::headless::CommandRecorder::new().record_view(&mut view, &mut renderer, &DEFAULT_THEME, (300, 200));
+        assert!(get_widget_by_id::<Button<(), Items>, (), Items>(&mut view, "item_1").is_ok());
+
+        // The page changes while the message is passed on
+        state.borrow_mut().fields.items.push(2);
+        assert!(view.propogate_message(&()));
+
+        // The tab view's map is rebuilt on the next lookup
+        assert!(!view.map().unwrap().is_valid());
+        assert!(get_widget_by_id::<Button<(), Items>, (), Items>(&mut view, "item_2").is_ok());
+    }
+
+    #[test]
+    fn removing_tabs_keeps_the_selection_in_range() {
+        let mut view: TabView<(), ()> = TabView! {
+            "A" => Button::new("a"),
+            "B" => Button::new("b"),
+            "C" => Button::new("c"),
+            "D" => Button::new("d"),
+        };
+
+        // Removing the selected last tab selects the one before it
+        view.select(3);
+        view.delete("d").unwrap();
+        assert_eq!(view.selected_index(), 2);
+
+        // Removing a tab before the selected one keeps the same page selected
+        view.delete("a").unwrap();
+        assert_eq!(view.selected_index(), 1);
+        assert_eq!(view.headers[view.selected_index()].title, "C");
+
+        // Removing a tab after the selected one changes nothing
+        view.select(0);
+        view.delete("c").unwrap();
+        assert_eq!(view.selected_index(), 0);
+
+        // Removing the only tab
+        view.delete("b").unwrap();
+        assert_eq!(view.selected_index(), 0);
+        assert_eq!(view.tab_count(), 0);
+    }
+}
@@ -6,23 +6,26 @@ use std::cell::RefMut;
 
 use super::Widget;
 
-pub struct Button<Msg> {
+pub struct Button<Msg, S> {
     id: &'static str,
     bounds: BoundingRect,
-    text: Option<super::Text<Msg>>,
-    on_click: Option<Box<dyn FnMut(RefMut<State>) -> Msg>>,
+    /// Size before being resized by a layout
+    size: (u32, u32),
+    text: Option<super::Text<Msg, S>>,
+    on_click: Option<Box<dyn FnMut(RefMut<State<S>>) -> Msg>>,
     color: Option<crate::Color>,
     roundness: f32,
 
-    message_handler: Option<Box<dyn FnMut(&mut Button<Msg>, &Msg, RefMut<State>)>>,
+    message_handler: Option<Box<dyn FnMut(&mut Button<Msg, S>, &Msg, RefMut<State<S>>)>>,
 
     // Register click only when mouse-down *and* mouse-up occur within bounds
     mouse_down_in_bounds: bool,
+    has_focus: bool,
 
     should_resize: bool,
 }
 
-impl<Msg> Button<Msg> {
+impl<Msg, S> Button<Msg, S> {
     pub fn new(id: &'static str) -> Self {
         let mut bounds = BoundingRect::new();
 
@@ -32,6 +35,7 @@ impl<Msg> Button<Msg> {
 
         Button {
             id,
+            size: bounds.dimensions(),
             bounds,
             text: None,
             on_click: None,
@@ -40,6 +44,7 @@ impl<Msg> Button<Msg> {
             roundness: -1.0,
             message_handler: None,
             mouse_down_in_bounds: false,
+            has_focus: false,
             should_resize: false,
         }
     }
@@ -52,17 +57,17 @@ impl<Msg> Button<Msg> {
         }
     }
 
-    pub fn message_handler<F: FnMut(&mut Button<Msg>, &Msg, RefMut<State>) + 'static>(mut self, handler: F) -> Self {
+    pub fn message_handler<F: FnMut(&mut Button<Msg, S>, &Msg, RefMut<State<S>>) + 'static>(mut self, handler: F) -> Self {
         self.message_handler = Some(Box::new(handler));
         self
     }
 
-    pub fn on_click<F: FnMut(RefMut<State>) -> Msg + 'static>(mut self, cb: F) -> Self {
+    pub fn on_click<F: FnMut(RefMut<State<S>>) -> Msg + 'static>(mut self, cb: F) -> Self {
         self.on_click = Some(Box::new(cb));
         self
     }
 
-    pub fn text(mut self, text: super::Text<Msg>) -> Self {
+    pub fn text(mut self, text: super::Text<Msg, S>) -> Self {
         self.text = Some(text);
         self
     }
@@ -82,7 +87,7 @@ impl<Msg> Button<Msg> {
     }
 }
 
-impl<Msg: EmptyMessage> Widget<Msg> for Button<Msg> where Msg: 'static {
+impl<Msg: EmptyMessage, S> Widget<Msg, S> for Button<Msg, S> where Msg: 'static, S: 'static {
     fn id(&self) -> &'static str {
         self.id
     }
@@ -91,6 +96,26 @@ impl<Msg: EmptyMessage> Widget<Msg> for Button<Msg> where Msg: 'static {
         &mut self.should_resize
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn on_focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn on_blur(&mut self) {
+        self.has_focus = false;
+    }
+
+    fn contains_point(&self, x: i32, y: i32) -> bool {
+        self.bounds.contains(x, y)
+    }
+
+    fn focus_bounds(&self) -> Option<&BoundingRect> {
+        Some(&self.bounds)
+    }
+
     fn translate(&mut self, dx: i32, dy: i32) {
         self.bounds.x += dx;
         self.bounds.y += dy;
@@ -115,7 +140,7 @@ impl<Msg: EmptyMessage> Widget<Msg> for Button<Msg> where Msg: 'static {
         }
     }
 
-    fn handle_event(&mut self, event: &crate::event::ApplicationEvent, state: RefMut<State>, messages: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
+    fn handle_event(&mut self, event: &crate::event::ApplicationEvent, state: RefMut<State<S>>, messages: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
         use crate::event::*;
         
         match event {
@@ -137,6 +162,18 @@ impl<Msg: EmptyMessage> Widget<Msg> for Button<Msg> where Msg: 'static {
                 
                 self.mouse_down_in_bounds = false;
             }
+
+            // Activate with the keyboard when focused
+            ApplicationEvent::Key { key: Key::Return, state: ButtonState::Pressed, .. }
+            | ApplicationEvent::Key { key: Key::NumpadEnter, state: ButtonState::Pressed, .. }
+            | ApplicationEvent::Key { key: Key::Space, state: ButtonState::Pressed, .. } => {
+                if self.has_focus {
+                    if let Some(on_click) = &mut self.on_click {
+                        messages.push((on_click)(state));
+                    }
+                    return crate::EventResponse::Consume;
+                }
+            }
             
             _ => {}
         }
@@ -144,10 +181,10 @@ impl<Msg: EmptyMessage> Widget<Msg> for Button<Msg> where Msg: 'static {
         crate::EventResponse::None
     }
 
-    fn handle_message(&mut self, message: &Msg, state: std::cell::RefMut<crate::state::State>) {
+    fn handle_message(&mut self, message: &Msg, state: std::cell::RefMut<crate::state::State<S>>) {
         // NOTE: See `Text::handle_message` for description of this implementation
         let this = unsafe {
-            (self as *mut Button<Msg>).as_mut().unwrap()
+            (self as *mut Button<Msg, S>).as_mut().unwrap()
         };
         
         if let Some(handler) = &mut self.message_handler {
@@ -177,9 +214,22 @@ impl<Msg: EmptyMessage> Widget<Msg> for Button<Msg> where Msg: 'static {
         (self.bounds.width, self.bounds.height)
     }
 
-    fn render(&self, renderer: &mut crate::render::ContextualRenderer, theme: &crate::style::Theme) {        
+    fn natural_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        self.size
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.bounds.width = width;
+        self.bounds.height = height;
+    }
+
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme) {        
         // TODO: Renderer can create draw commands using just the bounding_rect + style
 
+        if self.has_focus {
+            super::render_focus_ring(renderer, theme, &self.bounds, self.roundness);
+        }
+
         renderer.draw(crate::render::DrawCommand::RoundedRect {
             top_left: self.bounds.top_left(),
             width: self.bounds.width,
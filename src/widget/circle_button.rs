@@ -6,25 +6,26 @@ use std::cell::RefMut;
 
 use super::{Widget, Text, Image};
 
-pub enum Contents<Msg> {
-    Char(Text<Msg>),
+pub enum Contents<Msg, S> {
+    Char(Text<Msg, S>),
     // TODO: This (once image support is added)
-    Image(Image<Msg>),
+    Image(Image<Msg, S>),
     None,
 }
 
-pub struct CircleButton<Msg> {
+pub struct CircleButton<Msg, S> {
     id: &'static str,
     bounds: BoundingRect,
-    contents: Contents<Msg>,
-    on_click: Option<Box<dyn FnMut(RefMut<State>) -> Msg>>,
+    contents: Contents<Msg, S>,
+    on_click: Option<Box<dyn FnMut(RefMut<State<S>>) -> Msg>>,
     radius: u32,
     color: Option<crate::Color>,
     mouse_down_in_bounds: bool,
+    has_focus: bool,
     should_resize: bool,
 }
 
-impl<Msg> CircleButton<Msg> {
+impl<Msg, S> CircleButton<Msg, S> {
     pub fn new(id: &'static str) -> Self {
         Self {
             id,
@@ -35,11 +36,12 @@ impl<Msg> CircleButton<Msg> {
             radius: 0,
             color: None,
             mouse_down_in_bounds: false,
+            has_focus: false,
             should_resize: false,
         }
     }
 
-    pub fn on_click<F: FnMut(RefMut<State>) -> Msg + 'static>(mut self, on_click: F) -> Self {
+    pub fn on_click<F: FnMut(RefMut<State<S>>) -> Msg + 'static>(mut self, on_click: F) -> Self {
         self.on_click = Some(Box::new(on_click));
         self
     }
@@ -54,7 +56,7 @@ impl<Msg> CircleButton<Msg> {
         self
     }
 
-    pub fn character(mut self, character: crate::widget::text::TextCharacter<Msg>) -> Self {
+    pub fn character(mut self, character: crate::widget::text::TextCharacter<Msg, S>) -> Self {
         if let Contents::Image(_) = self.contents {
             println!("WARNING: Overwriting image resource of `{}` with a character", self.id);
         }
@@ -63,7 +65,7 @@ impl<Msg> CircleButton<Msg> {
         self
     }
 
-    pub fn image(mut self, image: Image<Msg>) -> Self {
+    pub fn image(mut self, image: Image<Msg, S>) -> Self {
         if let Contents::Char(_) = self.contents {
             println!("WARNING: Overwriting character resource of `{}` with an image", self.id);
         }
@@ -71,15 +73,9 @@ impl<Msg> CircleButton<Msg> {
         self.contents = Contents::Image(image);
         self
     }
-
-    fn contains_point(&self, x: i32, y: i32) -> bool {
-        let center = self.bounds.center();
-
-        (((x - center.0).abs().pow(2) + (y - center.1).abs().pow(2)) as f32).sqrt() < self.radius as f32
-    }
 }
 
-impl<Msg: EmptyMessage> Widget<Msg> for CircleButton<Msg> where Msg: 'static {
+impl<Msg: EmptyMessage, S> Widget<Msg, S> for CircleButton<Msg, S> where Msg: 'static, S: 'static {
     fn id(&self) -> &'static str {
         self.id
     }
@@ -88,6 +84,28 @@ impl<Msg: EmptyMessage> Widget<Msg> for CircleButton<Msg> where Msg: 'static {
         &mut self.should_resize
     }
 
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn on_focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn on_blur(&mut self) {
+        self.has_focus = false;
+    }
+
+    fn contains_point(&self, x: i32, y: i32) -> bool {
+        let center = self.bounds.center();
+
+        (((x - center.0).abs().pow(2) + (y - center.1).abs().pow(2)) as f32).sqrt() < self.radius as f32
+    }
+
+    fn focus_bounds(&self) -> Option<&BoundingRect> {
+        Some(&self.bounds)
+    }
+
     fn place(&mut self, x: i32, y: i32) {
         self.bounds.x = x;
         self.bounds.y = y;
@@ -167,7 +185,7 @@ impl<Msg: EmptyMessage> Widget<Msg> for CircleButton<Msg> where Msg: 'static {
         self.bounds.height = self.radius * 2;
     }
 
-    fn handle_event(&mut self, event: &crate::event::ApplicationEvent, state: std::cell::RefMut<crate::state::State>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
+    fn handle_event(&mut self, event: &crate::event::ApplicationEvent, state: std::cell::RefMut<crate::state::State<S>>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
         use crate::event::*;
         
         match event {
@@ -190,6 +208,18 @@ impl<Msg: EmptyMessage> Widget<Msg> for CircleButton<Msg> where Msg: 'static {
                 self.mouse_down_in_bounds = false;
             }
 
+            // Activate with the keyboard when focused
+            ApplicationEvent::Key { key: Key::Return, state: ButtonState::Pressed, .. }
+            | ApplicationEvent::Key { key: Key::NumpadEnter, state: ButtonState::Pressed, .. }
+            | ApplicationEvent::Key { key: Key::Space, state: ButtonState::Pressed, .. } => {
+                if self.has_focus {
+                    if let Some(on_click) = &mut self.on_click {
+                        message_queue.push((on_click)(state));
+                    }
+                    return crate::EventResponse::Consume;
+                }
+            }
+
             _ => {}
         }
 
@@ -200,7 +230,12 @@ impl<Msg: EmptyMessage> Widget<Msg> for CircleButton<Msg> where Msg: 'static {
         self.bounds.dimensions()
     }
 
-    fn render(&self, renderer: &mut crate::render::ContextualRenderer, theme: &crate::style::Theme) {
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme) {
+        if self.has_focus {
+            // Fully rounded square -> circle
+            super::render_focus_ring(renderer, theme, &self.bounds, 100.0);
+        }
+
         renderer.draw(crate::render::DrawCommand::Circle {
             center: self.bounds.center(),
             radius: self.radius,
@@ -1,17 +1,17 @@
 use crate::view_element::*;
 use super::Widget;
 
-pub struct Image<Msg> {
+pub struct Image<Msg, S> {
     id: &'static str,
     pub resource: String,
     pub bounds: crate::bounding_rect::BoundingRect,
     width_constraint: Option<u32>,
     height_constraint: Option<u32>,
     should_resize: bool,
-    _phantom_marker: std::marker::PhantomData<Msg>,
+    _phantom_marker: std::marker::PhantomData<(Msg, S)>,
 }
 
-impl<Msg> Image<Msg> {
+impl<Msg, S> Image<Msg, S> {
     pub fn new(id: &'static str) -> Self {
         Self {
             id,
@@ -50,7 +50,7 @@ impl<Msg> Image<Msg> {
     }
 }
 
-impl<Msg: EmptyMessage + 'static> Widget<Msg> for Image<Msg> {
+impl<Msg: EmptyMessage + 'static, S: 'static> Widget<Msg, S> for Image<Msg, S> {
     fn id(&self) -> &'static str {
         self.id
     }
@@ -93,7 +93,7 @@ impl<Msg: EmptyMessage + 'static> Widget<Msg> for Image<Msg> {
         self.bounds.dimensions()
     }
 
-    fn render(&self, renderer: &mut crate::render::ContextualRenderer, _theme: &crate::prelude::Theme) {
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, _theme: &crate::prelude::Theme) {
         renderer.draw(crate::render::DrawCommand::Image {
             alias: &self.resource,
             top_left: self.bounds.top_left(),
@@ -112,7 +112,7 @@ pub type IncludedImages = Vec<(&'static str, image::DynamicImage)>;
 /// `surreal::IncludedImages` is a type alias for this list.
 ///
 /// Usage:
-/// ```
+/// ```ignore
 /// let fonts = include_images! {
 ///     alias_1 => "path/to/image1.jpg",
 ///     alias_2 => "path/to/image2.png",
@@ -3,21 +3,44 @@ mod circle_button;
 mod text;
 mod image;
 mod scroll_bar;
+mod text_input;
+mod text_area;
 
 pub use self::image::{Image, IncludedImages};
 pub use circle_button::CircleButton;
 pub use button::Button;
 pub use text::Text;
 pub use scroll_bar::ScrollBar;
+pub use text_input::TextInput;
+pub use text_area::TextArea;
 
-pub trait Widget<Msg: crate::EmptyMessage> {
+/// Type identity of widgets, used to safely get a widget as its concrete type (see `view::get_widget_by_id`).
+/// Implemented for all widgets.
+pub trait AsAny {
+    fn as_any(&mut self) -> &mut dyn std::any::Any;
+    /// Name of the widget's type (for error messages)
+    fn type_name(&self) -> &'static str;
+}
+
+impl<T: std::any::Any> AsAny for T {
+    fn as_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+pub trait Widget<Msg: crate::EmptyMessage, S>: AsAny {
+    /// Unique within the view tree, unless anonymous (see `crate::id`)
     fn id(&self) -> &'static str;
 
-    fn handle_event(&mut self, _event: &crate::event::ApplicationEvent, _state: std::cell::RefMut<crate::state::State>, _message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
+    fn handle_event(&mut self, _event: &crate::event::ApplicationEvent, _state: std::cell::RefMut<crate::state::State<S>>, _message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
         crate::EventResponse::None
     }
 
-    fn handle_message(&mut self, _message: &Msg, _state: std::cell::RefMut<crate::state::State>) {
+    fn handle_message(&mut self, _message: &Msg, _state: std::cell::RefMut<crate::state::State<S>>) {
 
     }
 
@@ -33,6 +56,33 @@ pub trait Widget<Msg: crate::EmptyMessage> {
 
     fn should_resize(&mut self) -> &mut bool;
 
+    /// Return true here if the widget can receive keyboard focus.
+    /// Widgets without an id cannot be focused.
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    /// Called when the widget receives keyboard focus
+    fn on_focus(&mut self) {
+
+    }
+
+    /// Called when the widget loses keyboard focus
+    fn on_blur(&mut self) {
+
+    }
+
+    /// Returns whether a point is within the widget (used for click-to-focus)
+    fn contains_point(&self, _x: i32, _y: i32) -> bool {
+        false
+    }
+
+    /// Area which must be visible while the widget has focus.
+    /// Scroll views scroll this area into view when the widget is focused.
+    fn focus_bounds(&self) -> Option<&crate::bounding_rect::BoundingRect> {
+        None
+    }
+
     /// Return true here if the widget's `init` function should be called
     /// every time before layout. Otherwise, this function will only ever
     /// be called once for the original view initialization.
@@ -44,9 +94,61 @@ pub trait Widget<Msg: crate::EmptyMessage> {
 
     fn init(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme);
 
+    /// Second layout pass: positions the widget's top left corner (see `measure`)
     fn place(&mut self, x: i32, y: i32);
     fn translate(&mut self, dx: i32, dy: i32);
     
     fn render_size(&self, theme: &crate::style::Theme) -> (u32, u32);
-    fn render(&self, renderer: &mut crate::render::ContextualRenderer, theme: &crate::style::Theme);
-}
\ No newline at end of file
+
+    /// Size of the widget before a layout grows, shrinks, or stretches it (see `resize`)
+    fn natural_size(&self, theme: &crate::style::Theme) -> (u32, u32) {
+        self.render_size(theme)
+    }
+
+    /// How the widget grows and shrinks within a `Stack` (`None` -> the widget keeps its size).
+    /// Any element can be made flexible with `view::Flexible`.
+    fn flex(&self) -> Option<&crate::view::Flex> {
+        None
+    }
+
+    /// Called by stacks with their orientation before the widget is measured
+    /// (for elements which are laid out along their stack, such as `component::Divider`)
+    fn orient(&mut self, _orientation: crate::Orientation) {
+
+    }
+
+    /// Called by `measure` with the widget's new size.
+    /// Widgets with a fixed size ignore this.
+    fn resize(&mut self, _width: u32, _height: u32) {
+
+    }
+
+    /// First layout pass: sizes the widget within `constraints` and returns its size.
+    /// The widget is then positioned with `place`.
+    ///
+    /// Widgets which cannot be resized may return a size outside of the constraints.
+    fn measure(&mut self, _renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: crate::view::BoxConstraints) -> (u32, u32) {
+        let (width, height) = constraints.constrain(self.natural_size(theme));
+        self.resize(width, height);
+        self.render_size(theme)
+    }
+
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme);
+}
+
+/// Draws a ring around a focused widget's bounds. Call this before rendering the widget itself.
+pub(crate) fn render_focus_ring(renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme, bounds: &crate::bounding_rect::BoundingRect, roundness_percent: f32) {
+    let thickness = theme.widget_styles.focus_ring_thickness;
+
+    // Keep the ring's corners concentric with the widget's corners
+    let radius = 0.01 * roundness_percent * std::cmp::min(bounds.width, bounds.height) as f32 / 2.0;
+    let ring_min_dimension = std::cmp::min(bounds.width, bounds.height) + 2 * thickness;
+
+    renderer.draw(crate::render::DrawCommand::RoundedRect {
+        top_left: (bounds.x - thickness as i32, bounds.y - thickness as i32),
+        width: bounds.width + 2 * thickness,
+        height: bounds.height + 2 * thickness,
+        roundness_percent: 100.0 * (radius + thickness as f32) / (ring_min_dimension as f32 / 2.0),
+        color: theme.colors.accent,
+    });
+}
@@ -1,7 +1,7 @@
 use crate::state::State;
 use crate::bounding_rect::BoundingRect;
 
-pub struct ScrollBar<Msg> {
+pub struct ScrollBar<Msg, S> {
     id: &'static str,
     container_bounds: BoundingRect,
     slider_bounds: BoundingRect,
@@ -10,8 +10,10 @@ pub struct ScrollBar<Msg> {
     slider_size: u32,
     // width of the bar (in the unscrollable direction)
     slider_width: u32,
+    // length of the scrolled content (see `content_size`)
+    content_size: Option<u32>,
     // fn(scroll_percentage, state)..
-    on_scroll: Option<Box<dyn FnMut(f32, std::cell::RefMut<State>) -> Msg>>,
+    on_scroll: Option<Box<dyn FnMut(f32, std::cell::RefMut<State<S>>) -> Msg>>,
     // Scroller
     slider_roundness: Option<f32>,
     // Scroll container
@@ -28,9 +30,11 @@ pub struct ScrollBar<Msg> {
     should_resize: bool,
 
     last_percentage: f32,
+    /// Fraction of a pixel left over from scrolling with a wheel or trackpad
+    wheel_remainder: f32,
 }
 
-impl<Msg: crate::EmptyMessage> ScrollBar<Msg> {
+impl<Msg: crate::EmptyMessage, S> ScrollBar<Msg, S> {
     /// - `container_size`: the size of the scroll bar
     /// - `slider_size`: the size of the slider within the scroll bar
     pub fn new(id: &'static str, container_size: u32, slider_size: u32) -> Self {
@@ -51,6 +55,7 @@ impl<Msg: crate::EmptyMessage> ScrollBar<Msg> {
             on_scroll: None,
             slider_roundness: None,
             slider_width: 12,
+            content_size: None,
             container_roundness: None,
             has_container: true,
 
@@ -61,6 +66,7 @@ impl<Msg: crate::EmptyMessage> ScrollBar<Msg> {
             should_resize: false,
 
             last_percentage: 0.0,
+            wheel_remainder: 0.0,
         }
     }
 
@@ -69,6 +75,13 @@ impl<Msg: crate::EmptyMessage> ScrollBar<Msg> {
         self
     }
 
+    /// Length of the scrolled content (in pixels), so that the mouse wheel scrolls the content by the wheel's distance.
+    /// Defaults to the length implied by the slider's share of the bar.
+    pub fn content_size(mut self, content_size: u32) -> Self {
+        self.content_size = Some(content_size);
+        self
+    }
+
     pub fn orientation(mut self, orientation: crate::Orientation) -> Self {
         self.orientation = orientation;
         self
@@ -79,11 +92,63 @@ impl<Msg: crate::EmptyMessage> ScrollBar<Msg> {
         self
     }
 
-    pub fn on_scroll<F: FnMut(f32, std::cell::RefMut<State>) -> Msg + 'static>(mut self, on_scroll: F) -> Self {
+    pub fn on_scroll<F: FnMut(f32, std::cell::RefMut<State<S>>) -> Msg + 'static>(mut self, on_scroll: F) -> Self {
         self.on_scroll = Some(Box::new(on_scroll));
         self
     }
 
+    /// How far the slider is scrolled (0.0 -> start, 1.0 -> end)
+    pub fn percentage(&self) -> f32 {
+        self.last_percentage
+    }
+
+    /// Moves the slider without calling `on_scroll`
+    pub fn set_percentage(&mut self, percentage: f32) {
+        self.last_percentage = percentage.max(0.0).min(1.0);
+        self.set_slider_position_from_last_percentage();
+    }
+
+    /// Changes the length of the bar & slider (see `new`)
+    pub(crate) fn set_sizes(&mut self, container_size: u32, slider_size: u32) {
+        self.slider_size = slider_size;
+
+        match self.orientation {
+            crate::Orientation::Vertical => {
+                self.container_bounds.height = container_size;
+                self.slider_bounds.height = slider_size;
+            }
+            crate::Orientation::Horizontal => {
+                self.container_bounds.width = container_size;
+                self.slider_bounds.width = slider_size;
+            }
+        }
+
+        self.set_slider_position_from_last_percentage();
+    }
+
+    fn container_length(&self) -> u32 {
+        match self.orientation {
+            crate::Orientation::Vertical => self.container_bounds.height,
+            crate::Orientation::Horizontal => self.container_bounds.width,
+        }
+    }
+
+    /// Converts a distance scrolled in the content to the distance the slider moves along the bar
+    fn content_to_slider_distance(&self, distance: f32) -> f32 {
+        let container_length = self.container_length() as f32;
+        // The slider's share of the bar is the visible share of the content
+        let content_size = match self.content_size {
+            Some(content_size) => content_size as f32,
+            None => container_length * container_length / self.slider_size.max(1) as f32,
+        };
+
+        if content_size <= 0.0 {
+            0.0
+        } else {
+            distance * container_length / content_size
+        }
+    }
+
     /// slider % -> position
     fn set_slider_position_from_last_percentage(&mut self) {
         match self.orientation {
@@ -97,7 +162,7 @@ impl<Msg: crate::EmptyMessage> ScrollBar<Msg> {
     }
 
     /// Called when bar is scrolled
-    fn scroll(&mut self, change: i32, state: std::cell::RefMut<State>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
+    fn scroll(&mut self, change: i32, state: std::cell::RefMut<State<S>>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
         // FIXME: Swap the `if` and `else if` branches for better performance
         let percent = match self.orientation {
             // left/right -> use x
@@ -143,13 +208,12 @@ impl<Msg: crate::EmptyMessage> ScrollBar<Msg> {
     }
 }
 
-impl<Msg: crate::EmptyMessage> super::Widget<Msg> for ScrollBar<Msg> {
+impl<Msg: crate::EmptyMessage + 'static, S: 'static> super::Widget<Msg, S> for ScrollBar<Msg, S> {
     fn id(&self) -> &'static str {
         self.id
     }
 
-    // TODO: Listen for scroll wheel
-    fn handle_event(&mut self, event: &crate::event::ApplicationEvent, state: std::cell::RefMut<State>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
+    fn handle_event(&mut self, event: &crate::event::ApplicationEvent, state: std::cell::RefMut<State<S>>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
         use crate::event::*;
         
         match event {
@@ -181,6 +245,27 @@ impl<Msg: crate::EmptyMessage> super::Widget<Msg> for ScrollBar<Msg> {
                 crate::EventResponse::None
             }
 
+            // Scroll when hovered
+            ApplicationEvent::MouseWheel { delta, position: (x, y) } => {
+                if !self.container_bounds.contains(*x, *y) {
+                    return crate::EventResponse::None;
+                }
+
+                let (dx, dy) = delta.to_pixels();
+                // Scrolling up moves the slider towards the start.
+                // Vertical wheels also scroll horizontal bars.
+                let change = match self.orientation {
+                    crate::Orientation::Horizontal if dx != 0.0 => dx,
+                    _ => -dy,
+                };
+
+                // The wheel scrolls the content by its distance -> the slider moves less than the content
+                let change = self.wheel_remainder + self.content_to_slider_distance(change);
+                self.wheel_remainder = change.fract();
+
+                self.scroll(change.trunc() as i32, state, message_queue)
+            }
+
             _ => {
                 crate::EventResponse::None
             }
@@ -249,7 +334,7 @@ impl<Msg: crate::EmptyMessage> super::Widget<Msg> for ScrollBar<Msg> {
         }
     }
 
-    fn render(&self, renderer: &mut crate::render::ContextualRenderer, _theme: &crate::style::Theme) {
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, _theme: &crate::style::Theme) {
         // Container
         if self.has_container {
             renderer.draw(crate::render::DrawCommand::RoundedRect {
@@ -270,4 +355,49 @@ impl<Msg: crate::EmptyMessage> super::Widget<Msg> for ScrollBar<Msg> {
             color: self.slider_color.unwrap(),
         });
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::Widget;
+    use crate::event::{ApplicationEvent, ScrollDelta};
+
+    fn wheel(bar: &mut ScrollBar<(), ()>, pixels: f32) -> crate::EventResponse {
+        let state = crate::state::make_shared(crate::state::State::new(()));
+        let mut message_queue = crate::MessageQueue::new();
+        let event = ApplicationEvent::MouseWheel { delta: ScrollDelta::Pixels { x: 0.0, y: -pixels }, position: (5, 5) };
+
+        bar.handle_event(&event, state.borrow_mut(), &mut message_queue)
+    }
+
+    fn slider_offset(bar: &ScrollBar<(), ()>) -> i32 {
+        bar.slider_bounds.y - bar.container_bounds.y
+    }
+
+    #[test]
+    fn mouse_wheel_scrolls_by_content_distance() {
+        let mut renderer = crate::render::headless::test_renderer();
+
+        // A slider a tenth of the bar's length -> content ten times the bar's length
+        let mut bar = ScrollBar::new("bar", 100, 10);
+        bar.init(&mut renderer, &crate::style::DEFAULT_THEME);
+        bar.place(0, 0);
+
+        wheel(&mut bar, 20.0);
+        assert_eq!(slider_offset(&bar), 2);
+
+        // Fractions of a pixel accumulate
+        for _ in 0..4 {
+            wheel(&mut bar, 2.5);
+        }
+        assert_eq!(slider_offset(&bar), 3);
+
+        let mut bar = ScrollBar::new("bar", 100, 10).content_size(500);
+        bar.init(&mut renderer, &crate::style::DEFAULT_THEME);
+        bar.place(0, 0);
+
+        wheel(&mut bar, 20.0);
+        assert_eq!(slider_offset(&bar), 4);
+        assert_eq!(bar.percentage(), 4.0 / 90.0);
+    }
+}
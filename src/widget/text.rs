@@ -6,15 +6,15 @@ use super::Widget;
 use std::cell::RefMut;
 
 // TODO: If this isn't use anywhere else later on, consider moving this type to `circle_button.rs`
-pub struct TextCharacter<Msg> {
+pub struct TextCharacter<Msg, S> {
     character: char,
     font: String,
     scale: f32,
     color: Option<crate::Color>,
-    _phantom_marker: std::marker::PhantomData<Msg>,
+    _phantom_marker: std::marker::PhantomData<(Msg, S)>,
 }
 
-impl<Msg> TextCharacter<Msg> {
+impl<Msg, S> TextCharacter<Msg, S> {
     pub fn font(mut self, font: &str) -> Self {
         self.font = font.to_owned();
         self
@@ -32,8 +32,8 @@ impl<Msg> TextCharacter<Msg> {
     }
 }
 
-impl<Msg> Into<Text<Msg>> for TextCharacter<Msg> {
-    fn into(self) -> Text<Msg> {
+impl<Msg, S> Into<Text<Msg, S>> for TextCharacter<Msg, S> {
+    fn into(self) -> Text<Msg, S> {
         let mut text = Text::new("__from_TextCharacter")
             .text(&self.character.to_string())
             .font(&self.font);
@@ -51,7 +51,7 @@ impl<Msg> Into<Text<Msg>> for TextCharacter<Msg> {
 }
 
 
-pub struct Text<Msg> {
+pub struct Text<Msg, S> {
     id: &'static str,
     text: String,
     font: String,
@@ -59,14 +59,14 @@ pub struct Text<Msg> {
     color: Option<crate::Color>,
     pub bounds: crate::bounding_rect::BoundingRect,
 
-    message_handler: Option<Box<dyn FnMut(&mut Text<Msg>, &Msg, RefMut<State>)>>,
+    message_handler: Option<Box<dyn FnMut(&mut Text<Msg, S>, &Msg, RefMut<State<S>>)>>,
 
     section: Option<glyph_brush::OwnedSection>,
 
     should_resize: bool,
 }
 
-impl<Msg> Text<Msg> {
+impl<Msg, S> Text<Msg, S> {
     pub fn new(id: &'static str) -> Self {
         Text {
             id,
@@ -83,7 +83,7 @@ impl<Msg> Text<Msg> {
     }
 
     /// Descriptor for a single character. For use with `CircleButton`.
-    pub fn character(character: char) -> TextCharacter<Msg> {
+    pub fn character(character: char) -> TextCharacter<Msg, S> {
         TextCharacter {
             character,
             font: String::from(""),
@@ -116,7 +116,7 @@ impl<Msg> Text<Msg> {
         self
     }
 
-    pub fn message_handler<F: FnMut(&mut Text<Msg>, &Msg, RefMut<State>) + 'static>(mut self, handler: F) -> Self {
+    pub fn message_handler<F: FnMut(&mut Text<Msg, S>, &Msg, RefMut<State<S>>) + 'static>(mut self, handler: F) -> Self {
         self.message_handler = Some(Box::new(handler));
         self
     }
@@ -128,7 +128,7 @@ impl<Msg> Text<Msg> {
     }
 }
 
-impl<Msg: EmptyMessage> Widget<Msg> for Text<Msg> where Msg: 'static {
+impl<Msg: EmptyMessage, S> Widget<Msg, S> for Text<Msg, S> where Msg: 'static, S: 'static {
     fn should_reinit_before_layout(&self) -> bool {
         true
     }
@@ -159,13 +159,13 @@ impl<Msg: EmptyMessage> Widget<Msg> for Text<Msg> where Msg: 'static {
         }
     }
 
-    fn handle_message(&mut self, message: &Msg, state: RefMut<State>) {
+    fn handle_message(&mut self, message: &Msg, state: RefMut<State<S>>) {
         // Bypass the borrow checker to obtain second mutable reference
         // FIXME: This exposes backend traits (Widget & IntoViewElement)
         // TODO: See if there is an alternative solution without using unsafe
         // NOTE: Regarding safety, nothing truly "unsafe" can happen here
         let this = unsafe {
-            (self as *mut Text<Msg>).as_mut().unwrap()
+            (self as *mut Text<Msg, S>).as_mut().unwrap()
         };
         
         if let Some(handler) = &mut self.message_handler {
@@ -207,14 +207,57 @@ impl<Msg: EmptyMessage> Widget<Msg> for Text<Msg> where Msg: 'static {
         self.section = Some(section.to_owned());
     }
 
+    /// Wraps the text if it is wider than the constraints
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, _theme: &crate::style::Theme, constraints: crate::view::BoxConstraints) -> (u32, u32) {
+        if let Some(section) = &mut self.section {
+            section.bounds = (f32::INFINITY, f32::INFINITY);
+            let (mut width, mut height) = renderer.text_renderer.get_section_bounds(&section.to_borrowed());
+
+            if width > constraints.max.0 {
+                section.bounds = (constraints.max.0 as f32, f32::INFINITY);
+                let wrapped = renderer.text_renderer.get_section_bounds(&section.to_borrowed());
+                width = wrapped.0;
+                height = wrapped.1;
+            }
+
+            self.bounds.width = width;
+            self.bounds.height = height;
+        }
+
+        self.bounds.dimensions()
+    }
+
     fn render_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
         self.bounds.dimensions()
         // text_renderer.get_section_bounds(&self...)
     }
 
-    fn render(&self, renderer: &mut crate::render::ContextualRenderer, _theme: &crate::style::Theme) {       
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, _theme: &crate::style::Theme) {       
         if let Some(section) = &self.section {
             renderer.draw(crate::render::DrawCommand::Text(section));
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::BoxConstraints;
+
+    #[test]
+    fn text_wraps_within_the_maximum_width() {
+        let mut renderer = crate::render::headless::test_renderer();
+        let theme = &crate::style::DEFAULT_THEME;
+
+        let mut text: Text<(), ()> = Text::new("").text("several words which do not fit on one line");
+        text.init(&mut renderer, theme);
+
+        let (width, height) = text.measure(&mut renderer, theme, BoxConstraints::unbounded());
+        let (wrapped_width, wrapped_height) = text.measure(&mut renderer, theme, BoxConstraints::loose((width / 2, BoxConstraints::UNBOUNDED)));
+
+        assert!(wrapped_width <= width / 2);
+        assert!(wrapped_height > height);
+
+        // Measuring with enough space undoes the wrapping
+        assert_eq!(text.measure(&mut renderer, theme, BoxConstraints::unbounded()), (width, height));
+    }
+}
@@ -0,0 +1,949 @@
+use crate::state::State;
+use crate::bounding_rect::BoundingRect;
+use crate::view_element::*;
+
+use std::cell::RefMut;
+
+use super::Widget;
+
+/// Position of the caret before a character
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Caret {
+    /// Relative to the start of the line (in pixels)
+    x: u32,
+    line: usize,
+}
+
+/// Multi-line editable text. Text wraps to the width of the widget.
+///
+/// Lines which do not fit the rows are scrolled with the mouse wheel or by moving the caret.
+pub struct TextArea<Msg, S> {
+    id: &'static str,
+    bounds: BoundingRect,
+    text: String,
+    placeholder: String,
+    font: String,
+    scale: f32,
+    color: Option<crate::Color>,
+    text_color: Option<crate::Color>,
+    placeholder_color: Option<crate::Color>,
+    roundness: f32,
+    /// Number of visible lines (determines the height)
+    rows: usize,
+
+    // fn(text, state)..
+    on_change: Option<Box<dyn FnMut(&str, RefMut<State<S>>) -> Msg>>,
+    message_handler: Option<Box<dyn FnMut(&mut TextArea<Msg, S>, &Msg, RefMut<State<S>>)>>,
+
+    /// Caret position (in characters)
+    cursor: usize,
+    /// Where the selection began (in characters). The selection spans from here to the cursor.
+    selection_anchor: Option<usize>,
+    /// Horizontal position to return to when moving the caret between lines
+    preferred_x: Option<u32>,
+    has_focus: bool,
+    is_dragging: bool,
+
+    /// Layout of every caret position (one more than the number of characters)
+    carets: Vec<Caret>,
+    /// First character of every line
+    line_starts: Vec<usize>,
+    /// First visible line. Text scrolls vertically to keep the caret in view.
+    first_visible_line: usize,
+    /// Lines scrolled by the mouse wheel which did not add up to a whole line yet
+    wheel_remainder: f32,
+    line_height: u32,
+    /// Width before being resized by a layout
+    width: u32,
+    font_id: Option<wgpu_glyph::FontId>,
+    /// Used to wrap edited text without a renderer
+    glyph_font: Option<wgpu_glyph::ab_glyph::FontArc>,
+    padding: (u32, u32),
+
+    should_resize: bool,
+}
+
+impl<Msg, S> TextArea<Msg, S> {
+    pub fn new(id: &'static str) -> Self {
+        let mut bounds = BoundingRect::new();
+
+        // Height is determined by the number of rows
+        let width = 400;
+        bounds.width = width;
+
+        Self {
+            id,
+            bounds,
+            text: String::new(),
+            placeholder: String::new(),
+            font: String::from(""),
+            // Negative -> unset
+            scale: -1.0,
+            color: None,
+            text_color: None,
+            placeholder_color: None,
+            roundness: -1.0,
+            rows: 5,
+            on_change: None,
+            message_handler: None,
+            cursor: 0,
+            selection_anchor: None,
+            preferred_x: None,
+            has_focus: false,
+            is_dragging: false,
+            carets: vec![Caret { x: 0, line: 0 }],
+            line_starts: vec![0],
+            first_visible_line: 0,
+            wheel_remainder: 0.0,
+            line_height: 0,
+            width,
+            font_id: None,
+            glyph_font: None,
+            padding: (0, 0),
+            should_resize: false,
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_owned();
+        self
+    }
+
+    /// Text shown when the text area is empty
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_owned();
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self.bounds.width = width;
+        self
+    }
+
+    /// Number of visible lines. Overflowing lines are scrolled.
+    pub fn rows(mut self, rows: usize) -> Self {
+        if rows == 0 {
+            panic!("TextArea `{}` must have at least one row", self.id);
+        }
+        self.rows = rows;
+        self
+    }
+
+    pub fn scale(mut self, scale: f32) -> Self {
+        if scale < 0.0 {
+            panic!("Text scale cannot be negative (Tried setting `{}` to scale {})", self.id, scale);
+        }
+        self.scale = scale;
+        self
+    }
+
+    pub fn font(mut self, font: &str) -> Self {
+        self.font = font.to_owned();
+        self
+    }
+
+    /// Background color
+    pub fn color(mut self, color: crate::Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn text_color(mut self, color: crate::Color) -> Self {
+        self.text_color = Some(color);
+        self
+    }
+
+    pub fn placeholder_color(mut self, color: crate::Color) -> Self {
+        self.placeholder_color = Some(color);
+        self
+    }
+
+    pub fn roundness(mut self, roundness: f32) -> Self {
+        if roundness < 0.0 || roundness > 100.0 {
+            panic!("Roundness must be between 0 and 100 (percent). `{}` got `{}`", self.id, roundness);
+        }
+
+        self.roundness = roundness;
+        self
+    }
+
+    /// Called whenever the text is edited by the user
+    pub fn on_change<F: FnMut(&str, RefMut<State<S>>) -> Msg + 'static>(mut self, on_change: F) -> Self {
+        self.on_change = Some(Box::new(on_change));
+        self
+    }
+
+    pub fn message_handler<F: FnMut(&mut TextArea<Msg, S>, &Msg, RefMut<State<S>>) + 'static>(mut self, handler: F) -> Self {
+        self.message_handler = Some(Box::new(handler));
+        self
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_owned();
+        self.cursor = std::cmp::min(self.cursor, self.char_count());
+        self.selection_anchor = None;
+        self.preferred_x = None;
+
+        self.rewrap();
+    }
+
+    /// Number of lines after wrapping
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Byte index of the character at `char_index`
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text.char_indices()
+            .nth(char_index)
+            .map(|(index, _)| index)
+            .unwrap_or(self.text.len())
+    }
+
+    /// Whether the layout matches the text (it is measured by `init` and updated by `wrap`)
+    fn is_measured(&self) -> bool {
+        self.carets.len() == self.char_count() + 1
+    }
+
+    /// Selected range of characters, if anything is selected
+    fn selection(&self) -> Option<(usize, usize)> {
+        match self.selection_anchor {
+            Some(anchor) if anchor != self.cursor => {
+                Some((std::cmp::min(anchor, self.cursor), std::cmp::max(anchor, self.cursor)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes the selected text. Returns `false` if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        if let Some((start, end)) = self.selection() {
+            let (start_byte, end_byte) = (self.byte_index(start), self.byte_index(end));
+            self.text.replace_range(start_byte..end_byte, "");
+            self.cursor = start;
+            self.selection_anchor = None;
+            true
+        } else {
+            self.selection_anchor = None;
+            false
+        }
+    }
+
+    fn insert(&mut self, character: char) {
+        self.delete_selection();
+
+        let index = self.byte_index(self.cursor);
+        self.text.insert(index, character);
+        self.cursor += 1;
+    }
+
+    /// First character after the end of `line`
+    fn line_end(&self, line: usize) -> usize {
+        if line + 1 < self.line_starts.len() {
+            // The caret cannot be placed after a wrapped line's last character (it would be on the next line)
+            self.line_starts[line + 1] - 1
+        } else {
+            self.char_count()
+        }
+    }
+
+    /// Moves the caret, extending the selection if `select` is true
+    fn move_cursor(&mut self, position: usize, select: bool) {
+        if select {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some(self.cursor);
+            }
+        } else {
+            self.selection_anchor = None;
+        }
+
+        self.cursor = std::cmp::min(position, self.char_count());
+        self.preferred_x = None;
+        self.scroll_to_cursor();
+    }
+
+    /// Moves the caret up (negative) or down (positive) by some lines, keeping its horizontal position
+    fn move_cursor_lines(&mut self, lines: isize, select: bool) {
+        if !self.is_measured() {
+            return;
+        }
+
+        let caret = self.carets[self.cursor];
+        let x = self.preferred_x.unwrap_or(caret.x);
+
+        let target_line = caret.line as isize + lines;
+        let position = if target_line < 0 {
+            0
+        } else if target_line as usize >= self.line_count() {
+            self.char_count()
+        } else {
+            self.cursor_on_line(target_line as usize, x)
+        };
+
+        self.move_cursor(position, select);
+        self.preferred_x = Some(x);
+    }
+
+    /// Nearest caret position on a line to the x coordinate (relative to the start of the line)
+    fn cursor_on_line(&self, line: usize, x: u32) -> usize {
+        (self.line_starts[line] ..= self.line_end(line))
+            .min_by_key(|index| (self.carets[*index].x as i32 - x as i32).abs())
+            .unwrap_or(0)
+    }
+
+    /// Nearest caret position to a point on the screen
+    fn cursor_from_point(&self, x: i32, y: i32) -> usize {
+        let (text_x, text_y) = self.text_position();
+
+        let line = std::cmp::max(0, y - text_y) as usize / std::cmp::max(1, self.line_height) as usize;
+        let line = std::cmp::min(self.first_visible_line + line, self.line_count() - 1);
+
+        self.cursor_on_line(line, std::cmp::max(0, x - text_x) as u32)
+    }
+
+    /// Scrolls vertically so that the caret is visible
+    fn scroll_to_cursor(&mut self) {
+        if !self.is_measured() {
+            return;
+        }
+
+        let line = self.carets[self.cursor].line;
+
+        if line < self.first_visible_line {
+            self.first_visible_line = line;
+        } else if line >= self.first_visible_line + self.rows {
+            self.first_visible_line = line + 1 - self.rows;
+        }
+    }
+
+    /// Scrolls by whole lines without moving the caret (positive `pixels` scroll towards the first line).
+    /// Returns false if the text cannot scroll any further in that direction.
+    fn scroll_lines(&mut self, pixels: f32) -> bool {
+        if !self.is_measured() || pixels == 0.0 {
+            return false;
+        }
+
+        let last_first_line = self.line_count().saturating_sub(self.rows);
+        let can_scroll = if pixels > 0.0 {
+            self.first_visible_line > 0
+        } else {
+            self.first_visible_line < last_first_line
+        };
+
+        if !can_scroll {
+            self.wheel_remainder = 0.0;
+            return false;
+        }
+
+        let lines = self.wheel_remainder - pixels / std::cmp::max(1, self.line_height) as f32;
+        self.wheel_remainder = lines.fract();
+
+        let first_line = self.first_visible_line as i64 + lines.trunc() as i64;
+        self.first_visible_line = first_line.max(0).min(last_first_line as i64) as usize;
+
+        true
+    }
+
+    fn text_position(&self) -> (i32, i32) {
+        (self.bounds.x + self.padding.0 as i32, self.bounds.y + self.padding.1 as i32)
+    }
+
+    fn inner_width(&self) -> u32 {
+        self.bounds.width.saturating_sub(2 * self.padding.0)
+    }
+
+    /// Lets glyph_brush wrap the text, then finds where each character was placed
+    fn wrap(&mut self) {
+        use glyph_brush::GlyphPositioner;
+        use wgpu_glyph::ab_glyph::{Font, ScaleFont};
+
+        // Measured by `init`
+        let font = if let Some(font) = &self.glyph_font { font.clone() } else { return };
+
+        // Same layout as a section with `Layout::default_wrap`
+        let geometry = glyph_brush::SectionGeometry {
+            bounds: (self.inner_width() as f32, f32::INFINITY),
+            ..glyph_brush::SectionGeometry::default()
+        };
+        let glyphs = glyph_brush::Layout::default_wrap().calculate_glyphs(
+            std::slice::from_ref(&font),
+            &geometry,
+            &[glyph_brush::Text::<glyph_brush::Extra>::new(&self.text).with_scale(self.scale)],
+        );
+        let font = font.as_scaled(self.scale);
+
+        // Same line spacing as glyph_brush's layout
+        let line_advance = font.height() + font.line_gap();
+        self.line_height = line_advance.round() as u32;
+
+        let mut glyphs = glyphs.iter().peekable();
+        let mut caret = Caret { x: 0, line: 0 };
+
+        self.carets.clear();
+        for (index, character) in self.text.char_indices() {
+            let glyph = match glyphs.peek() {
+                Some(glyph) if glyph.byte_index == index => glyphs.next(),
+                _ => None,
+            };
+
+            if let Some(glyph) = glyph {
+                caret = Caret {
+                    x: glyph.glyph.position.x.round() as u32,
+                    line: ((glyph.glyph.position.y - font.ascent()) / line_advance).round() as usize,
+                };
+            }
+
+            self.carets.push(caret);
+
+            // Position of the next character if it is not drawn (such as newlines)
+            if character == '\n' {
+                caret = Caret { x: 0, line: caret.line + 1 };
+            } else if let Some(glyph) = glyph {
+                caret.x = (glyph.glyph.position.x + font.h_advance(glyph.glyph.id)).round() as u32;
+            }
+        }
+        self.carets.push(caret);
+
+        self.line_starts = vec![0];
+        for (index, window) in self.carets.windows(2).enumerate() {
+            if window[1].line != window[0].line {
+                self.line_starts.push(index + 1);
+            }
+        }
+
+        self.cursor = std::cmp::min(self.cursor, self.char_count());
+        self.first_visible_line = std::cmp::min(self.first_visible_line, self.line_count() - 1);
+        self.scroll_to_cursor();
+    }
+
+    /// Wraps the text again after it changed.
+    /// The view is only laid out again if the number of lines or the size changed.
+    fn rewrap(&mut self) {
+        let lines = self.line_count();
+        let size = self.bounds.dimensions();
+
+        self.wrap();
+
+        if self.line_count() != lines || self.bounds.dimensions() != size {
+            self.should_resize = true;
+        }
+    }
+
+    fn notify_change(&mut self, state: RefMut<State<S>>, message_queue: &mut crate::MessageQueue<Msg>) where Msg: EmptyMessage {
+        self.preferred_x = None;
+        self.rewrap();
+
+        if let Some(on_change) = &mut self.on_change {
+            message_queue.push((on_change)(&self.text, state));
+        }
+    }
+
+    fn handle_key(&mut self, key: crate::event::Key, modifiers: crate::event::Modifiers, state: RefMut<State<S>>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse where Msg: EmptyMessage {
+        use crate::event::Key;
+
+        match key {
+            Key::Back => {
+                if !self.delete_selection() {
+                    if self.cursor == 0 {
+                        return crate::EventResponse::Consume;
+                    }
+                    self.cursor -= 1;
+                    let index = self.byte_index(self.cursor);
+                    self.text.remove(index);
+                }
+                self.notify_change(state, message_queue);
+            }
+
+            Key::Delete => {
+                if !self.delete_selection() {
+                    if self.cursor == self.char_count() {
+                        return crate::EventResponse::Consume;
+                    }
+                    let index = self.byte_index(self.cursor);
+                    self.text.remove(index);
+                }
+                self.notify_change(state, message_queue);
+            }
+
+            Key::Return | Key::NumpadEnter => {
+                self.insert('\n');
+                self.notify_change(state, message_queue);
+            }
+
+            Key::Left => {
+                match self.selection() {
+                    Some((start, _)) if !modifiers.shift => self.move_cursor(start, false),
+                    _ => self.move_cursor(self.cursor.saturating_sub(1), modifiers.shift),
+                }
+            }
+
+            Key::Right => {
+                match self.selection() {
+                    Some((_, end)) if !modifiers.shift => self.move_cursor(end, false),
+                    _ => self.move_cursor(self.cursor + 1, modifiers.shift),
+                }
+            }
+
+            Key::Up => {
+                self.move_cursor_lines(-1, modifiers.shift);
+            }
+
+            Key::Down => {
+                self.move_cursor_lines(1, modifiers.shift);
+            }
+
+            Key::PageUp => {
+                self.move_cursor_lines(-(self.rows as isize), modifiers.shift);
+            }
+
+            Key::PageDown => {
+                self.move_cursor_lines(self.rows as isize, modifiers.shift);
+            }
+
+            // Start of the line (or the text with ctrl)
+            Key::Home => {
+                let position = if modifiers.ctrl || !self.is_measured() {
+                    0
+                } else {
+                    self.line_starts[self.carets[self.cursor].line]
+                };
+                self.move_cursor(position, modifiers.shift);
+            }
+
+            // End of the line (or the text with ctrl)
+            Key::End => {
+                let position = if modifiers.ctrl || !self.is_measured() {
+                    self.char_count()
+                } else {
+                    self.line_end(self.carets[self.cursor].line)
+                };
+                self.move_cursor(position, modifiers.shift);
+            }
+
+            // Select all
+            Key::A if modifiers.ctrl || modifiers.logo => {
+                self.selection_anchor = Some(0);
+                self.cursor = self.char_count();
+                self.scroll_to_cursor();
+            }
+
+            // Not handled here (see `ApplicationEvent::Character`)
+            _ => return crate::EventResponse::None,
+        }
+
+        crate::EventResponse::Redraw
+    }
+}
+
+impl<Msg: EmptyMessage, S> Widget<Msg, S> for TextArea<Msg, S> where Msg: 'static, S: 'static {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn should_resize(&mut self) -> &mut bool {
+        &mut self.should_resize
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn on_focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn on_blur(&mut self) {
+        self.has_focus = false;
+        self.is_dragging = false;
+        self.selection_anchor = None;
+    }
+
+    fn contains_point(&self, x: i32, y: i32) -> bool {
+        self.bounds.contains(x, y)
+    }
+
+    fn focus_bounds(&self) -> Option<&BoundingRect> {
+        Some(&self.bounds)
+    }
+
+    fn init(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme) {
+        if self.scale < 0.0 {
+            self.scale = theme.text.scale;
+        }
+
+        if self.roundness < 0.0 {
+            self.roundness = theme.widget_styles.buttons.roundness / 8.0;
+        }
+
+        if self.color.is_none() {
+            self.color = Some(theme.colors.secondary);
+        }
+
+        if self.text_color.is_none() {
+            self.text_color = Some(theme.colors.text);
+        }
+
+        if self.placeholder_color.is_none() {
+            let color = theme.colors.text;
+            self.placeholder_color = Some(crate::Color::new(color.r, color.g, color.b, color.a * 0.5));
+        }
+
+        let padding = &theme.widget_styles.internal_padding;
+        self.padding = (padding.horizontal * 2, padding.vertical);
+
+        let font_id = renderer.text_renderer.get_font_id(&self.font);
+        self.font_id = Some(font_id);
+
+        self.glyph_font = Some(renderer.text_renderer.fonts()[font_id.0].clone());
+        self.wrap();
+
+        self.bounds.height = self.rows as u32 * self.line_height + 2 * self.padding.1;
+    }
+
+    fn handle_event(&mut self, event: &crate::event::ApplicationEvent, state: RefMut<State<S>>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
+        use crate::event::*;
+
+        match event {
+            ApplicationEvent::MouseButton { state: ButtonState::Pressed, button: MouseButton::Left, position: (x, y) } => {
+                // Focus is given by the application before the click is handled
+                if self.has_focus && self.bounds.contains(*x, *y) {
+                    self.is_dragging = true;
+                    if self.is_measured() {
+                        self.move_cursor(self.cursor_from_point(*x, *y), false);
+                    }
+                    return crate::EventResponse::Consume;
+                }
+            }
+
+            ApplicationEvent::MouseButton { state: ButtonState::Released, button: MouseButton::Left, .. } => {
+                self.is_dragging = false;
+            }
+
+            // Drag to select
+            ApplicationEvent::MouseMotion { position: (x, y), .. } => {
+                if self.is_dragging && self.is_measured() {
+                    let cursor = self.cursor_from_point(*x, *y);
+                    if cursor != self.cursor {
+                        self.move_cursor(cursor, true);
+                        return crate::EventResponse::Redraw;
+                    }
+                }
+            }
+
+            // Overflowing text can be scrolled while the mouse is over it (with or without focus)
+            ApplicationEvent::MouseWheel { delta, position: (x, y) } => {
+                if self.bounds.contains(*x, *y) && self.scroll_lines(delta.to_pixels().1) {
+                    return crate::EventResponse::Redraw;
+                }
+            }
+
+            ApplicationEvent::Character(character) => {
+                if self.has_focus && !character.is_control() {
+                    self.insert(*character);
+                    self.notify_change(state, message_queue);
+                    return crate::EventResponse::Redraw;
+                }
+            }
+
+            ApplicationEvent::Key { key, state: ButtonState::Pressed, modifiers } => {
+                if self.has_focus {
+                    return self.handle_key(*key, *modifiers, state, message_queue);
+                }
+            }
+
+            _ => {}
+        }
+
+        crate::EventResponse::None
+    }
+
+    fn handle_message(&mut self, message: &Msg, state: RefMut<State<S>>) {
+        // NOTE: See `Text::handle_message` for description of this implementation
+        let this = unsafe {
+            (self as *mut TextArea<Msg, S>).as_mut().unwrap()
+        };
+
+        if let Some(handler) = &mut self.message_handler {
+            (handler)(this, message, state);
+        }
+    }
+
+    fn place(&mut self, x: i32, y: i32) {
+        self.bounds.x = x;
+        self.bounds.y = y;
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+    }
+
+    /// Narrows (and wraps the text again) if the width does not fit the constraints.
+    /// The height is determined by the number of rows.
+    fn measure(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme, constraints: crate::view::BoxConstraints) -> (u32, u32) {
+        let width = constraints.constrain((self.width, self.bounds.height)).0;
+
+        if width != self.bounds.width {
+            self.bounds.width = width;
+            self.init(renderer, theme);
+        }
+
+        self.bounds.dimensions()
+    }
+
+    fn render_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        self.bounds.dimensions()
+    }
+
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme) {
+        if self.has_focus {
+            super::render_focus_ring(renderer, theme, &self.bounds, self.roundness);
+        }
+
+        renderer.draw(crate::render::DrawCommand::RoundedRect {
+            top_left: self.bounds.top_left(),
+            width: self.bounds.width,
+            height: self.bounds.height,
+            roundness_percent: self.roundness,
+            color: self.color.unwrap(),
+        });
+
+        let font_id = if let Some(font_id) = self.font_id { font_id } else { return };
+        let (text_x, text_y) = self.text_position();
+
+        // Not measured until `init`
+        if !self.is_measured() {
+            return;
+        }
+
+        // Only the visible lines are drawn
+        let first_line = self.first_visible_line;
+        let last_line = std::cmp::min(first_line + self.rows, self.line_count());
+        let line_y = |line: usize| text_y + ((line - first_line) as u32 * self.line_height) as i32;
+
+        // Selection highlight (one rect per line)
+        if let (Some((start, end)), true) = (self.selection(), self.has_focus) {
+            for line in first_line..last_line {
+                let line_start = std::cmp::max(start, self.line_starts[line]);
+                let line_end = std::cmp::min(end, self.line_end(line));
+
+                // Selected line breaks are highlighted up to the edge
+                let continues = start <= line_end && end > line_end;
+                if line_start >= line_end && !continues {
+                    continue;
+                }
+
+                let left = self.carets[line_start].x;
+                let right = if continues { self.inner_width() } else { self.carets[line_end].x };
+
+                renderer.draw(crate::render::DrawCommand::Rect {
+                    top_left: (text_x + left as i32, line_y(line)),
+                    width: right.saturating_sub(left),
+                    height: self.line_height,
+                    color: theme.colors.accent,
+                });
+            }
+        }
+
+        let text_section = |text: &str, y: i32, layout: glyph_brush::Layout<glyph_brush::BuiltInLineBreaker>, color: crate::Color| {
+            glyph_brush::OwnedSection::default()
+                .with_screen_position((text_x as f32, y as f32))
+                .with_bounds((self.inner_width() as f32, f32::INFINITY))
+                .with_layout(layout)
+                .add_text(glyph_brush::OwnedText::new(text)
+                    .with_scale(self.scale)
+                    .with_color(color.as_array())
+                    .with_font_id(font_id)
+                )
+        };
+
+        if self.text.is_empty() {
+            let section = text_section(&self.placeholder, text_y, glyph_brush::Layout::default_wrap(), self.placeholder_color.unwrap());
+            renderer.draw(crate::render::DrawCommand::Text(&section));
+        } else {
+            // Lines are drawn separately (and are already wrapped) so that they are placed exactly where they were measured
+            for line in first_line..last_line {
+                let start = self.byte_index(self.line_starts[line]);
+                let end = if line + 1 < self.line_count() {
+                    self.byte_index(self.line_starts[line + 1])
+                } else {
+                    self.text.len()
+                };
+
+                let line_text = self.text[start..end].trim_end();
+                if !line_text.is_empty() {
+                    let section = text_section(line_text, line_y(line), glyph_brush::Layout::default_single_line(), self.text_color.unwrap());
+                    renderer.draw(crate::render::DrawCommand::Text(&section));
+                }
+            }
+        }
+
+        // Caret
+        if self.has_focus {
+            let caret = self.carets[self.cursor];
+
+            if caret.line >= first_line && caret.line < last_line {
+                renderer.draw(crate::render::DrawCommand::Rect {
+                    top_left: (text_x + caret.x as i32, line_y(caret.line)),
+                    width: 2,
+                    height: self.line_height,
+                    color: self.text_color.unwrap(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{ApplicationEvent, ButtonState, Key, Modifiers, ScrollDelta};
+
+    fn send(area: &mut TextArea<(), ()>, event: ApplicationEvent) -> crate::EventResponse {
+        let state = crate::state::make_shared(crate::state::State::new(()));
+        let mut message_queue = crate::MessageQueue::new();
+
+        area.handle_event(&event, state.borrow_mut(), &mut message_queue)
+    }
+
+    fn key(key: Key) -> ApplicationEvent {
+        ApplicationEvent::Key { key, state: ButtonState::Pressed, modifiers: Modifiers::default() }
+    }
+
+    /// Text area which fits `columns` characters of the (monospace) default font
+    fn focused_area(text: &str, columns: u32) -> (TextArea<(), ()>, crate::render::Renderer) {
+        use wgpu_glyph::ab_glyph::{Font, ScaleFont};
+
+        let theme = &crate::style::DEFAULT_THEME;
+        let mut renderer = crate::render::headless::test_renderer();
+
+        let font = renderer.text_renderer.fonts()[0].as_scaled(theme.text.scale);
+        // With a pixel to spare for rounding
+        let text_width = (columns as f32 * font.h_advance(font.glyph_id('a'))).ceil() as u32 + 1;
+        let padding = 2 * 2 * theme.widget_styles.internal_padding.horizontal;
+
+        let mut area = TextArea::new("area").text(text).width(text_width + padding);
+        area.init(&mut renderer, theme);
+        area.on_focus();
+
+        (area, renderer)
+    }
+
+    /// Wrapping edited text must match a fresh layout
+    fn assert_layout_matches_init(area: &mut TextArea<(), ()>, renderer: &mut crate::render::Renderer) {
+        let (carets, line_starts) = (area.carets.clone(), area.line_starts.clone());
+        area.init(renderer, &crate::style::DEFAULT_THEME);
+
+        assert_eq!(carets, area.carets);
+        assert_eq!(line_starts, area.line_starts);
+    }
+
+    #[test]
+    fn text_wraps_at_the_width() {
+        let (mut area, mut renderer) = focused_area("abcd efgh", 9);
+        assert_eq!(area.line_starts, vec![0]);
+
+        send(&mut area, ApplicationEvent::Key { key: Key::End, state: ButtonState::Pressed, modifiers: Modifiers { ctrl: true, ..Modifiers::default() } });
+
+        // One more character does not fit -> the word wraps
+        send(&mut area, ApplicationEvent::Character('i'));
+        assert_eq!(area.line_starts, vec![0, 5]);
+        assert_eq!(area.carets[area.cursor].line, 1);
+        assert_layout_matches_init(&mut area, &mut renderer);
+        assert!(*area.should_resize());
+
+        // The number of lines is the same -> no relayout
+        *area.should_resize() = false;
+        send(&mut area, ApplicationEvent::Character('j'));
+        assert_eq!(area.get_text(), "abcd efghij");
+        assert_eq!(area.line_starts, vec![0, 5]);
+        assert_layout_matches_init(&mut area, &mut renderer);
+        assert!(!*area.should_resize());
+
+        // Removing the characters again unwraps the word
+        send(&mut area, key(Key::Back));
+        send(&mut area, key(Key::Back));
+        assert_eq!(area.line_starts, vec![0]);
+        assert!(*area.should_resize());
+    }
+
+    #[test]
+    fn caret_moves_up_and_down_across_wrapped_lines() {
+        // "abcd efgh " and "ijkl"
+        let (mut area, _) = focused_area("abcd efgh ijkl", 9);
+        assert_eq!(area.line_starts, vec![0, 10]);
+
+        for _ in 0..7 {
+            send(&mut area, key(Key::Right));
+        }
+
+        // The second line is shorter -> the caret is placed at its end
+        send(&mut area, key(Key::Down));
+        assert_eq!(area.cursor, 14);
+
+        // The original column is kept
+        send(&mut area, key(Key::Up));
+        assert_eq!(area.cursor, 7);
+
+        send(&mut area, key(Key::Home));
+        send(&mut area, key(Key::Right));
+        send(&mut area, key(Key::Right));
+        send(&mut area, key(Key::Down));
+        assert_eq!(area.cursor, 12);
+
+        // Past the first and last lines
+        send(&mut area, key(Key::Down));
+        assert_eq!(area.cursor, 14);
+        send(&mut area, key(Key::Up));
+        send(&mut area, key(Key::Up));
+        assert_eq!(area.cursor, 0);
+    }
+
+    fn wheel(area: &mut TextArea<(), ()>, lines: f32, position: (i32, i32)) -> crate::EventResponse {
+        let state = crate::state::make_shared(crate::state::State::new(()));
+        let mut message_queue = crate::MessageQueue::new();
+        let event = ApplicationEvent::MouseWheel { delta: ScrollDelta::Lines { x: 0.0, y: lines }, position };
+
+        area.handle_event(&event, state.borrow_mut(), &mut message_queue)
+    }
+
+    #[test]
+    fn mouse_wheel_scrolls_without_moving_the_caret() {
+        let mut renderer = crate::render::headless::test_renderer();
+        let mut area = TextArea::new("area").text("one\ntwo\nthree\nfour\nfive").rows(2);
+        area.init(&mut renderer, &crate::style::DEFAULT_THEME);
+
+        assert_eq!(area.first_visible_line, 0);
+        let cursor = area.cursor;
+
+        // Scrolling down stops at the last line
+        for _ in 0..20 {
+            wheel(&mut area, -3.0, (5, 5));
+        }
+        assert_eq!(area.first_visible_line, 3);
+        assert_eq!(area.cursor, cursor);
+
+        // The end was reached -> the event is left for enclosing views
+        assert!(matches!(wheel(&mut area, -3.0, (5, 5)), crate::EventResponse::None));
+
+        // Scroll up (towards the start) as far as possible
+        for _ in 0..20 {
+            wheel(&mut area, 3.0, (5, 5));
+        }
+        assert_eq!(area.first_visible_line, 0);
+        assert_eq!(area.cursor, cursor);
+
+        // Only scrolls while the mouse is over the area
+        assert!(matches!(wheel(&mut area, -3.0, (-5, 5)), crate::EventResponse::None));
+        assert_eq!(area.first_visible_line, 0);
+    }
+}
@@ -0,0 +1,833 @@
+use crate::state::State;
+use crate::bounding_rect::BoundingRect;
+use crate::view_element::*;
+
+use std::cell::RefMut;
+
+use super::Widget;
+
+/// Single-line editable text
+pub struct TextInput<Msg, S> {
+    id: &'static str,
+    bounds: BoundingRect,
+    text: String,
+    placeholder: String,
+    max_length: Option<usize>,
+    font: String,
+    scale: f32,
+    color: Option<crate::Color>,
+    text_color: Option<crate::Color>,
+    placeholder_color: Option<crate::Color>,
+    roundness: f32,
+
+    // fn(text, state)..
+    on_change: Option<Box<dyn FnMut(&str, RefMut<State<S>>) -> Msg>>,
+    on_submit: Option<Box<dyn FnMut(&str, RefMut<State<S>>) -> Msg>>,
+    message_handler: Option<Box<dyn FnMut(&mut TextInput<Msg, S>, &Msg, RefMut<State<S>>)>>,
+
+    /// Caret position (in characters)
+    cursor: usize,
+    /// Where the selection began (in characters). The selection spans from here to the cursor.
+    selection_anchor: Option<usize>,
+    has_focus: bool,
+    is_dragging: bool,
+
+    /// Horizontal offset of every caret position (in pixels, relative to the start of the text)
+    caret_offsets: Vec<f32>,
+    /// Measures edited text without laying it out again (set by `init`)
+    glyph_font: Option<wgpu_glyph::ab_glyph::FontArc>,
+    /// First visible character. Text scrolls horizontally to keep the caret in view.
+    first_visible: usize,
+    text_height: u32,
+    /// Width before being resized by a layout
+    width: u32,
+    font_id: Option<wgpu_glyph::FontId>,
+    padding: (u32, u32),
+
+    should_resize: bool,
+}
+
+impl<Msg, S> TextInput<Msg, S> {
+    pub fn new(id: &'static str) -> Self {
+        let mut bounds = BoundingRect::new();
+
+        // Height is determined by the text scale
+        let width = 300;
+        bounds.width = width;
+
+        Self {
+            id,
+            bounds,
+            text: String::new(),
+            placeholder: String::new(),
+            max_length: None,
+            font: String::from(""),
+            // Negative -> unset
+            scale: -1.0,
+            color: None,
+            text_color: None,
+            placeholder_color: None,
+            roundness: -1.0,
+            on_change: None,
+            on_submit: None,
+            message_handler: None,
+            cursor: 0,
+            selection_anchor: None,
+            has_focus: false,
+            is_dragging: false,
+            caret_offsets: vec![0.0],
+            glyph_font: None,
+            first_visible: 0,
+            text_height: 0,
+            width,
+            font_id: None,
+            padding: (0, 0),
+            should_resize: false,
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_owned();
+        self.cursor = self.text.chars().count();
+        self
+    }
+
+    /// Text shown when the input is empty
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_owned();
+        self
+    }
+
+    /// Maximum number of characters
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self.bounds.width = width;
+        self
+    }
+
+    pub fn scale(mut self, scale: f32) -> Self {
+        if scale < 0.0 {
+            panic!("Text scale cannot be negative (Tried setting `{}` to scale {})", self.id, scale);
+        }
+        self.scale = scale;
+        self
+    }
+
+    pub fn font(mut self, font: &str) -> Self {
+        self.font = font.to_owned();
+        self
+    }
+
+    /// Background color
+    pub fn color(mut self, color: crate::Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn text_color(mut self, color: crate::Color) -> Self {
+        self.text_color = Some(color);
+        self
+    }
+
+    pub fn placeholder_color(mut self, color: crate::Color) -> Self {
+        self.placeholder_color = Some(color);
+        self
+    }
+
+    pub fn roundness(mut self, roundness: f32) -> Self {
+        if roundness < 0.0 || roundness > 100.0 {
+            panic!("Roundness must be between 0 and 100 (percent). `{}` got `{}`", self.id, roundness);
+        }
+
+        self.roundness = roundness;
+        self
+    }
+
+    /// Called whenever the text is edited by the user
+    pub fn on_change<F: FnMut(&str, RefMut<State<S>>) -> Msg + 'static>(mut self, on_change: F) -> Self {
+        self.on_change = Some(Box::new(on_change));
+        self
+    }
+
+    /// Called when the user presses enter
+    pub fn on_submit<F: FnMut(&str, RefMut<State<S>>) -> Msg + 'static>(mut self, on_submit: F) -> Self {
+        self.on_submit = Some(Box::new(on_submit));
+        self
+    }
+
+    pub fn message_handler<F: FnMut(&mut TextInput<Msg, S>, &Msg, RefMut<State<S>>) + 'static>(mut self, handler: F) -> Self {
+        self.message_handler = Some(Box::new(handler));
+        self
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// The input keeps its size, so this does not cause a resize
+    pub fn set_text(&mut self, text: &str) {
+        let removed = self.char_count();
+        self.text = text.to_owned();
+        self.update_caret_offsets(0, removed, self.char_count());
+
+        self.cursor = self.char_count();
+        self.selection_anchor = None;
+        self.scroll_to_cursor();
+    }
+
+    fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Byte index of the character at `char_index`
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text.char_indices()
+            .nth(char_index)
+            .map(|(index, _)| index)
+            .unwrap_or(self.text.len())
+    }
+
+    /// Selected range of characters, if anything is selected
+    fn selection(&self) -> Option<(usize, usize)> {
+        match self.selection_anchor {
+            Some(anchor) if anchor != self.cursor => {
+                Some((std::cmp::min(anchor, self.cursor), std::cmp::max(anchor, self.cursor)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Replaces the characters in `start..end` with `text`, placing the caret after the new text
+    fn replace_chars(&mut self, start: usize, end: usize, text: &str) {
+        let (start_byte, end_byte) = (self.byte_index(start), self.byte_index(end));
+        self.text.replace_range(start_byte..end_byte, text);
+
+        let inserted = text.chars().count();
+        self.update_caret_offsets(start, end - start, inserted);
+        self.cursor = start + inserted;
+    }
+
+    /// Removes the selected text. Returns `false` if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        let selection = self.selection();
+        self.selection_anchor = None;
+
+        if let Some((start, end)) = selection {
+            self.replace_chars(start, end, "");
+            true
+        } else {
+            false
+        }
+    }
+
+    fn insert(&mut self, character: char) -> bool {
+        let had_selection = self.delete_selection();
+
+        if let Some(max_length) = self.max_length {
+            if self.char_count() >= max_length {
+                return had_selection;
+            }
+        }
+
+        self.replace_chars(self.cursor, self.cursor, character.encode_utf8(&mut [0; 4]));
+
+        true
+    }
+
+    /// Measures the caret offsets with a single layout of the text
+    fn layout_caret_offsets(&mut self, renderer: &mut crate::render::Renderer, font_id: wgpu_glyph::FontId) {
+        use wgpu_glyph::ab_glyph::{Font, ScaleFont};
+
+        let section = glyph_brush::OwnedSection::default()
+            .add_text(glyph_brush::OwnedText::new(&self.text)
+                .with_scale(self.scale)
+                .with_font_id(font_id)
+            );
+        let glyphs = renderer.text_renderer.section_glyphs(&section);
+
+        let font = renderer.text_renderer.fonts()[font_id.0].clone();
+        let scaled_font = font.as_scaled(self.scale);
+
+        let char_indices: std::collections::HashMap<usize, usize> = self.text.char_indices()
+            .enumerate()
+            .map(|(char_index, (byte_index, _))| (byte_index, char_index))
+            .collect();
+
+        // A caret is placed at the start of the following glyph (which includes kerning),
+        // or after the preceding glyph at the end of the text
+        let mut offsets = vec![None; self.char_count() + 1];
+        offsets[0] = Some(0.0);
+        for glyph in &glyphs {
+            let index = char_indices[&glyph.byte_index];
+            offsets[index] = Some(glyph.glyph.position.x);
+            offsets[index + 1] = Some(glyph.glyph.position.x + scaled_font.h_advance(glyph.glyph.id));
+        }
+
+        // Characters without glyphs take up no space
+        let mut previous = 0.0;
+        self.caret_offsets = offsets.into_iter()
+            .map(|offset| {
+                previous = offset.unwrap_or(previous);
+                previous
+            })
+            .collect();
+        self.glyph_font = Some(font);
+    }
+
+    /// Updates the caret offsets after the characters `start..start + removed` were replaced by `inserted` characters.
+    /// Only the new characters are measured; the text after them is moved.
+    fn update_caret_offsets(&mut self, start: usize, removed: usize, inserted: usize) {
+        use wgpu_glyph::ab_glyph::{Font, ScaleFont};
+
+        // Measured by `init`
+        let font = if let Some(font) = &self.glyph_font { font } else { return };
+        let scaled_font = font.as_scaled(self.scale);
+        let chars: Vec<char> = self.text.chars().collect();
+
+        // Distance from a character's caret to the next. Mirrors the layout of `glyph_brush`,
+        // which kerns a glyph with the following one, but not across words.
+        let advance = |index: usize| -> f32 {
+            let glyph = scaled_font.glyph_id(chars[index]);
+            let kerning = match chars.get(index + 1) {
+                Some(next) if !chars[index].is_whitespace() => scaled_font.kern(glyph, scaled_font.glyph_id(*next)),
+                _ => 0.0,
+            };
+
+            scaled_font.h_advance(glyph) + kerning
+        };
+
+        // The character before the edit may kern differently with its new neighbor
+        let mut offsets = self.caret_offsets[..start].to_vec();
+        if let Some(previous) = start.checked_sub(1) {
+            offsets.push(offsets[previous] + advance(previous));
+        } else {
+            offsets.push(0.0);
+        }
+
+        for index in start .. start + inserted {
+            offsets.push(offsets[index] + advance(index));
+        }
+
+        // The remaining characters keep their spacing
+        let remaining = start + inserted;
+        if remaining < chars.len() {
+            let moved = offsets[remaining] - self.caret_offsets[start + removed];
+            offsets.extend(self.caret_offsets[start + removed + 1 ..].iter().map(|offset| offset + moved));
+        }
+
+        self.caret_offsets = offsets;
+    }
+
+    /// Moves the caret, extending the selection if `select` is true
+    fn move_cursor(&mut self, position: usize, select: bool) {
+        if select {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some(self.cursor);
+            }
+        } else {
+            self.selection_anchor = None;
+        }
+
+        self.cursor = std::cmp::min(position, self.char_count());
+        self.scroll_to_cursor();
+    }
+
+    fn inner_width(&self) -> u32 {
+        self.bounds.width.saturating_sub(2 * self.padding.0)
+    }
+
+    /// Scrolls horizontally so that the caret is visible
+    fn scroll_to_cursor(&mut self) {
+        // Offsets are updated by `init` after the text changes
+        if self.caret_offsets.len() != self.char_count() + 1 {
+            return;
+        }
+
+        let inner_width = self.inner_width();
+        let cursor = std::cmp::min(self.cursor, self.caret_offsets.len() - 1);
+
+        if cursor < self.first_visible {
+            self.first_visible = cursor;
+        }
+
+        while self.first_visible < cursor && self.caret_offsets[cursor] - self.caret_offsets[self.first_visible] > inner_width as f32 {
+            self.first_visible += 1;
+        }
+    }
+
+    /// Nearest caret position to the x coordinate
+    fn cursor_from_point(&self, x: i32) -> usize {
+        let text_x = (x - self.bounds.x - self.padding.0 as i32) as f32 + self.caret_offsets[self.first_visible];
+
+        (0..self.caret_offsets.len())
+            .min_by_key(|index| (self.caret_offsets[*index] - text_x).abs().round() as u32)
+            .unwrap_or(0)
+    }
+
+    fn text_position(&self) -> (i32, i32) {
+        (self.bounds.x + self.padding.0 as i32, self.bounds.y + self.padding.1 as i32)
+    }
+
+    fn notify_change(&mut self, state: RefMut<State<S>>, message_queue: &mut crate::MessageQueue<Msg>) where Msg: EmptyMessage {
+        self.scroll_to_cursor();
+
+        if let Some(on_change) = &mut self.on_change {
+            message_queue.push((on_change)(&self.text, state));
+        }
+    }
+
+    fn handle_key(&mut self, key: crate::event::Key, modifiers: crate::event::Modifiers, state: RefMut<State<S>>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse where Msg: EmptyMessage {
+        use crate::event::Key;
+
+        match key {
+            Key::Back => {
+                if !self.delete_selection() {
+                    if self.cursor == 0 {
+                        return crate::EventResponse::Consume;
+                    }
+                    self.replace_chars(self.cursor - 1, self.cursor, "");
+                }
+                self.notify_change(state, message_queue);
+            }
+
+            Key::Delete => {
+                if !self.delete_selection() {
+                    if self.cursor == self.char_count() {
+                        return crate::EventResponse::Consume;
+                    }
+                    self.replace_chars(self.cursor, self.cursor + 1, "");
+                }
+                self.notify_change(state, message_queue);
+            }
+
+            Key::Left => {
+                match self.selection() {
+                    Some((start, _)) if !modifiers.shift => self.move_cursor(start, false),
+                    _ => self.move_cursor(self.cursor.saturating_sub(1), modifiers.shift),
+                }
+            }
+
+            Key::Right => {
+                match self.selection() {
+                    Some((_, end)) if !modifiers.shift => self.move_cursor(end, false),
+                    _ => self.move_cursor(self.cursor + 1, modifiers.shift),
+                }
+            }
+
+            Key::Home => {
+                self.move_cursor(0, modifiers.shift);
+            }
+
+            Key::End => {
+                self.move_cursor(self.char_count(), modifiers.shift);
+            }
+
+            // Select all
+            Key::A if modifiers.ctrl || modifiers.logo => {
+                self.selection_anchor = Some(0);
+                self.cursor = self.char_count();
+                self.scroll_to_cursor();
+            }
+
+            Key::Return | Key::NumpadEnter => {
+                if let Some(on_submit) = &mut self.on_submit {
+                    message_queue.push((on_submit)(&self.text, state));
+                }
+            }
+
+            // Not handled here (see `ApplicationEvent::Character`)
+            _ => return crate::EventResponse::None,
+        }
+
+        crate::EventResponse::Redraw
+    }
+}
+
+impl<Msg: EmptyMessage, S> Widget<Msg, S> for TextInput<Msg, S> where Msg: 'static, S: 'static {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn should_resize(&mut self) -> &mut bool {
+        &mut self.should_resize
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn on_focus(&mut self) {
+        self.has_focus = true;
+    }
+
+    fn on_blur(&mut self) {
+        self.has_focus = false;
+        self.is_dragging = false;
+        self.selection_anchor = None;
+    }
+
+    fn contains_point(&self, x: i32, y: i32) -> bool {
+        self.bounds.contains(x, y)
+    }
+
+    fn focus_bounds(&self) -> Option<&BoundingRect> {
+        Some(&self.bounds)
+    }
+
+    fn init(&mut self, renderer: &mut crate::render::Renderer, theme: &crate::style::Theme) {
+        if self.scale < 0.0 {
+            self.scale = theme.text.scale;
+        }
+
+        if self.roundness < 0.0 {
+            self.roundness = theme.widget_styles.buttons.roundness / 4.0;
+        }
+
+        if self.color.is_none() {
+            self.color = Some(theme.colors.secondary);
+        }
+
+        if self.text_color.is_none() {
+            self.text_color = Some(theme.colors.text);
+        }
+
+        if self.placeholder_color.is_none() {
+            let color = theme.colors.text;
+            self.placeholder_color = Some(crate::Color::new(color.r, color.g, color.b, color.a * 0.5));
+        }
+
+        let font_id = renderer.text_renderer.get_font_id(&self.font);
+        self.font_id = Some(font_id);
+
+        // Whitespace is measured too, so any character gives the line height
+        self.text_height = renderer.text_renderer.get_section_bounds(&wgpu_glyph::Section {
+            text: vec![
+                wgpu_glyph::Text::new(" ")
+                    .with_scale(self.scale)
+                    .with_font_id(font_id),
+            ],
+            ..wgpu_glyph::Section::default()
+        }).1;
+
+        self.layout_caret_offsets(renderer, font_id);
+
+        let padding = &theme.widget_styles.internal_padding;
+        self.padding = (padding.horizontal * 2, padding.vertical);
+        self.bounds.height = self.text_height + 2 * self.padding.1;
+
+        self.cursor = std::cmp::min(self.cursor, self.char_count());
+        self.first_visible = std::cmp::min(self.first_visible, self.cursor);
+        self.scroll_to_cursor();
+    }
+
+    fn handle_event(&mut self, event: &crate::event::ApplicationEvent, state: RefMut<State<S>>, message_queue: &mut crate::MessageQueue<Msg>) -> crate::EventResponse {
+        use crate::event::*;
+
+        match event {
+            ApplicationEvent::MouseButton { state: ButtonState::Pressed, button: MouseButton::Left, position: (x, y) } => {
+                // Focus is given by the application before the click is handled
+                if self.has_focus && self.bounds.contains(*x, *y) {
+                    self.is_dragging = true;
+                    self.move_cursor(self.cursor_from_point(*x), false);
+                    return crate::EventResponse::Consume;
+                }
+            }
+
+            ApplicationEvent::MouseButton { state: ButtonState::Released, button: MouseButton::Left, .. } => {
+                self.is_dragging = false;
+            }
+
+            // Drag to select
+            ApplicationEvent::MouseMotion { position: (x, _), .. } => {
+                if self.is_dragging {
+                    let cursor = self.cursor_from_point(*x);
+                    if cursor != self.cursor {
+                        self.move_cursor(cursor, true);
+                        return crate::EventResponse::Redraw;
+                    }
+                }
+            }
+
+            ApplicationEvent::Character(character) => {
+                if self.has_focus && !character.is_control() {
+                    if self.insert(*character) {
+                        self.notify_change(state, message_queue);
+                    }
+                    return crate::EventResponse::Redraw;
+                }
+            }
+
+            ApplicationEvent::Key { key, state: ButtonState::Pressed, modifiers } => {
+                if self.has_focus {
+                    return self.handle_key(*key, *modifiers, state, message_queue);
+                }
+            }
+
+            _ => {}
+        }
+
+        crate::EventResponse::None
+    }
+
+    fn handle_message(&mut self, message: &Msg, state: RefMut<State<S>>) {
+        // NOTE: See `Text::handle_message` for description of this implementation
+        let this = unsafe {
+            (self as *mut TextInput<Msg, S>).as_mut().unwrap()
+        };
+
+        if let Some(handler) = &mut self.message_handler {
+            (handler)(this, message, state);
+        }
+    }
+
+    fn place(&mut self, x: i32, y: i32) {
+        self.bounds.x = x;
+        self.bounds.y = y;
+    }
+
+    fn translate(&mut self, dx: i32, dy: i32) {
+        self.bounds.x += dx;
+        self.bounds.y += dy;
+    }
+
+    fn render_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        self.bounds.dimensions()
+    }
+
+    fn natural_size(&self, _theme: &crate::style::Theme) -> (u32, u32) {
+        (self.width, self.bounds.height)
+    }
+
+    /// Only the width can change (the height is determined by the text scale)
+    fn resize(&mut self, width: u32, _height: u32) {
+        self.bounds.width = width;
+        self.scroll_to_cursor();
+    }
+
+    fn render(&self, renderer: &mut dyn crate::render::RenderTarget, theme: &crate::style::Theme) {
+        if self.has_focus {
+            super::render_focus_ring(renderer, theme, &self.bounds, self.roundness);
+        }
+
+        renderer.draw(crate::render::DrawCommand::RoundedRect {
+            top_left: self.bounds.top_left(),
+            width: self.bounds.width,
+            height: self.bounds.height,
+            roundness_percent: self.roundness,
+            color: self.color.unwrap(),
+        });
+
+        let font_id = if let Some(font_id) = self.font_id { font_id } else { return };
+        let (text_x, text_y) = self.text_position();
+
+        let (text, color) = if self.text.is_empty() {
+            (self.placeholder.as_str(), self.placeholder_color.unwrap())
+        } else {
+            (self.text.as_str(), self.text_color.unwrap())
+        };
+
+        // Text scrolled out of view is clipped (leaving room for the caret at the edges)
+        renderer.push_clip(crate::render::ClipRect::new(
+            (self.bounds.x + self.padding.0 as i32 / 2, self.bounds.y),
+            self.bounds.width.saturating_sub(self.padding.0),
+            self.bounds.height,
+        ));
+
+        // Start of the text (scrolled to show the first visible character)
+        let start_x = text_x as f32 - self.caret_offsets[self.first_visible];
+
+        // Selection highlight
+        if let (Some((start, end)), true) = (self.selection(), self.has_focus) {
+            renderer.draw(crate::render::DrawCommand::Rect {
+                top_left: ((start_x + self.caret_offsets[start]).round() as i32, text_y),
+                width: (self.caret_offsets[end] - self.caret_offsets[start]).max(0.0).round() as u32,
+                height: self.text_height,
+                color: theme.colors.accent,
+            });
+        }
+
+        if !text.is_empty() {
+            let section = wgpu_glyph::Section {
+                screen_position: (start_x, text_y as f32),
+                text: vec![
+                    wgpu_glyph::Text::new(text)
+                        .with_scale(self.scale)
+                        .with_color(color.as_array())
+                        .with_font_id(font_id),
+                ],
+                ..wgpu_glyph::Section::default()
+            };
+
+            renderer.draw(crate::render::DrawCommand::Text(&section.to_owned()));
+        }
+
+        // Caret
+        if self.has_focus {
+            renderer.draw(crate::render::DrawCommand::Rect {
+                top_left: ((start_x + self.caret_offsets[self.cursor]).round() as i32, text_y),
+                width: 2,
+                height: self.text_height,
+                color: self.text_color.unwrap(),
+            });
+        }
+
+        renderer.pop_clip();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{ApplicationEvent, ButtonState, Key, Modifiers};
+
+    fn focused_input(text: &str) -> (TextInput<(), ()>, crate::render::Renderer) {
+        focused(TextInput::new("input").text(text))
+    }
+
+    fn focused(input: TextInput<(), ()>) -> (TextInput<(), ()>, crate::render::Renderer) {
+        focused_with(input, crate::render::headless::test_renderer())
+    }
+
+    fn focused_with(mut input: TextInput<(), ()>, mut renderer: crate::render::Renderer) -> (TextInput<(), ()>, crate::render::Renderer) {
+        input.init(&mut renderer, &crate::style::DEFAULT_THEME);
+        input.on_focus();
+
+        (input, renderer)
+    }
+
+    /// Test renderer with the font `proportional`, which is proportional and kerned (unlike the default font).
+    /// Only these tests need the font, so it isn't part of `test_resources`.
+    fn proportional_renderer() -> crate::render::Renderer {
+        let (mut fonts, images) = crate::render::headless::test_resources();
+        let font = wgpu_glyph::ab_glyph::FontArc::try_from_slice(include_bytes!("../../res/DejaVuSans/DejaVuSans.ttf")).unwrap();
+        fonts.push(("proportional", font));
+
+        crate::render::Renderer::headless(fonts, images)
+    }
+
+    fn send(input: &mut TextInput<(), ()>, event: ApplicationEvent) {
+        let state = crate::state::make_shared(crate::state::State::new(()));
+        let mut message_queue = crate::MessageQueue::new();
+
+        input.handle_event(&event, state.borrow_mut(), &mut message_queue);
+    }
+
+    fn key(key: Key, modifiers: Modifiers) -> ApplicationEvent {
+        ApplicationEvent::Key { key, state: ButtonState::Pressed, modifiers }
+    }
+
+    /// Offsets updated by edits must match a fresh layout of the text
+    fn assert_offsets_match_layout(input: &mut TextInput<(), ()>, renderer: &mut crate::render::Renderer) {
+        let edited = input.caret_offsets.clone();
+        input.layout_caret_offsets(renderer, input.font_id.unwrap());
+
+        assert_eq!(edited.len(), input.text.chars().count() + 1);
+        for (edited, measured) in edited.iter().zip(&input.caret_offsets) {
+            assert!((edited - measured).abs() < 0.01, "{:?} != {:?}", edited, input.caret_offsets);
+        }
+    }
+
+    #[test]
+    fn caret_offsets_follow_the_glyphs() {
+        let (input, _) = focused_input("Hi there");
+
+        assert_eq!(input.caret_offsets.len(), 9);
+        assert_eq!(input.caret_offsets[0], 0.0);
+        assert!(input.caret_offsets.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn edits_update_caret_offsets_without_resizing() {
+        let (mut input, mut renderer) = focused_input("Hello");
+
+        for character in " wörld 日本".chars() {
+            send(&mut input, ApplicationEvent::Character(character));
+        }
+        assert_eq!(input.get_text(), "Hello wörld 日本");
+        assert_offsets_match_layout(&mut input, &mut renderer);
+
+        send(&mut input, key(Key::Home, Modifiers::default()));
+        send(&mut input, key(Key::Delete, Modifiers::default()));
+        send(&mut input, key(Key::End, Modifiers::default()));
+        send(&mut input, key(Key::Back, Modifiers::default()));
+        assert_eq!(input.get_text(), "ello wörld 日");
+        assert_offsets_match_layout(&mut input, &mut renderer);
+
+        // Replace a selection
+        send(&mut input, key(Key::Left, Modifiers { shift: true, ..Modifiers::default() }));
+        send(&mut input, key(Key::Left, Modifiers { shift: true, ..Modifiers::default() }));
+        send(&mut input, ApplicationEvent::Character('!'));
+        assert_eq!(input.get_text(), "ello wörld!");
+        assert_offsets_match_layout(&mut input, &mut renderer);
+
+        input.set_text("Replaced");
+        assert_offsets_match_layout(&mut input, &mut renderer);
+
+        // The size of the input does not depend on its text
+        assert!(!*input.should_resize());
+    }
+
+    #[test]
+    fn edits_with_a_kerned_font_match_the_layout() {
+        use wgpu_glyph::ab_glyph::{Font, ScaleFont};
+
+        let (mut input, mut renderer) = focused_with(TextInput::new("input").font("proportional"), proportional_renderer());
+
+        for character in "AVATAR WAVE".chars() {
+            send(&mut input, ApplicationEvent::Character(character));
+        }
+        assert_offsets_match_layout(&mut input, &mut renderer);
+
+        // The font kerns "AV", so the offsets are not just the sum of the advances
+        let font = input.glyph_font.clone().unwrap();
+        let scaled_font = font.as_scaled(input.scale);
+        let advances = scaled_font.h_advance(scaled_font.glyph_id('A')) + scaled_font.h_advance(scaled_font.glyph_id('V'));
+        assert!(input.caret_offsets[2] < advances - 0.5);
+
+        let (width, _) = renderer.text_renderer.get_section_bounds(&wgpu_glyph::Section {
+            text: vec![
+                wgpu_glyph::Text::new(input.get_text())
+                    .with_scale(input.scale)
+                    .with_font_id(input.font_id.unwrap()),
+            ],
+            ..wgpu_glyph::Section::default()
+        });
+        assert!((input.caret_offsets.last().unwrap() - width as f32).abs() <= 1.0);
+
+        // The "A" after the removed "T" is measured with its new neighbor
+        send(&mut input, key(Key::Home, Modifiers::default()));
+        for _ in 0..4 {
+            send(&mut input, key(Key::Right, Modifiers::default()));
+        }
+        send(&mut input, key(Key::Back, Modifiers::default()));
+        assert_eq!(input.get_text(), "AVAAR WAVE");
+        assert_offsets_match_layout(&mut input, &mut renderer);
+
+        // Inserting between a kerned pair
+        send(&mut input, key(Key::Home, Modifiers::default()));
+        send(&mut input, key(Key::Right, Modifiers::default()));
+        send(&mut input, ApplicationEvent::Character('x'));
+        assert_eq!(input.get_text(), "AxVAAR WAVE");
+        assert_offsets_match_layout(&mut input, &mut renderer);
+    }
+
+    #[test]
+    fn edited_text_is_drawn_immediately() {
+        let (mut input, _) = focused_input("");
+        send(&mut input, ApplicationEvent::Character('a'));
+
+        let mut recorder = crate::render::headless::CommandRecorder::new();
+        input.render(&mut recorder, &crate::style::DEFAULT_THEME);
+
+        let has_text = recorder.commands().iter().any(|command| match command {
+            crate::render::headless::RecordedCommand::Text(section) => section.text[0].text == "a",
+            _ => false,
+        });
+        assert!(has_text);
+    }
+}
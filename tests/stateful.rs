@@ -0,0 +1,67 @@
+//! `Stateful!` views which must compile (see `surreal::state::State` for the ones which must not)
+
+use surreal::prelude::*;
+
+#[test]
+fn fields_within_nested_closures() {
+    let _view: Stack<(), _> = Stateful! {
+        @State {
+            count: u32 = 0,
+            todos: Vec<String> = Vec::new(),
+        },
+        VStack! {
+            Button::new("add").on_click(|mut state| {
+                @count = (0..2).map(|n| n + @count).sum();
+                (0..2).for_each(|_| @count += 1);
+                @todos.retain(|todo| !todo.is_empty());
+                if let small @ 1..=5 = @count {
+                    @count = small * 2;
+                }
+            }),
+            Button::new("typed").on_click(move |mut state: std::cell::RefMut<state::State<_>>| {
+                @count = 0;
+            }),
+            HStack! {
+                ForEach::new(|state| &@todos, |todo: &String| todo.clone(), |todo| {
+                    Text::new("").text(todo)
+                }),
+            },
+        }
+    };
+}
+
+#[test]
+fn fields_within_state_conditions() {
+    let _view: Stack<(), _> = Stateful! {
+        @State {
+            count: u32 = 0,
+            shown: bool = true,
+        },
+        VStack! {
+            if |state| @shown && @count > 2 {
+                Text::new("many"),
+            } else {
+                Text::new("few"),
+            },
+            HStack! {
+                if |state| @count == 0 {
+                    Text::new("none"),
+                },
+            },
+        }
+    };
+}
+
+#[test]
+fn bitwise_or_is_not_a_closure() {
+    let _view: Stack<(), _> = Stateful! {
+        @State { count: u32 = 0, },
+        VStack! {
+            Text::new("bits").text(&{
+                let state = 1u32;
+                format!("{}", 2 | state | 4)
+            }),
+            Button::new("add").on_click(|mut state| @count += 1),
+        }
+    };
+}